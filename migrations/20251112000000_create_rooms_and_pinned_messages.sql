@@ -0,0 +1,37 @@
+CREATE TABLE IF NOT EXISTS rooms (
+    name VARCHAR(255) PRIMARY KEY,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    topic TEXT,
+    topic_set_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    topic_set_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO rooms (name)
+SELECT DISTINCT room_name FROM room_members
+ON CONFLICT (name) DO NOTHING;
+
+ALTER TABLE room_members
+    ADD COLUMN IF NOT EXISTS role VARCHAR(20) NOT NULL DEFAULT 'member'
+    CHECK (role IN ('owner', 'moderator', 'member'));
+
+-- The earliest member of each existing room becomes its owner. Nobody owns the
+-- default room, so nobody can lock others out of it
+UPDATE room_members rm
+SET role = 'owner'
+FROM (
+    SELECT DISTINCT ON (room_name) room_name, user_id
+    FROM room_members
+    ORDER BY room_name, joined_at
+) first_members
+WHERE rm.room_name = first_members.room_name
+  AND rm.user_id = first_members.user_id
+  AND rm.room_name <> 'general';
+
+CREATE TABLE IF NOT EXISTS pinned_messages (
+    room_name VARCHAR(255) NOT NULL,
+    message_id UUID NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+    pinned_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    pinned_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (room_name, message_id)
+);
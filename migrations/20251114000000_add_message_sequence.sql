@@ -0,0 +1,15 @@
+-- Per-room sequence numbers let reconnecting clients resume from a cursor
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS seq BIGINT;
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS is_action BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE messages m
+SET seq = numbered.seq
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY room_name ORDER BY created_at, id) AS seq
+    FROM messages
+) numbered
+WHERE m.id = numbered.id AND m.seq IS NULL;
+
+ALTER TABLE messages ALTER COLUMN seq SET NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_messages_room_seq ON messages (room_name, seq);
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS incoming_webhooks (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    room_name VARCHAR(255) NOT NULL,
+    -- SHA-256 of the token; the token itself is only shown when the webhook is created
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    display_name VARCHAR(64) NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    revoked_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_incoming_webhooks_room_name ON incoming_webhooks (room_name);
+
+-- Messages posted through an incoming webhook have no user
+ALTER TABLE messages ALTER COLUMN user_id DROP NOT NULL;
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS incoming_webhook_id UUID REFERENCES incoming_webhooks(id) ON DELETE SET NULL;
@@ -0,0 +1,30 @@
+CREATE TABLE IF NOT EXISTS moderation_rules (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    room_name VARCHAR(255) NOT NULL,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('word', 'regex', 'link_domain')),
+    pattern TEXT NOT NULL,
+    action VARCHAR(20) NOT NULL CHECK (action IN ('mask', 'reject', 'flag')),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_moderation_rules_room_name ON moderation_rules (room_name, created_at);
+
+CREATE TABLE IF NOT EXISTS moderation_queue (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    room_name VARCHAR(255) NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    username VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    attachment_id UUID REFERENCES attachments(id) ON DELETE SET NULL,
+    is_action BOOLEAN NOT NULL DEFAULT FALSE,
+    reasons TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'approved', 'rejected')),
+    reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMP WITH TIME ZONE,
+    message_id UUID REFERENCES messages(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_moderation_queue_pending ON moderation_queue (room_name, created_at) WHERE status = 'pending';
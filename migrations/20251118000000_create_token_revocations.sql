@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS token_revocations (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    -- Tokens issued at or before this time are no longer accepted
+    revoked_before TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,10 @@
+-- Per-room retention; NULL falls back to the server default and 0 keeps messages forever
+ALTER TABLE rooms ADD COLUMN IF NOT EXISTS retention_days INTEGER CHECK (retention_days >= 0);
+-- Rooms under legal hold are exempt from purging
+ALTER TABLE rooms ADD COLUMN IF NOT EXISTS legal_hold BOOLEAN NOT NULL DEFAULT FALSE;
+-- Highest sequence number purged from a room, so numbering continues after a room is emptied
+ALTER TABLE rooms ADD COLUMN IF NOT EXISTS last_purged_seq BIGINT NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_messages_created_at ON messages (created_at);
+CREATE INDEX IF NOT EXISTS idx_messages_attachment_id ON messages (attachment_id) WHERE attachment_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_moderation_queue_attachment_id ON moderation_queue (attachment_id) WHERE attachment_id IS NOT NULL;
@@ -0,0 +1,27 @@
+CREATE TABLE IF NOT EXISTS polls (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    room_name VARCHAR(255) NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    question TEXT NOT NULL,
+    options TEXT[] NOT NULL,
+    multiple_choice BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Votes of anonymous polls are still recorded per user, so each member votes once,
+    -- but voters are never shown
+    anonymous BOOLEAN NOT NULL DEFAULT FALSE,
+    closes_at TIMESTAMP WITH TIME ZONE,
+    closed_at TIMESTAMP WITH TIME ZONE,
+    -- Chat message posted with the final results
+    results_message_id UUID REFERENCES messages(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_polls_room_name ON polls (room_name, created_at);
+CREATE INDEX IF NOT EXISTS idx_polls_open_closes_at ON polls (closes_at) WHERE closed_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS poll_votes (
+    poll_id UUID NOT NULL REFERENCES polls(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    option_index INTEGER NOT NULL,
+    voted_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (poll_id, user_id, option_index)
+);
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS scheduled_messages (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    room_name VARCHAR(255) NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    attachment_id UUID REFERENCES attachments(id) ON DELETE SET NULL,
+    is_action BOOLEAN NOT NULL DEFAULT FALSE,
+    send_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- 'sending' while the scheduler posts the message; 'held' when moderation queued it
+    -- for review instead
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'sending', 'sent', 'held', 'failed')),
+    error TEXT,
+    message_id UUID REFERENCES messages(id) ON DELETE SET NULL,
+    sent_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_scheduled_messages_due ON scheduled_messages (send_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_scheduled_messages_user_id ON scheduled_messages (user_id, send_at);
@@ -0,0 +1,19 @@
+-- Private rooms admit only their members; others join through an invite.
+-- The default room is always public
+ALTER TABLE rooms ADD COLUMN IF NOT EXISTS private BOOLEAN NOT NULL DEFAULT FALSE
+    CHECK (NOT private OR name <> 'general');
+
+CREATE TABLE IF NOT EXISTS room_invites (
+    code VARCHAR(64) PRIMARY KEY,
+    room_name VARCHAR(255) NOT NULL REFERENCES rooms(name) ON DELETE CASCADE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    -- Role given to users joining through the invite
+    role VARCHAR(20) NOT NULL DEFAULT 'member' CHECK (role IN ('moderator', 'member')),
+    max_uses INTEGER CHECK (max_uses > 0),
+    uses INTEGER NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_room_invites_room_name ON room_invites (room_name, created_at);
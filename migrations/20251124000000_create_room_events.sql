@@ -0,0 +1,12 @@
+-- Room events other than chat messages (joins, leaves, topic, pin and poll updates) share
+-- the room's sequence with its messages, so reconnecting clients can replay them too
+CREATE TABLE IF NOT EXISTS room_events (
+    room_name VARCHAR(255) NOT NULL,
+    seq BIGINT NOT NULL,
+    -- The frame as it was broadcast, replayed verbatim
+    frame TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (room_name, seq)
+);
+
+CREATE INDEX IF NOT EXISTS idx_room_events_created_at ON room_events (created_at);
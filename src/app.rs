@@ -0,0 +1,77 @@
+use axum::{middleware, Router};
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::config::env::{AppConfig, ChatConfig};
+use crate::config::environment::Environment;
+use crate::modules::auth::utils::revocation::reject_revoked_tokens;
+use crate::modules::chat::plugins::PluginRegistry;
+use crate::modules::chat::server::ChatState;
+use crate::modules::chat::service::{PollService, RetentionService, ScheduledMessageService, WebhookService};
+use crate::routes::auth_routes::ApiDoc;
+use crate::routes::{announcement_routes, attachment_routes, auth_routes, block_routes, chat_routes, file_routes, incoming_webhook_routes, invite_routes, moderation_routes, notification_routes, poll_routes, room_routes, scheduled_message_routes, search_routes, webhook_routes};
+use crate::utils::origin::{cors_layer, enforce_origin, OriginPolicy};
+
+/// Create the chat state backed by the database, with the configured plugins running
+pub fn chat_state(pool: Pool<Postgres>, config: &ChatConfig, app_config: &AppConfig) -> ChatState {
+    let chat_state = ChatState::with_pool(pool)
+        .with_plugins(PluginRegistry::with_builtin_plugins(&config.plugins))
+        .with_admins(config.admin_users.clone())
+        .with_origin_policy(Arc::new(OriginPolicy::from_config(app_config)));
+    chat_state.plugins.start_timers(&chat_state);
+    chat_state
+}
+
+/// Start the jobs that deliver webhooks, close polls, post scheduled messages, purge
+/// expired messages and close connections whose tokens were revoked
+pub fn spawn_background_jobs(pool: Pool<Postgres>, chat_state: &ChatState) {
+    let env = Environment::from_env();
+
+    // Deliver queued outgoing webhook events in the background
+    tokio::spawn(WebhookService::new(pool.clone()).run_delivery_worker(chat_state.webhook_notify.clone()));
+
+    // Close polls at their deadline, including those that passed while the server was down
+    tokio::spawn(PollService::new(pool.clone()).run_close_job(chat_state.clone(), chat_state.poll_notify.clone()));
+
+    // Post scheduled messages when due, including those that came due while the server was down
+    tokio::spawn(
+        ScheduledMessageService::new(pool.clone()).run_scheduler(chat_state.clone(), chat_state.schedule_notify.clone()),
+    );
+
+    // Close sockets whose tokens were revoked, also when another process revoked them
+    let revocation_check = std::time::Duration::from_secs(env.chat.revocation_check_secs);
+    tokio::spawn(chat_state.clone().run_revocation_watch(revocation_check));
+
+    // Purge messages past their room's retention period in the background
+    tokio::spawn(RetentionService::new(pool, env).run_purge_job());
+}
+
+/// The complete application: every API route, the chat socket and the API docs
+pub fn router(pool: Pool<Postgres>, chat_state: ChatState) -> Router {
+    // Browser origins allowed to call the API, the same ones allowed to open chat sockets
+    let origin_policy = chat_state.origin_policy.clone();
+
+    Router::new()
+        .merge(auth_routes())
+        .merge(chat_routes(chat_state.clone()))
+        .merge(room_routes(chat_state.clone()))
+        .merge(webhook_routes(chat_state.clone()))
+        .merge(incoming_webhook_routes(chat_state.clone()))
+        .merge(moderation_routes(chat_state.clone()))
+        .merge(poll_routes(chat_state.clone()))
+        .merge(scheduled_message_routes(chat_state.clone()))
+        .merge(invite_routes(chat_state.clone()))
+        .merge(block_routes(chat_state.clone()))
+        .merge(announcement_routes(chat_state))
+        .merge(file_routes())
+        .merge(attachment_routes())
+        .merge(search_routes())
+        .merge(notification_routes())
+        .merge(SwaggerUi::new("/swagger-ui/").url("/api-docs/openapi.json", ApiDoc::openapi()))
+        .with_state(pool.clone())
+        .layer(middleware::from_fn_with_state(pool, reject_revoked_tokens))
+        .layer(middleware::from_fn_with_state(origin_policy.clone(), enforce_origin))
+        .layer(cors_layer(origin_policy))
+}
@@ -0,0 +1,309 @@
+//! Load generator for the chat server
+//!
+//! Connects simulated users spread over a number of rooms, has them send messages at a
+//! fixed total rate and measures how long each message takes to reach every member of
+//! its room. Without `--server` it starts an in-memory chat server in this process, so
+//! it needs no database or other services.
+
+use std::collections::HashMap;
+use std::env;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+use futures::{stream, StreamExt};
+use uuid::Uuid;
+
+use rust_axum_project::config::env::AuthConfig;
+use rust_axum_project::modules::auth::utils::jwt::JwtUtil;
+use rust_axum_project::modules::chat::client::{ChatClient, ChatEvent};
+use rust_axum_project::modules::chat::loadgen::{ClientStats, LoadReport};
+use rust_axum_project::modules::chat::server::{websocket_handler, ChatState};
+use rust_axum_project::routes::chat_routes::issue_chat_ticket;
+
+/// Prefix of generated messages: "lg <client> <seq> <sent at, in microseconds since start>"
+const PAYLOAD_PREFIX: &str = "lg ";
+
+const USAGE: &str = "Usage: chat_loadgen [options]
+
+Options:
+  --server <url>         Chat server to load; without it an in-memory server is started in-process
+  --clients <n>          Simulated users (default 100)
+  --rooms <n>            Rooms the users are spread over (default 10)
+  --rate <n>             Messages per second sent by all users together (default 100)
+  --duration <secs>      How long to send (default 10)
+  --drain <secs>         How long to wait for deliveries after sending stops (default 2)
+  --concurrency <n>      Connections opened at a time (default 50)
+
+Tokens are signed with AUTH_JWT_SECRET, so an external server must share it and must not
+need the users to exist in its database.";
+
+struct Options {
+    server: Option<String>,
+    clients: usize,
+    rooms: usize,
+    rate: f64,
+    duration: Duration,
+    drain: Duration,
+    concurrency: usize,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        server: None,
+        clients: 100,
+        rooms: 10,
+        rate: 100.0,
+        duration: Duration::from_secs(10),
+        drain: Duration::from_secs(2),
+        concurrency: 50,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        if flag == "--help" || flag == "-h" {
+            return Err(String::new());
+        }
+        let value = args.next().ok_or_else(|| format!("{} needs a value", flag))?;
+        let invalid = || format!("Invalid value for {}: {}", flag, value);
+        let secs = || {
+            value
+                .parse::<f64>()
+                .ok()
+                .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                .ok_or_else(invalid)
+        };
+        match flag.as_str() {
+            "--server" => options.server = Some(value.clone()),
+            "--clients" => options.clients = value.parse().map_err(|_| invalid())?,
+            "--rooms" => options.rooms = value.parse().map_err(|_| invalid())?,
+            "--rate" => options.rate = value.parse().map_err(|_| invalid())?,
+            "--duration" => options.duration = secs()?,
+            "--drain" => options.drain = secs()?,
+            "--concurrency" => options.concurrency = value.parse().map_err(|_| invalid())?,
+            _ => return Err(format!("Unknown option {}", flag)),
+        }
+    }
+
+    if options.clients == 0 || options.rooms == 0 || options.concurrency == 0 {
+        return Err("--clients, --rooms and --concurrency must be at least 1".to_string());
+    }
+    if !(options.rate.is_finite() && options.rate > 0.0) {
+        return Err("--rate must be positive".to_string());
+    }
+    options.rooms = options.rooms.min(options.clients);
+    Ok(options)
+}
+
+/// Start an in-memory chat server on an ephemeral port
+fn start_local_server() -> Result<SocketAddr, Box<dyn std::error::Error>> {
+    let app = Router::new()
+        .route("/ws", get(websocket_handler))
+        .route("/api/chat/ticket", post(issue_chat_ticket))
+        .with_state(ChatState::new());
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let server = axum::Server::from_tcp(listener)?.serve(app.into_make_service());
+    tokio::spawn(async move {
+        if let Err(e) = server.await {
+            eprintln!("Chat server failed: {}", e);
+        }
+    });
+    Ok(addr)
+}
+
+/// When one simulated user sends, shared by all of them
+#[derive(Clone, Copy)]
+struct Schedule {
+    /// Reference point of the timestamps in generated messages
+    clock: Instant,
+    send_from: Instant,
+    send_until: Instant,
+    drain_until: Instant,
+    /// Time between two messages of one user
+    period: Duration,
+}
+
+async fn run_client(id: usize, clients: usize, mut client: ChatClient, schedule: Schedule) -> ClientStats {
+    let mut stats = ClientStats::default();
+    let sender = client.sender();
+    let mut last_seq: HashMap<usize, u64> = HashMap::new();
+
+    // Spread the first messages evenly over one period so users don't send in bursts
+    let offset = schedule.period.mul_f64(id as f64 / clients as f64);
+    let mut ticker = tokio::time::interval_at((schedule.send_from + offset).into(), schedule.period);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let drain = tokio::time::sleep_until(schedule.drain_until.into());
+    tokio::pin!(drain);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick(), if Instant::now() < schedule.send_until => {
+                let sent_at = schedule.clock.elapsed().as_micros();
+                let payload = format!("{}{} {} {}", PAYLOAD_PREFIX, id, stats.sent + 1, sent_at);
+                if sender.send_message(payload).is_ok() {
+                    stats.sent += 1;
+                }
+            }
+            event = client.next() => match event {
+                Some(ChatEvent::Message(message)) => {
+                    let received_at = schedule.clock.elapsed().as_micros() as u64;
+                    let Some((from, seq, sent_at)) = parse_payload(&message.message) else { continue };
+                    stats.received += 1;
+                    stats.latencies_us.push(received_at.saturating_sub(sent_at));
+                    let last = last_seq.entry(from).or_insert(0);
+                    if seq > *last {
+                        stats.gaps += seq - *last - 1;
+                        *last = seq;
+                    } else {
+                        stats.duplicates += 1;
+                    }
+                }
+                Some(ChatEvent::Error(_)) => stats.rejected += 1,
+                Some(ChatEvent::Disconnected { .. }) => stats.disconnects += 1,
+                Some(_) => {}
+                None => break,
+            },
+            _ = &mut drain => break,
+        }
+    }
+    let _ = sender.close();
+    stats
+}
+
+fn parse_payload(message: &str) -> Option<(usize, u64, u64)> {
+    let mut fields = message.strip_prefix(PAYLOAD_PREFIX)?.split(' ');
+    let from = fields.next()?.parse().ok()?;
+    let seq = fields.next()?.parse().ok()?;
+    let sent_at = fields.next()?.parse().ok()?;
+    Some((from, seq, sent_at))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenvy::dotenv().ok();
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            if !message.is_empty() {
+                eprintln!("{}\n", message);
+            }
+            eprintln!("{}", USAGE);
+            std::process::exit(if message.is_empty() { 0 } else { 1 });
+        }
+    };
+
+    let server_url = match &options.server {
+        Some(url) => url.clone(),
+        None => {
+            let addr = start_local_server()?;
+            println!("Started in-memory chat server on {}", addr);
+            format!("http://{}", addr)
+        }
+    };
+
+    // Connect every user before anyone sends, so each message has a known audience
+    let auth_config = AuthConfig::from_env();
+    let connect_started = Instant::now();
+    let connections: Vec<(usize, Result<ChatClient, String>)> = stream::iter(0..options.clients)
+        .map(|id| {
+            let token = JwtUtil::generate_access_token(Uuid::new_v4().to_string(), &auth_config);
+            let server_url = server_url.clone();
+            let room = format!("loadgen-{}", id % options.rooms);
+            async move {
+                let token = match token {
+                    Ok(token) => token,
+                    Err(e) => return (id, Err(e.to_string())),
+                };
+                let client = ChatClient::builder(server_url)
+                    .token(token, None)
+                    .room(room)
+                    .connect()
+                    .await
+                    .map_err(|e| e.to_string());
+                (id, client)
+            }
+        })
+        .buffer_unordered(options.concurrency)
+        .collect()
+        .await;
+    let connect_time = connect_started.elapsed();
+
+    let mut room_sizes = vec![0u64; options.rooms];
+    let mut clients = Vec::new();
+    let mut failures: HashMap<String, usize> = HashMap::new();
+    for (id, connection) in connections {
+        match connection {
+            Ok(client) => {
+                room_sizes[id % options.rooms] += 1;
+                clients.push((id, client));
+            }
+            Err(e) => *failures.entry(e).or_insert(0) += 1,
+        }
+    }
+    let connect_failures: usize = failures.values().sum();
+    for (error, count) in &failures {
+        eprintln!("{} connections failed: {}", count, error);
+    }
+    if clients.is_empty() {
+        return Err("No client could connect".into());
+    }
+
+    let clock = Instant::now();
+    let send_from = clock + Duration::from_millis(200);
+    let schedule = Schedule {
+        clock,
+        send_from,
+        send_until: send_from + options.duration,
+        drain_until: send_from + options.duration + options.drain,
+        period: Duration::from_secs_f64(options.clients as f64 / options.rate),
+    };
+    println!(
+        "Sending {:.1} msg/s from {} clients in {} rooms for {:.1}s...",
+        options.rate,
+        clients.len(),
+        options.rooms,
+        options.duration.as_secs_f64()
+    );
+
+    let tasks: Vec<_> = clients
+        .into_iter()
+        .map(|(id, client)| {
+            let handle = tokio::spawn(run_client(id, options.clients, client, schedule));
+            (id, handle)
+        })
+        .collect();
+
+    let mut results = Vec::new();
+    for (id, handle) in tasks {
+        results.push((handle.await?, room_sizes[id % options.rooms]));
+    }
+    let report = LoadReport::from_clients(results);
+    let send_secs = options.duration.as_secs_f64();
+
+    println!();
+    println!("Connections: {} ok, {} failed, opened in {:.2}s", options.clients - connect_failures, connect_failures, connect_time.as_secs_f64());
+    println!("Sent:        {} messages ({:.1} msg/s), {} rejected", report.sent, report.sent as f64 / send_secs, report.rejected);
+    println!(
+        "Delivered:   {} of {} expected ({:.2}%), {:.1} deliveries/s",
+        report.received,
+        report.expected,
+        report.delivered_percent,
+        report.received as f64 / send_secs
+    );
+    println!(
+        "Dropped:     {} missing, {} sequence gaps (lagged), {} duplicates or reordered, {} disconnects",
+        report.missing, report.gaps, report.duplicates, report.disconnects
+    );
+    match report.latency_ms {
+        Some(latency) => println!(
+            "Latency ms:  p50 {:.2}  p90 {:.2}  p99 {:.2}  p99.9 {:.2}  max {:.2}",
+            latency.p50, latency.p90, latency.p99, latency.p999, latency.max
+        ),
+        None => println!("Latency ms:  n/a, no message was delivered"),
+    }
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use std::error::Error;
+
+use clap::Args;
+
+use crate::modules::chat::client::Credentials;
+use crate::modules::chat::entities::room::DEFAULT_ROOM;
+use crate::modules::chat::tui::{self, ClientOptions};
+
+#[derive(Args)]
+pub struct ChatArgs {
+    /// Rooms to join; the last one is shown first
+    #[arg(value_name = "ROOM", default_value = DEFAULT_ROOM)]
+    pub rooms: Vec<String>,
+
+    /// Server to connect to
+    #[arg(long, short, env = "CHAT_SERVER_URL", default_value = "http://localhost:3005")]
+    pub server: String,
+
+    /// Access token to connect with
+    #[arg(long, short, env = "CHAT_TOKEN", hide_env_values = true, required_unless_present = "email")]
+    pub token: Option<String>,
+
+    /// Log in with this email instead of a token
+    #[arg(long, short, conflicts_with = "token", requires = "password")]
+    pub email: Option<String>,
+
+    /// Password for --email
+    #[arg(long, env = "CHAT_PASSWORD", hide_env_values = true)]
+    pub password: Option<String>,
+
+    /// Read lines from stdin and print one line per event, for scripting
+    #[arg(long)]
+    pub plain: bool,
+}
+
+pub async fn run(args: ChatArgs) -> Result<(), Box<dyn Error>> {
+    let (credentials, username) = match (args.email, args.password, args.token) {
+        (Some(email), Some(password), _) => (Credentials::Password { email, password }, None),
+        (_, _, Some(token)) => {
+            let username = tui::token_username(&token);
+            let credentials = Credentials::Token {
+                access_token: token,
+                refresh_token: None,
+            };
+            (credentials, username)
+        }
+        _ => return Err("Either --token or --email and --password are needed".into()),
+    };
+    let options = ClientOptions {
+        server_url: args.server,
+        credentials,
+        rooms: args.rooms,
+    };
+
+    if args.plain {
+        tui::run_plain(options).await
+    } else {
+        tui::run(options, username).await
+    }
+}
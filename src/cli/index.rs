@@ -0,0 +1,41 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::modules::file_indexer::service::FileIndexerService;
+
+#[derive(Args)]
+pub struct IndexArgs {
+    /// Directory to scan, including its subdirectories
+    pub directory: PathBuf,
+
+    /// Threads hashing files in parallel
+    #[arg(long, short, default_value_t = 4)]
+    pub threads: usize,
+}
+
+pub async fn run(args: IndexArgs) -> Result<(), Box<dyn Error>> {
+    let directory = args.directory.to_str().ok_or("Directory path is not valid UTF-8")?;
+    println!("Scanning directory: {}", directory);
+    println!("Using {} threads", args.threads);
+
+    let result = FileIndexerService::scan_directory_async(directory, Some(args.threads))
+        .await
+        .map_err(|e| format!("Error scanning directory: {}", e))?;
+
+    println!("\nScan completed in {}", result.time_taken);
+    println!("Total files processed: {}", result.total_files);
+    if result.duplicates.is_empty() {
+        println!("\nNo duplicate files found.");
+    } else {
+        println!("\nDuplicate files found:");
+        for (hash, paths) in &result.duplicates {
+            println!(" - Hash {}: {} duplicates", &hash[..12], paths.len());
+            for path in paths {
+                println!("   → {}", path.display());
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use sqlx::migrate::Migrator;
+use sqlx::PgPool;
+
+use crate::infrastructure::db::init_pool;
+
+/// Where migrations are read from unless `--source` says otherwise
+pub const DEFAULT_SOURCE: &str = "migrations";
+
+#[derive(Args)]
+pub struct MigrateArgs {
+    /// Directory holding the migration files
+    #[arg(long, value_name = "DIR", default_value = DEFAULT_SOURCE)]
+    pub source: PathBuf,
+
+    /// Only list the migrations and whether each has been applied
+    #[arg(long)]
+    pub status: bool,
+}
+
+pub async fn run(args: MigrateArgs) -> Result<(), Box<dyn Error>> {
+    let pool = init_pool().await;
+
+    if args.status {
+        let migrator = Migrator::new(args.source.as_path()).await?;
+        let applied = applied_versions(&pool).await?;
+        for migration in migrator.iter() {
+            let state = if applied.contains(&migration.version) { "applied" } else { "pending" };
+            println!("{:<8} {} {}", state, migration.version, migration.description);
+        }
+        return Ok(());
+    }
+
+    let count = apply(&pool, &args.source).await?;
+    println!("Applied {} migration(s)", count);
+    Ok(())
+}
+
+/// Apply the migrations in `source` that have not been applied yet, returning how many ran
+pub async fn apply(pool: &PgPool, source: &Path) -> Result<usize, Box<dyn Error>> {
+    let migrator = Migrator::new(source)
+        .await
+        .map_err(|e| format!("Failed to read migrations from {}: {}", source.display(), e))?;
+    let applied = applied_versions(pool).await?;
+    migrator.run(pool).await?;
+    Ok(migrator.iter().filter(|migration| !applied.contains(&migration.version)).count())
+}
+
+/// Versions recorded as applied, none when no migration has ever run
+async fn applied_versions(pool: &PgPool) -> Result<HashSet<i64>, sqlx::Error> {
+    let has_table: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(pool)
+        .await?;
+    if !has_table {
+        return Ok(HashSet::new());
+    }
+    let versions: Vec<i64> = sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+        .fetch_all(pool)
+        .await?;
+    Ok(versions.into_iter().collect())
+}
@@ -0,0 +1,85 @@
+//! Command-line interface: one binary that runs the server and the terminal chat
+//! client, indexes files and manages the database and user accounts
+//!
+//! Every command loads the configuration the same way, from the environment and an
+//! optional `.env` file, so the server and the tools always agree on it.
+
+pub mod chat;
+pub mod index;
+pub mod migrate;
+pub mod serve;
+pub mod user;
+
+use std::env;
+use std::error::Error;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+#[derive(Parser)]
+#[command(version, about = "Chat server with authentication, file indexing and a terminal client")]
+pub struct Cli {
+    /// File to load environment variables from; variables already set take precedence
+    /// [default: .env in the current or a parent directory]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub env_file: Option<PathBuf>,
+
+    /// Database to connect to, instead of DATABASE_URL or the DATABASE_* variables
+    #[arg(long, global = true, value_name = "URL")]
+    pub database_url: Option<String>,
+
+    /// Command to run [default: serve]
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run the API and chat server
+    Serve(serve::ServeArgs),
+    /// Open the terminal chat client
+    Chat(chat::ChatArgs),
+    /// Find duplicate files in a directory
+    Index(index::IndexArgs),
+    /// Apply pending database migrations or list them
+    Migrate(migrate::MigrateArgs),
+    /// Manage user accounts
+    #[command(subcommand)]
+    User(user::UserCommand),
+}
+
+impl Cli {
+    /// Load the configuration shared by all commands into the process environment,
+    /// where the application reads it from
+    fn load_config(&self) -> Result<(), Box<dyn Error>> {
+        match &self.env_file {
+            Some(path) => {
+                dotenvy::from_path(path).map_err(|e| format!("Failed to load {}: {}", path.display(), e))?;
+            }
+            None => {
+                dotenvy::dotenv().ok();
+            }
+        }
+        if let Some(url) = &self.database_url {
+            env::set_var("DATABASE_URL", url);
+        }
+        Ok(())
+    }
+}
+
+/// Run the command given on the command line
+pub fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    // The environment must be complete before the runtime starts other threads
+    cli.load_config()?;
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async move {
+        match cli.command.unwrap_or(Command::Serve(serve::ServeArgs::default())) {
+            Command::Serve(args) => serve::run(args).await,
+            Command::Chat(args) => chat::run(args).await,
+            Command::Index(args) => index::run(args).await,
+            Command::Migrate(args) => migrate::run(args).await,
+            Command::User(command) => user::run(command).await,
+        }
+    })
+}
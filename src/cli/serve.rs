@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use clap::Args;
+use tracing::info;
+
+use crate::app;
+use crate::config::environment::Environment;
+use crate::infrastructure::db::init_pool;
+use crate::utils::logger::init_logger;
+
+#[derive(Args, Default)]
+pub struct ServeArgs {
+    /// Port to listen on [default: PORT, or 3005]
+    #[arg(long, short)]
+    pub port: Option<u16>,
+
+    /// Address to listen on [default: 0.0.0.0]
+    #[arg(long)]
+    pub host: Option<IpAddr>,
+
+    /// Apply pending migrations from ./migrations before starting
+    #[arg(long)]
+    pub migrate: bool,
+}
+
+pub async fn run(args: ServeArgs) -> Result<(), Box<dyn Error>> {
+    init_logger();
+
+    let pool = init_pool().await;
+    if args.migrate {
+        let applied = super::migrate::apply(&pool, super::migrate::DEFAULT_SOURCE.as_ref()).await?;
+        info!("Applied {} migration(s)", applied);
+    } else {
+        // Skip migrations for external database to avoid schema conflicts
+        info!("Skipping migrations for external database - using existing schema");
+    }
+
+    // Initialize chat state
+    let env = Environment::from_env();
+    let chat_state = app::chat_state(pool.clone(), &env.chat, &env.app);
+    app::spawn_background_jobs(pool.clone(), &chat_state);
+
+    let app = app::router(pool, chat_state);
+
+    let addr = SocketAddr::new(args.host.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)), args.port.unwrap_or(env.app.port));
+    info!("Listening on {}", addr);
+    info!("API Documentation available at: http://{}:{}/swagger-ui/", addr.ip(), addr.port());
+    info!("Health check endpoint: http://{}:{}/health", addr.ip(), addr.port());
+
+    axum::Server::try_bind(&addr)?.serve(app.into_make_service()).await?;
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use std::error::Error;
+
+use clap::Subcommand;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::config::environment::Environment;
+use crate::infrastructure::db::init_pool;
+use crate::modules::auth::dto::auth_dto::RegisterDto;
+use crate::modules::auth::entities::user::User;
+use crate::modules::auth::repositories::AuthRepository;
+use crate::modules::auth::service::AuthService;
+use crate::modules::auth::utils::jwt::JwtUtil;
+use crate::modules::chat::server::display_name;
+
+#[derive(Subcommand)]
+pub enum UserCommand {
+    /// Create a user account
+    Create {
+        /// Full name
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        email: String,
+        #[arg(long, env = "USER_PASSWORD", hide_env_values = true)]
+        password: String,
+    },
+    /// Show a user account
+    Show {
+        /// Email or ID of the user
+        user: String,
+    },
+    /// Issue an access and a refresh token for a user, e.g. for `chat --token`
+    Token {
+        /// Email or ID of the user
+        user: String,
+    },
+    /// Revoke every token issued to a user so far; a running server closes their
+    /// connections within `CHAT_REVOCATION_CHECK_SECS`
+    RevokeTokens {
+        /// Email or ID of the user
+        user: String,
+    },
+}
+
+pub async fn run(command: UserCommand) -> Result<(), Box<dyn Error>> {
+    let pool = init_pool().await;
+    let env = Environment::from_env();
+    let repository = AuthRepository::new(pool.clone());
+
+    match command {
+        UserCommand::Create { name, email, password } => {
+            // The users table only keeps the name and email, so the other profile
+            // fields of a registration are left empty
+            let register_dto = RegisterDto {
+                full_name: name,
+                email,
+                age: 0,
+                password,
+                date_of_birth: OffsetDateTime::UNIX_EPOCH,
+                gender: String::new(),
+                phone_number: String::new(),
+            };
+            let user = AuthService::new(pool, env).register(register_dto).await?;
+            print_user(&user);
+        }
+        UserCommand::Show { user } => print_user(&find_user(&repository, &user).await?),
+        UserCommand::Token { user } => {
+            let user = find_user(&repository, &user).await?;
+            println!("access_token:  {}", JwtUtil::generate_access_token(user.id.to_string(), &env.auth)?);
+            println!("refresh_token: {}", JwtUtil::generate_refresh_token(user.id.to_string(), &env.auth)?);
+        }
+        UserCommand::RevokeTokens { user } => {
+            let user = find_user(&repository, &user).await?;
+            AuthService::new(pool, env).revoke_tokens(user.id).await?;
+            println!("Revoked all tokens of {}", user.email);
+        }
+    }
+    Ok(())
+}
+
+async fn find_user(repository: &AuthRepository, user: &str) -> Result<User, Box<dyn Error>> {
+    let found = match Uuid::parse_str(user) {
+        Ok(id) => repository.find_user_by_id(id).await?,
+        Err(_) => repository.find_user_by_email(user).await?,
+    };
+    found.ok_or_else(|| format!("User {} not found", user).into())
+}
+
+fn print_user(user: &User) {
+    println!("id:         {}", user.id);
+    println!("name:       {}", user.name);
+    println!("email:      {}", user.email);
+    println!("chat name:  {}", display_name(user.id));
+    println!("created_at: {}", user.created_at);
+}
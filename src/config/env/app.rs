@@ -3,6 +3,9 @@ use std::env;
 /// Application environment configuration
 pub struct AppConfig {
     pub port: u16,
+    /// Origins, e.g. `https://chat.example.com`, allowed to open chat sockets and call
+    /// the API from a browser in addition to the server's own origin; `*` allows any
+    pub allowed_origins: Vec<String>,
 }
 
 impl AppConfig {
@@ -12,7 +15,14 @@ impl AppConfig {
             .unwrap_or_else(|_| "3005".to_string())
             .parse::<u16>()
             .unwrap_or(3005);
+
+        let allowed_origins = env::var("ALLOWED_ORIGINS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|origin| origin.trim().trim_end_matches('/').to_lowercase())
+            .filter(|origin| !origin.is_empty())
+            .collect();
             
-        Self { port }
+        Self { port, allowed_origins }
     }
 }
\ No newline at end of file
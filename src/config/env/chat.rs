@@ -0,0 +1,137 @@
+use std::env;
+use std::path::PathBuf;
+
+use uuid::Uuid;
+
+const DEFAULT_PROFANITY_WORDS: &str = "fuck,fucking,shit,bitch,asshole,bastard,cunt";
+
+/// Chat environment configuration
+pub struct ChatConfig {
+    pub attachments_dir: PathBuf,
+    pub max_attachment_bytes: usize,
+    /// Attempts made to deliver a webhook event before giving up
+    pub webhook_max_attempts: u32,
+    /// Delay before the first webhook retry; doubles with every further attempt
+    pub webhook_retry_base_ms: u64,
+    pub webhook_timeout_secs: u64,
+    /// Deliver webhooks to loopback, private and link-local addresses, e.g. for local testing
+    pub webhook_allow_private_addresses: bool,
+    /// Messages each incoming webhook may post per minute
+    pub incoming_webhook_rate_limit: u32,
+    /// Built-in plugins to enable, e.g. `echo`
+    pub plugins: Vec<String>,
+    /// Words masked in every room's messages
+    pub profanity_words: Vec<String>,
+    /// Server administrators, who may act on any room
+    pub admin_users: Vec<Uuid>,
+    /// Days messages are kept in rooms without their own retention; 0 keeps them forever
+    pub retention_days: u32,
+    /// How often the purge job looks for expired messages
+    pub retention_interval_secs: u64,
+    /// Rows deleted per purge query, keeping each transaction short
+    pub retention_batch_size: i64,
+    /// How often open chat connections are checked for revoked tokens
+    pub revocation_check_secs: u64,
+}
+
+impl ChatConfig {
+    /// Load chat configuration from environment variables
+    pub fn from_env() -> Self {
+        let attachments_dir = env::var("CHAT_ATTACHMENTS_DIR")
+            .unwrap_or_else(|_| "./data/attachments".to_string())
+            .into();
+
+        let max_attachment_bytes = env::var("CHAT_MAX_ATTACHMENT_BYTES")
+            .unwrap_or_else(|_| "10485760".to_string())
+            .parse::<usize>()
+            .unwrap_or(10 * 1024 * 1024);
+
+        let webhook_max_attempts = env::var("CHAT_WEBHOOK_MAX_ATTEMPTS")
+            .unwrap_or_else(|_| "8".to_string())
+            .parse::<u32>()
+            .unwrap_or(8);
+
+        let webhook_retry_base_ms = env::var("CHAT_WEBHOOK_RETRY_BASE_MS")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse::<u64>()
+            .unwrap_or(1000);
+
+        let webhook_timeout_secs = env::var("CHAT_WEBHOOK_TIMEOUT_SECS")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse::<u64>()
+            .unwrap_or(10);
+
+        let webhook_allow_private_addresses = env::var("CHAT_WEBHOOK_ALLOW_PRIVATE_ADDRESSES")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse::<bool>()
+            .unwrap_or(false);
+
+        let incoming_webhook_rate_limit = env::var("CHAT_INCOMING_WEBHOOK_RATE_LIMIT")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse::<u32>()
+            .unwrap_or(30);
+
+        let plugins = comma_separated(&env::var("CHAT_PLUGINS").unwrap_or_default());
+
+        let profanity_words = comma_separated(
+            &env::var("CHAT_PROFANITY_WORDS").unwrap_or_else(|_| DEFAULT_PROFANITY_WORDS.to_string()),
+        );
+
+        let admin_users = comma_separated(&env::var("CHAT_ADMIN_USERS").unwrap_or_default())
+            .iter()
+            .filter_map(|id| match id.parse() {
+                Ok(id) => Some(id),
+                Err(_) => {
+                    eprintln!("Ignoring invalid admin user ID: {}", id);
+                    None
+                }
+            })
+            .collect();
+
+        let retention_days = env::var("CHAT_RETENTION_DAYS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<u32>()
+            .unwrap_or(0);
+
+        let retention_interval_secs = env::var("CHAT_RETENTION_INTERVAL_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse::<u64>()
+            .unwrap_or(3600);
+
+        let retention_batch_size = env::var("CHAT_RETENTION_BATCH_SIZE")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse::<i64>()
+            .unwrap_or(1000);
+
+        let revocation_check_secs = env::var("CHAT_REVOCATION_CHECK_SECS")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse::<u64>()
+            .unwrap_or(10)
+            .max(1);
+
+        Self {
+            attachments_dir,
+            max_attachment_bytes,
+            webhook_max_attempts,
+            webhook_retry_base_ms,
+            webhook_timeout_secs,
+            webhook_allow_private_addresses,
+            incoming_webhook_rate_limit,
+            plugins,
+            profanity_words,
+            admin_users,
+            retention_days,
+            retention_interval_secs,
+            retention_batch_size,
+            revocation_check_secs,
+        }
+    }
+}
+
+fn comma_separated(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
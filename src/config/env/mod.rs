@@ -1,7 +1,9 @@
 pub mod app;
 pub mod auth;
+pub mod chat;
 pub mod database;
 
 pub use app::AppConfig;
 pub use auth::AuthConfig;
+pub use chat::ChatConfig;
 pub use database::DatabaseConfig;
\ No newline at end of file
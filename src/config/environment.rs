@@ -1,10 +1,11 @@
-use crate::config::env::{AppConfig, AuthConfig, DatabaseConfig};
+use crate::config::env::{AppConfig, AuthConfig, ChatConfig, DatabaseConfig};
 
 /// Application environment configuration
 pub struct Environment {
     pub database: DatabaseConfig,
     pub app: AppConfig,
     pub auth: AuthConfig,
+    pub chat: ChatConfig,
 }
 
 impl Environment {
@@ -13,11 +14,13 @@ impl Environment {
         let database = DatabaseConfig::from_env();
         let app = AppConfig::from_env();
         let auth = AuthConfig::from_env();
+        let chat = ChatConfig::from_env();
         
         Self {
             database,
             app,
             auth,
+            chat,
         }
     }
 }
\ No newline at end of file
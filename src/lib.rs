@@ -1,8 +1,12 @@
+pub mod app;
+pub mod cli;
 pub mod config;
 pub mod infrastructure;
 pub mod modules;
 pub mod routes;
 pub mod utils;
 
+#[cfg(test)]
+mod test_harness;
 #[cfg(test)]
 mod tests;
\ No newline at end of file
@@ -53,4 +53,62 @@ impl AuthRepository {
 
         Ok(user)
     }
+
+    /// Find users addressed by chat mention names.
+    ///
+    /// A name matches a user's name with spaces removed, or their `User_xxxxxxxx`
+    /// chat display name, case-insensitively.
+    pub async fn find_users_by_mention_names(&self, names: &[String]) -> Result<Vec<User>, Error> {
+        let names: Vec<String> = names.iter().map(|name| name.to_lowercase()).collect();
+        let users = sqlx::query_as::<_, User>(
+            "SELECT id, name, email, created_at, updated_at 
+             FROM users
+             WHERE lower(replace(name, ' ', '')) = ANY($1)
+                OR 'user_' || left(id::text, 8) = ANY($1)"
+        )
+        .bind(&names)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(users)
+    }
+
+    /// Revoke every token issued to a user so far
+    pub async fn revoke_tokens(&self, user_id: Uuid) -> Result<(), Error> {
+        sqlx::query(
+            "INSERT INTO token_revocations (user_id, revoked_before) VALUES ($1, NOW())
+             ON CONFLICT (user_id) DO UPDATE SET revoked_before = EXCLUDED.revoked_before"
+        )
+        .bind(user_id)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Unix time at or before which the user's tokens were revoked, if ever
+    pub async fn find_tokens_revoked_before(&self, user_id: Uuid) -> Result<Option<i64>, Error> {
+        let revoked_before = sqlx::query_scalar::<_, i64>(
+            "SELECT floor(EXTRACT(EPOCH FROM revoked_before) * 1000)::BIGINT FROM token_revocations WHERE user_id = $1"
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(revoked_before)
+    }
+    /// Users whose tokens were revoked within the last `within_secs` seconds, with the
+    /// Unix time in milliseconds at or before which their tokens were revoked
+    pub async fn find_recent_revocations(&self, within_secs: f64) -> Result<Vec<(Uuid, i64)>, Error> {
+        let revocations = sqlx::query_as::<_, (Uuid, i64)>(
+            "SELECT user_id, floor(EXTRACT(EPOCH FROM revoked_before) * 1000)::BIGINT
+             FROM token_revocations
+             WHERE revoked_before > NOW() - make_interval(secs => $1)"
+        )
+        .bind(within_secs)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(revocations)
+    }
 }
\ No newline at end of file
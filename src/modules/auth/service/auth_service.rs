@@ -66,6 +66,10 @@ impl AuthService {
         // Extract user ID
         let user_id = Uuid::parse_str(&claims.sub)?;
         
+        if self.is_token_revoked(user_id, claims.issued_at_ms()).await? {
+            return Err("Refresh token has been revoked".into());
+        }
+
         // Check if user still exists
         if (self.auth_repository.find_user_by_id(user_id).await?).is_some() {
             // Generate new JWT tokens
@@ -82,6 +86,33 @@ impl AuthService {
         }
     }
 
+    /// Revoke every access and refresh token issued to the user so far
+    pub async fn revoke_tokens(&self, user_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+        self.auth_repository.revoke_tokens(user_id).await?;
+        Ok(())
+    }
+
+    /// Whether a token issued at `issued_at_ms` (Unix milliseconds) may no longer be used,
+    /// because the user revoked their tokens since or has been deleted
+    pub async fn is_token_revoked(&self, user_id: Uuid, issued_at_ms: u64) -> Result<bool, Box<dyn std::error::Error>> {
+        if let Some(revoked_before) = self.auth_repository.find_tokens_revoked_before(user_id).await? {
+            if issued_at_ms as i64 <= revoked_before {
+                return Ok(true);
+            }
+        }
+        Ok(self.auth_repository.find_user_by_id(user_id).await?.is_none())
+    }
+
+    /// Users who revoked their tokens within `within`, with the Unix time in milliseconds
+    /// at or before which their tokens stopped being accepted
+    pub async fn recent_revocations(&self, within: std::time::Duration) -> Result<Vec<(Uuid, u64)>, Box<dyn std::error::Error>> {
+        let revocations = self.auth_repository.find_recent_revocations(within.as_secs_f64()).await?;
+        Ok(revocations
+            .into_iter()
+            .map(|(user_id, revoked_before)| (user_id, revoked_before.max(0) as u64))
+            .collect())
+    }
+
     pub async fn change_password(&self, user_id: Uuid, _change_password_dto: ChangePasswordDto) -> Result<(), Box<dyn std::error::Error>> {
         let user = self.auth_repository.find_user_by_id(user_id).await?;
         
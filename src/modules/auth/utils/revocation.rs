@@ -0,0 +1,52 @@
+//! Rejection of revoked access tokens on the REST routes
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::config::environment::Environment;
+use crate::modules::auth::service::AuthService;
+use crate::modules::auth::utils::jwt::JwtUtil;
+
+/// Middleware rejecting requests whose bearer token was revoked or whose user was deleted.
+///
+/// Handlers validate the token themselves with [`JwtUtil::user_id_from_headers`], which
+/// cannot reach the database; requests without a valid bearer token are passed on for
+/// them to reject.
+pub async fn reject_revoked_tokens(
+    State(pool): State<Pool<Postgres>>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let env = Environment::from_env();
+    let claims = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .and_then(|token| JwtUtil::validate_access_token(token, &env.auth).ok());
+    let Some(claims) = claims else {
+        return next.run(request).await;
+    };
+    let Ok(user_id) = Uuid::parse_str(&claims.sub) else {
+        return next.run(request).await;
+    };
+
+    let revoked = AuthService::new(pool, env)
+        .is_token_revoked(user_id, claims.issued_at_ms())
+        .await
+        .map_err(|e| e.to_string());
+    match revoked {
+        Ok(false) => next.run(request).await,
+        Ok(true) => (StatusCode::UNAUTHORIZED, "Token has been revoked").into_response(),
+        Err(e) => {
+            eprintln!("Failed to check token revocation: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Token could not be verified, please try again").into_response()
+        }
+    }
+}
@@ -1,53 +1,710 @@
-//! Simple chat client for testing the WebSocket server
+//! Chat client SDK
+//!
+//! [`ChatClient`] logs in, keeps a WebSocket connection to one room open and hands
+//! out server frames as typed [`ChatEvent`]s. Dropped connections are re-established
+//! with exponential backoff, resuming the room after the last message seen, and
+//! sessions are kept alive by answering the server's `reauth:` prompts with a
+//! refreshed access token.
+//!
+//! ```no_run
+//! use futures::StreamExt;
+//! use rust_axum_project::modules::chat::client::{ChatClient, ChatEvent};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut client = ChatClient::builder("http://localhost:3000")
+//!     .login("bot@example.com", "password123")
+//!     .room("general")
+//!     .connect()
+//!     .await?;
+//! let sender = client.sender();
+//! while let Some(event) = client.next().await {
+//!     if let ChatEvent::Message(message) = event {
+//!         if message.message == "ping" {
+//!             sender.send_message("pong")?;
+//!         }
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
 
-use futures::{SinkExt, StreamExt};
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message as TungsteniteMessage};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::{SinkExt, Stream, StreamExt};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::{self, protocol::Message as TungsteniteMessage};
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 use url::Url;
+use uuid::Uuid;
+
+use crate::modules::auth::dto::auth_dto::{LoginDto, RefreshTokenDto, TokenResponse};
+use crate::modules::chat::dto::announcement_dto::AnnouncementResponse;
+use crate::modules::chat::dto::notification_dto::NotificationResponse;
+use crate::modules::chat::dto::poll_dto::PollResponse;
+use crate::modules::chat::dto::room_dto::{PinnedMessagesResponse, RoomTopicResponse};
+use crate::modules::chat::dto::ticket_dto::ChatTicketResponse;
+use crate::modules::chat::entities::room::DEFAULT_ROOM;
+use crate::modules::chat::server::{ChatMessage, DirectMessage, IncomingChatMessage, SessionInfo, SystemMessage};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+const DEFAULT_MIN_BACKOFF: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Exchange an access token for a single-use WebSocket ticket
+pub async fn request_ticket(server_url: &str, jwt_token: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let response = reqwest::Client::new()
+        .post(endpoint(&parse_server_url(server_url)?, &["api", "chat", "ticket"]))
+        .bearer_auth(jwt_token)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(response.json::<ChatTicketResponse>().await?.ticket)
+}
+
+/// Build the WebSocket URL for joining `room` with a ticket, resuming after `since`
+pub fn websocket_url(server_url: &str, ticket: &str, room: &str, since: Option<u64>) -> Result<Url, ClientError> {
+    let mut url = endpoint(&parse_server_url(server_url)?, &["ws"]);
+    let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+    url.set_scheme(scheme)
+        .map_err(|_| ClientError::InvalidUrl(server_url.to_string()))?;
+    {
+        let mut query = url.query_pairs_mut();
+        query.append_pair("ticket", ticket).append_pair("room", room);
+        if let Some(since) = since {
+            query.append_pair("since", &since.to_string());
+        }
+    }
+    Ok(url)
+}
+
+fn parse_server_url(server_url: &str) -> Result<Url, ClientError> {
+    match Url::parse(server_url) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.has_host() => Ok(url),
+        _ => Err(ClientError::InvalidUrl(server_url.to_string())),
+    }
+}
+
+/// `segments` appended to the server URL's path, so servers mounted under a prefix work
+fn endpoint(base: &Url, segments: &[&str]) -> Url {
+    let mut url = base.clone();
+    url.set_query(None);
+    url.set_fragment(None);
+    if let Ok(mut path) = url.path_segments_mut() {
+        path.pop_if_empty().extend(segments);
+    }
+    url
+}
+
+/// How the client authenticates
+#[derive(Clone)]
+pub enum Credentials {
+    /// Logged in through `/auth/login`, and again whenever the refresh token stops working
+    Password { email: String, password: String },
+    /// Tokens issued elsewhere; without a refresh token the client stops once the
+    /// access token expires
+    Token {
+        access_token: String,
+        refresh_token: Option<String>,
+    },
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Credentials::Password { email, .. } => f.debug_struct("Password").field("email", email).finish_non_exhaustive(),
+            Credentials::Token { .. } => f.debug_struct("Token").finish_non_exhaustive(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ClientError {
+    InvalidUrl(String),
+    /// The credentials were rejected, or the user may not join the room
+    Unauthorized(String),
+    Http(reqwest::Error),
+    WebSocket(Box<tungstenite::Error>),
+    /// The client has been closed
+    Closed,
+}
+
+impl ClientError {
+    /// Whether retrying cannot help
+    fn is_fatal(&self) -> bool {
+        matches!(self, ClientError::InvalidUrl(_) | ClientError::Unauthorized(_) | ClientError::Closed)
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::InvalidUrl(url) => write!(f, "Invalid server URL: {}", url),
+            ClientError::Unauthorized(reason) => write!(f, "Unauthorized: {}", reason),
+            ClientError::Http(e) => write!(f, "HTTP error: {}", e),
+            ClientError::WebSocket(e) => write!(f, "WebSocket error: {}", e),
+            ClientError::Closed => write!(f, "The chat client has been closed"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(e: reqwest::Error) -> Self {
+        ClientError::Http(e)
+    }
+}
+
+impl From<tungstenite::Error> for ClientError {
+    fn from(e: tungstenite::Error) -> Self {
+        match e {
+            tungstenite::Error::Http(response) if response.status() == StatusCode::FORBIDDEN => {
+                ClientError::Unauthorized("Not allowed to join the room".to_string())
+            }
+            e => ClientError::WebSocket(Box::new(e)),
+        }
+    }
+}
+
+/// Something that happened on the connection
+#[derive(Debug)]
+pub enum ChatEvent {
+    /// Connected, or reconnected, to `room`; missed messages and other room events follow
+    Connected { room: String },
+    /// The connection was lost and is retried after `retry_in`; `None` means the
+    /// client gave up and this is the last event
+    Disconnected { reason: String, retry_in: Option<Duration> },
+    Message(ChatMessage),
+    DirectMessage(DirectMessage),
+    Mention(NotificationResponse),
+    Topic(RoomTopicResponse),
+    Pins(PinnedMessagesResponse),
+    Poll(PollResponse),
+    System(SystemMessage),
+    /// Server-wide announcement from an administrator, or the current banner after joining
+    Announcement(AnnouncementResponse),
+    Error(SystemMessage),
+    Session(SessionInfo),
+    /// The session is about to expire; the client reauthenticates on its own
+    ReauthRequired(SessionInfo),
+    /// A frame this client does not understand
+    Unknown(String),
+}
+
+impl ChatEvent {
+    /// Parse a text frame sent by the server
+    pub fn parse(frame: &str) -> ChatEvent {
+        fn typed<T: DeserializeOwned>(json: &str, event: fn(T) -> ChatEvent, frame: &str) -> ChatEvent {
+            serde_json::from_str(json).map(event).unwrap_or_else(|_| ChatEvent::Unknown(frame.to_string()))
+        }
+
+        if frame.starts_with('{') {
+            return typed(frame, ChatEvent::Message, frame);
+        }
+        let Some((prefix, json)) = frame.split_once(':') else {
+            return ChatEvent::Unknown(frame.to_string());
+        };
+        match prefix {
+            "system" => typed(json, ChatEvent::System, frame),
+            "error" => typed(json, ChatEvent::Error, frame),
+            "session" => typed(json, ChatEvent::Session, frame),
+            "reauth" => typed(json, ChatEvent::ReauthRequired, frame),
+            "topic" => typed(json, ChatEvent::Topic, frame),
+            "pins" => typed(json, ChatEvent::Pins, frame),
+            "poll" => typed(json, ChatEvent::Poll, frame),
+            "dm" => typed(json, ChatEvent::DirectMessage, frame),
+            "mention" => typed(json, ChatEvent::Mention, frame),
+            "announcement" => typed(json, ChatEvent::Announcement, frame),
+            _ => ChatEvent::Unknown(frame.to_string()),
+        }
+    }
+}
+
+/// Configures and connects a [`ChatClient`]
+#[derive(Debug)]
+pub struct ChatClientBuilder {
+    server_url: String,
+    credentials: Option<Credentials>,
+    room: String,
+    since: Option<u64>,
+    min_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl ChatClientBuilder {
+    /// Log in with email and password
+    pub fn login(mut self, email: impl Into<String>, password: impl Into<String>) -> Self {
+        self.credentials = Some(Credentials::Password {
+            email: email.into(),
+            password: password.into(),
+        });
+        self
+    }
+
+    /// Use an existing access token, refreshed with `refresh_token` when given
+    pub fn token(mut self, access_token: impl Into<String>, refresh_token: Option<String>) -> Self {
+        self.credentials = Some(Credentials::Token {
+            access_token: access_token.into(),
+            refresh_token,
+        });
+        self
+    }
+
+    pub fn credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Room to join; defaults to "general"
+    pub fn room(mut self, room: impl Into<String>) -> Self {
+        self.room = room.into();
+        self
+    }
+
+    /// Replay the room's events after this sequence number on connect
+    pub fn since(mut self, seq: u64) -> Self {
+        self.since = Some(seq);
+        self
+    }
+
+    /// Delay before the first reconnect attempt, doubled after each failure up to `max`
+    pub fn backoff(mut self, min: Duration, max: Duration) -> Self {
+        self.min_backoff = min;
+        self.max_backoff = max.max(min);
+        self
+    }
+
+    /// Log in and open the connection; failures here are returned rather than retried
+    pub async fn connect(self) -> Result<ChatClient, ClientError> {
+        let credentials = self
+            .credentials
+            .ok_or_else(|| ClientError::Unauthorized("No credentials given".to_string()))?;
+        let (access_token, refresh_token) = match &credentials {
+            Credentials::Token { access_token, refresh_token } => (Some(access_token.clone()), refresh_token.clone()),
+            Credentials::Password { .. } => (None, None),
+        };
+        parse_server_url(&self.server_url)?;
+
+        let (events_tx, events) = mpsc::unbounded_channel();
+        let (commands_tx, commands) = mpsc::unbounded_channel();
+        let mut connection = Connection {
+            http: reqwest::Client::new(),
+            server_url: self.server_url,
+            credentials,
+            access_token,
+            refresh_token,
+            room: self.room,
+            switching_room: false,
+            last_seq: HashMap::new(),
+            min_backoff: self.min_backoff,
+            max_backoff: self.max_backoff,
+            pending: VecDeque::new(),
+            events: events_tx,
+            commands,
+        };
+        if let Some(since) = self.since {
+            connection.last_seq.insert(connection.room.clone(), since);
+        }
+
+        let socket = connection.open().await?;
+        let task = tokio::spawn(connection.run(socket));
+        Ok(ChatClient {
+            sender: ChatSender { commands: commands_tx },
+            events,
+            task,
+        })
+    }
+}
+
+#[derive(Debug)]
+enum Command {
+    Send(String),
+    Close,
+}
+
+/// Sends messages and commands over a [`ChatClient`]'s connection
+///
+/// Anything sent while the client is reconnecting is delivered once it is connected again.
+#[derive(Clone, Debug)]
+pub struct ChatSender {
+    commands: mpsc::UnboundedSender<Command>,
+}
 
-/// Connect to the chat server
-pub async fn connect_to_chat_server(
-    server_url: &str,
-    jwt_token: &str,
-    room: Option<&str>,
-) -> Result<
-    (
-        futures::stream::SplitSink<
-            tokio_tungstenite::WebSocketStream<
-                tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
-            >,
-            TungsteniteMessage,
-        >,
-        futures::stream::SplitStream<
-            tokio_tungstenite::WebSocketStream<
-                tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
-            >,
-        >,
-    ),
-    Box<dyn std::error::Error>,
-> {
-    let room_param = room.unwrap_or("general");
-    let ws_url = format!(
-        "{}/ws?token={}&room={}",
-        server_url.replace("http://", "ws://").replace("https://", "wss://"),
-        jwt_token,
-        room_param
-    );
-
-    let url = Url::parse(&ws_url)?;
-    let (ws_stream, _) = connect_async(url).await?;
-    Ok(ws_stream.split())
-}
-
-/// Send a message through the WebSocket connection
-pub async fn send_message(
-    sender: &mut futures::stream::SplitSink<
-        tokio_tungstenite::WebSocketStream<
-            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
-        >,
-        TungsteniteMessage,
-    >,
-    message: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    sender.send(TungsteniteMessage::Text(message.to_string())).await?;
-    Ok(())
-}
\ No newline at end of file
+impl ChatSender {
+    fn send_frame(&self, frame: String) -> Result<(), ClientError> {
+        self.commands.send(Command::Send(frame)).map_err(|_| ClientError::Closed)
+    }
+
+    fn send_incoming(&self, message: IncomingChatMessage) -> Result<(), ClientError> {
+        // Sent as JSON so text that looks like a command or JSON is posted verbatim
+        self.send_frame(serde_json::to_string(&message).unwrap())
+    }
+
+    /// Post a chat message to the current room
+    pub fn send_message(&self, text: impl Into<String>) -> Result<(), ClientError> {
+        self.send_incoming(IncomingChatMessage {
+            message: text.into(),
+            attachment_id: None,
+        })
+    }
+
+    /// Post a chat message sharing an uploaded attachment
+    pub fn send_attachment(&self, text: impl Into<String>, attachment_id: Uuid) -> Result<(), ClientError> {
+        self.send_incoming(IncomingChatMessage {
+            message: text.into(),
+            attachment_id: Some(attachment_id),
+        })
+    }
+
+    /// Post a `/me` action message
+    pub fn send_action(&self, text: &str) -> Result<(), ClientError> {
+        self.send_command(&format!("/me {}", text))
+    }
+
+    /// Send a private message to `username`
+    pub fn direct_message(&self, username: &str, text: &str) -> Result<(), ClientError> {
+        self.send_command(&format!("/dm {} {}", username, text))
+    }
+
+    /// Switch to another room, which later reconnects rejoin
+    pub fn join(&self, room: &str) -> Result<(), ClientError> {
+        self.send_command(&format!("/join {}", room))
+    }
+
+    /// Leave the current room; reconnects rejoin the room that was left
+    pub fn leave(&self) -> Result<(), ClientError> {
+        self.send_command("/leave")
+    }
+
+    /// Run a slash command such as `/topic New topic`; the leading slash is optional
+    pub fn send_command(&self, command: &str) -> Result<(), ClientError> {
+        match command.strip_prefix('/') {
+            Some(_) => self.send_frame(command.to_string()),
+            None => self.send_frame(format!("/{}", command)),
+        }
+    }
+
+    /// Close the connection for good; the event stream ends afterwards
+    pub fn close(&self) -> Result<(), ClientError> {
+        self.commands.send(Command::Close).map_err(|_| ClientError::Closed)
+    }
+}
+
+/// A connection to a chat room that stays up until closed
+///
+/// The client is a [`Stream`] of [`ChatEvent`]s; messages are sent through a
+/// [`ChatSender`], which can be cloned into other tasks. Dropping the client closes
+/// the connection.
+pub struct ChatClient {
+    sender: ChatSender,
+    events: mpsc::UnboundedReceiver<ChatEvent>,
+    task: JoinHandle<()>,
+}
+
+impl ChatClient {
+    pub fn builder(server_url: impl Into<String>) -> ChatClientBuilder {
+        ChatClientBuilder {
+            server_url: server_url.into(),
+            credentials: None,
+            room: DEFAULT_ROOM.to_string(),
+            since: None,
+            min_backoff: DEFAULT_MIN_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+        }
+    }
+
+    pub fn sender(&self) -> ChatSender {
+        self.sender.clone()
+    }
+}
+
+impl Stream for ChatClient {
+    type Item = ChatEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<ChatEvent>> {
+        self.events.poll_recv(cx)
+    }
+}
+
+impl Drop for ChatClient {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Why a connection ended
+enum Ended {
+    Closed,
+    Dropped(String),
+}
+
+/// State of the background task that owns the socket
+struct Connection {
+    http: reqwest::Client,
+    server_url: String,
+    credentials: Credentials,
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+    room: String,
+    /// Set between sending `/join` or `/leave` and the server confirming the switch,
+    /// while messages cannot be attributed to a room
+    switching_room: bool,
+    /// Last event sequence number seen per room, used to resume after reconnecting
+    last_seq: HashMap<String, u64>,
+    min_backoff: Duration,
+    max_backoff: Duration,
+    /// Frames to send once connected again
+    pending: VecDeque<String>,
+    events: mpsc::UnboundedSender<ChatEvent>,
+    commands: mpsc::UnboundedReceiver<Command>,
+}
+
+impl Connection {
+    async fn run(mut self, socket: WsStream) {
+        let mut socket = Some(socket);
+        let mut backoff = self.min_backoff;
+        loop {
+            let reason = match socket.take() {
+                Some(socket) => {
+                    backoff = self.min_backoff;
+                    self.emit(ChatEvent::Connected { room: self.room.clone() });
+                    match self.pump(socket).await {
+                        Ended::Closed => return,
+                        Ended::Dropped(reason) => reason,
+                    }
+                }
+                None => match self.open().await {
+                    Ok(opened) => {
+                        socket = Some(opened);
+                        continue;
+                    }
+                    Err(e) if e.is_fatal() => {
+                        self.emit(ChatEvent::Disconnected {
+                            reason: e.to_string(),
+                            retry_in: None,
+                        });
+                        return;
+                    }
+                    Err(e) => e.to_string(),
+                },
+            };
+
+            let delay = with_jitter(backoff);
+            backoff = (backoff * 2).min(self.max_backoff);
+            self.emit(ChatEvent::Disconnected {
+                reason,
+                retry_in: Some(delay),
+            });
+            if !self.wait(delay).await {
+                return;
+            }
+        }
+    }
+
+    fn emit(&self, event: ChatEvent) {
+        let _ = self.events.send(event);
+    }
+
+    /// Sleep before reconnecting, queueing anything sent meanwhile; false once closed
+    async fn wait(&mut self, delay: Duration) -> bool {
+        let sleep = tokio::time::sleep(delay);
+        tokio::pin!(sleep);
+        loop {
+            tokio::select! {
+                _ = &mut sleep => return true,
+                command = self.commands.recv() => match command {
+                    Some(Command::Send(frame)) => self.queue(frame),
+                    Some(Command::Close) | None => return false,
+                },
+            }
+        }
+    }
+
+    fn queue(&mut self, frame: String) {
+        if is_room_switch(&frame) {
+            self.switching_room = true;
+        }
+        self.pending.push_back(frame);
+    }
+
+    async fn open(&mut self) -> Result<WsStream, ClientError> {
+        let ticket = self.ticket().await?;
+        let since = self.last_seq.get(&self.room).copied();
+        let url = websocket_url(&self.server_url, &ticket, &self.room, since)?;
+        let (socket, _) = connect_async(url).await?;
+        Ok(socket)
+    }
+
+    async fn ticket(&mut self) -> Result<String, ClientError> {
+        let url = endpoint(&parse_server_url(&self.server_url)?, &["api", "chat", "ticket"]);
+        let mut refreshed = false;
+        loop {
+            let token = self.access_token().await?;
+            let response = self.http.post(url.clone()).bearer_auth(token).send().await?;
+            if response.status() == StatusCode::UNAUTHORIZED && !refreshed {
+                self.refresh().await?;
+                refreshed = true;
+                continue;
+            }
+            return Ok(rejected_as_unauthorized(response).await?.json::<ChatTicketResponse>().await?.ticket);
+        }
+    }
+
+    async fn access_token(&mut self) -> Result<String, ClientError> {
+        if self.access_token.is_none() {
+            self.login().await?;
+        }
+        self.access_token.clone().ok_or(ClientError::Closed)
+    }
+
+    async fn login(&mut self) -> Result<(), ClientError> {
+        let Credentials::Password { email, password } = &self.credentials else {
+            return Err(ClientError::Unauthorized("The access token has expired".to_string()));
+        };
+        let url = endpoint(&parse_server_url(&self.server_url)?, &["auth", "login"]);
+        let body = LoginDto {
+            email: email.clone(),
+            password: password.clone(),
+        };
+        let response = self.http.post(url).json(&body).send().await?;
+        let tokens = rejected_as_unauthorized(response).await?.json::<TokenResponse>().await?;
+        self.access_token = Some(tokens.token);
+        self.refresh_token = Some(tokens.refresh_token);
+        Ok(())
+    }
+
+    /// Get a new access token, logging in again when the refresh token no longer works
+    async fn refresh(&mut self) -> Result<(), ClientError> {
+        if let Some(refresh_token) = self.refresh_token.clone() {
+            let url = endpoint(&parse_server_url(&self.server_url)?, &["auth", "refresh-token"]);
+            let response = self.http.post(url).json(&RefreshTokenDto { refresh_token }).send().await?;
+            if response.status() != StatusCode::UNAUTHORIZED {
+                let tokens = response.error_for_status()?.json::<TokenResponse>().await?;
+                self.access_token = Some(tokens.token);
+                self.refresh_token = Some(tokens.refresh_token);
+                return Ok(());
+            }
+            self.refresh_token = None;
+        }
+        self.access_token = None;
+        self.login().await
+    }
+
+    async fn pump(&mut self, socket: WsStream) -> Ended {
+        let (mut sink, mut stream) = socket.split();
+        while let Some(frame) = self.pending.pop_front() {
+            if let Err(e) = sink.send(TungsteniteMessage::Text(frame.clone())).await {
+                self.pending.push_front(frame);
+                return Ended::Dropped(e.to_string());
+            }
+        }
+
+        loop {
+            let frame = tokio::select! {
+                message = stream.next() => match message {
+                    Some(Ok(TungsteniteMessage::Text(text))) => text,
+                    Some(Ok(TungsteniteMessage::Close(frame))) => {
+                        return Ended::Dropped(match frame {
+                            Some(frame) if !frame.reason.is_empty() => frame.reason.into_owned(),
+                            _ => "Connection closed by the server".to_string(),
+                        });
+                    }
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Ended::Dropped(e.to_string()),
+                    None => return Ended::Dropped("Connection lost".to_string()),
+                },
+                command = self.commands.recv() => match command {
+                    Some(Command::Send(frame)) => {
+                        if is_room_switch(&frame) {
+                            self.switching_room = true;
+                        }
+                        if let Err(e) = sink.send(TungsteniteMessage::Text(frame.clone())).await {
+                            self.pending.push_back(frame);
+                            return Ended::Dropped(e.to_string());
+                        }
+                        continue;
+                    }
+                    Some(Command::Close) | None => {
+                        let _ = sink.send(TungsteniteMessage::Close(None)).await;
+                        return Ended::Closed;
+                    }
+                },
+            };
+
+            let seq = frame_seq(&frame);
+            if let (Some(seq), false) = (seq, self.switching_room) {
+                self.last_seq.insert(self.room.clone(), seq);
+            }
+            let event = ChatEvent::parse(&frame);
+            match &event {
+                // Every join is confirmed with the new room's topic, sent to this connection only
+                ChatEvent::Topic(topic) if self.switching_room && seq.is_none() => {
+                    self.room = topic.room_name.clone();
+                    self.switching_room = false;
+                }
+                ChatEvent::Error(_) => self.switching_room = false,
+                ChatEvent::ReauthRequired(_) => {
+                    if let Err(e) = self.reauthenticate(&mut sink).await {
+                        eprintln!("Failed to reauthenticate chat session: {}", e);
+                    }
+                }
+                _ => {}
+            }
+            self.emit(event);
+        }
+    }
+
+    async fn reauthenticate<S>(&mut self, sink: &mut S) -> Result<(), ClientError>
+    where
+        S: futures::Sink<TungsteniteMessage, Error = tungstenite::Error> + Unpin,
+    {
+        self.refresh().await?;
+        let token = self.access_token().await?;
+        let frame = format!("reauth:{}", serde_json::json!({ "token": token }));
+        sink.send(TungsteniteMessage::Text(frame)).await?;
+        Ok(())
+    }
+}
+
+fn is_room_switch(frame: &str) -> bool {
+    frame.starts_with("/join ") || frame == "/leave"
+}
+
+/// Room sequence number of a frame broadcast to the room; replies to this connection have none
+fn frame_seq(frame: &str) -> Option<u64> {
+    #[derive(serde::Deserialize)]
+    struct Sequenced {
+        seq: Option<u64>,
+    }
+
+    let json = &frame[frame.find('{')?..];
+    serde_json::from_str::<Sequenced>(json).ok()?.seq
+}
+
+/// 401 and 403 responses as [`ClientError::Unauthorized`], other failures as HTTP errors
+async fn rejected_as_unauthorized(response: reqwest::Response) -> Result<reqwest::Response, ClientError> {
+    match response.status() {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            Err(ClientError::Unauthorized(response.text().await.unwrap_or_default()))
+        }
+        _ => Ok(response.error_for_status()?),
+    }
+}
+
+/// `delay` plus up to a quarter more, so clients dropped together don't reconnect together
+fn with_jitter(delay: Duration) -> Duration {
+    let quarter = delay.as_millis() as u64 / 4;
+    if quarter == 0 {
+        return delay;
+    }
+    delay + Duration::from_millis((Uuid::new_v4().as_u128() % u128::from(quarter)) as u64)
+}
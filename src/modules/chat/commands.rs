@@ -0,0 +1,409 @@
+//! IRC-style slash commands for the chat protocol
+use std::collections::BTreeMap;
+
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::modules::chat::dto::poll_dto::CreatePollDto;
+use crate::modules::chat::server::ChatState;
+use crate::modules::chat::service::scheduled_message_service::MAX_SCHEDULE_AHEAD;
+
+/// Everything a command can see about the connection that issued it
+pub struct CommandContext<'a> {
+    pub state: &'a ChatState,
+    pub user_id: Uuid,
+    pub username: &'a str,
+    pub room_name: Option<&'a str>,
+}
+
+/// What the connection should do after a command has run
+#[derive(Debug, PartialEq)]
+pub enum CommandAction {
+    /// Send a system message to the issuing connection only
+    Reply(String),
+    /// Broadcast an action message (`/me`) to the current room
+    Emote(String),
+    /// Show the topic of the current room
+    ShowTopic,
+    /// Change the topic of the current room
+    SetTopic(String),
+    /// Move the connection into another room
+    Join(String),
+    /// Leave the current room without joining another one
+    Leave,
+    /// Send a private message to a connected user
+    DirectMessage { to: String, message: String },
+    /// Start a poll in the current room
+    CreatePoll(CreatePollDto),
+    /// Vote in a poll of the current room, the newest open one unless a poll ID prefix is given
+    Vote { poll: Option<String>, options: Vec<usize> },
+    /// Post a message to the current room once the delay has passed
+    Schedule { delay: time::Duration, message: String },
+}
+
+/// A slash command that can be registered with a [`CommandRegistry`]
+pub trait ChatCommand: Send + Sync {
+    /// Name used to invoke the command, without the leading slash
+    fn name(&self) -> &'static str;
+
+    /// Short usage line shown by `/help`
+    fn usage(&self) -> &'static str;
+
+    /// Run the command; an `Err` is reported back to the sender only
+    fn execute(&self, ctx: &CommandContext<'_>, args: &str) -> Result<CommandAction, String>;
+}
+
+/// A parsed `/name args` input line
+#[derive(Debug, PartialEq)]
+pub struct ParsedCommand<'a> {
+    pub name: String,
+    pub args: &'a str,
+}
+
+/// Parse a slash command out of a text frame.
+///
+/// Returns `None` for ordinary chat text, including lines starting with `//`,
+/// which clients use to send a literal leading slash.
+pub fn parse_command(text: &str) -> Option<ParsedCommand<'_>> {
+    let rest = text.strip_prefix('/')?;
+    if rest.starts_with('/') {
+        return None;
+    }
+
+    let (name, args) = match rest.split_once(char::is_whitespace) {
+        Some((name, args)) => (name, args.trim()),
+        None => (rest, ""),
+    };
+    if name.is_empty() {
+        return None;
+    }
+
+    Some(ParsedCommand {
+        name: name.to_lowercase(),
+        args,
+    })
+}
+
+/// Registry of available slash commands, keyed by name
+pub struct CommandRegistry {
+    commands: BTreeMap<&'static str, Box<dyn ChatCommand>>,
+}
+
+impl std::fmt::Debug for CommandRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommandRegistry")
+            .field("commands", &self.commands.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        Self::with_builtin_commands()
+    }
+}
+
+impl CommandRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            commands: BTreeMap::new(),
+        }
+    }
+
+    /// Create a registry with all built-in commands
+    pub fn with_builtin_commands() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(MeCommand));
+        registry.register(Box::new(WhoCommand));
+        registry.register(Box::new(TopicCommand));
+        registry.register(Box::new(JoinCommand));
+        registry.register(Box::new(LeaveCommand));
+        registry.register(Box::new(DmCommand));
+        registry.register(Box::new(PollCommand));
+        registry.register(Box::new(VoteCommand));
+        registry.register(Box::new(ScheduleCommand));
+        registry
+    }
+
+    /// Add a command, replacing any existing command with the same name
+    pub fn register(&mut self, command: Box<dyn ChatCommand>) {
+        self.commands.insert(command.name(), command);
+    }
+
+    /// Run a parsed command against the registry
+    pub fn dispatch(&self, ctx: &CommandContext<'_>, command: &ParsedCommand<'_>) -> Result<CommandAction, String> {
+        if command.name == "help" {
+            let usage: Vec<&str> = self.commands.values().map(|command| command.usage()).collect();
+            return Ok(CommandAction::Reply(format!("Available commands: /help, {}", usage.join(", "))));
+        }
+
+        match self.commands.get(command.name.as_str()) {
+            Some(handler) => handler.execute(ctx, command.args),
+            None => Err(format!("Unknown command: /{}. Type /help for a list of commands.", command.name)),
+        }
+    }
+}
+
+fn require_room<'a>(ctx: &CommandContext<'a>) -> Result<&'a str, String> {
+    ctx.room_name
+        .ok_or_else(|| "You are not in a room. Use /join <room> first.".to_string())
+}
+
+struct MeCommand;
+
+impl ChatCommand for MeCommand {
+    fn name(&self) -> &'static str {
+        "me"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/me <action>"
+    }
+
+    fn execute(&self, ctx: &CommandContext<'_>, args: &str) -> Result<CommandAction, String> {
+        require_room(ctx)?;
+        if args.is_empty() {
+            return Err(format!("Usage: {}", self.usage()));
+        }
+        Ok(CommandAction::Emote(args.to_string()))
+    }
+}
+
+struct WhoCommand;
+
+impl ChatCommand for WhoCommand {
+    fn name(&self) -> &'static str {
+        "who"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/who"
+    }
+
+    fn execute(&self, ctx: &CommandContext<'_>, _args: &str) -> Result<CommandAction, String> {
+        let room_name = require_room(ctx)?;
+        let usernames = ctx.state.usernames_in_room(room_name);
+        Ok(CommandAction::Reply(format!("Users in {}: {}", room_name, usernames.join(", "))))
+    }
+}
+
+struct TopicCommand;
+
+impl ChatCommand for TopicCommand {
+    fn name(&self) -> &'static str {
+        "topic"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/topic [text]"
+    }
+
+    fn execute(&self, ctx: &CommandContext<'_>, args: &str) -> Result<CommandAction, String> {
+        require_room(ctx)?;
+        if args.is_empty() {
+            return Ok(CommandAction::ShowTopic);
+        }
+        Ok(CommandAction::SetTopic(args.to_string()))
+    }
+}
+
+struct JoinCommand;
+
+impl ChatCommand for JoinCommand {
+    fn name(&self) -> &'static str {
+        "join"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/join <room>"
+    }
+
+    fn execute(&self, ctx: &CommandContext<'_>, args: &str) -> Result<CommandAction, String> {
+        let room_name = args.strip_prefix('#').unwrap_or(args);
+        if room_name.is_empty() || room_name.contains(char::is_whitespace) || room_name.len() > 255 {
+            return Err(format!("Usage: {}", self.usage()));
+        }
+        if ctx.room_name == Some(room_name) {
+            return Err(format!("You are already in {}", room_name));
+        }
+        Ok(CommandAction::Join(room_name.to_string()))
+    }
+}
+
+struct LeaveCommand;
+
+impl ChatCommand for LeaveCommand {
+    fn name(&self) -> &'static str {
+        "leave"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/leave"
+    }
+
+    fn execute(&self, ctx: &CommandContext<'_>, _args: &str) -> Result<CommandAction, String> {
+        require_room(ctx)?;
+        Ok(CommandAction::Leave)
+    }
+}
+
+struct DmCommand;
+
+impl ChatCommand for DmCommand {
+    fn name(&self) -> &'static str {
+        "dm"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/dm <user> <text>"
+    }
+
+    fn execute(&self, ctx: &CommandContext<'_>, args: &str) -> Result<CommandAction, String> {
+        let (to, message) = args
+            .split_once(char::is_whitespace)
+            .map(|(to, message)| (to, message.trim()))
+            .filter(|(_, message)| !message.is_empty())
+            .ok_or_else(|| format!("Usage: {}", self.usage()))?;
+        let to = to.strip_prefix('@').unwrap_or(to);
+        if to.eq_ignore_ascii_case(ctx.username) {
+            return Err("You cannot send a direct message to yourself".to_string());
+        }
+        Ok(CommandAction::DirectMessage {
+            to: to.to_string(),
+            message: message.to_string(),
+        })
+    }
+}
+
+struct PollCommand;
+
+impl ChatCommand for PollCommand {
+    fn name(&self) -> &'static str {
+        "poll"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/poll [--multi] [--anonymous] [--minutes <n>] <question> | <option> | <option>..."
+    }
+
+    fn execute(&self, ctx: &CommandContext<'_>, args: &str) -> Result<CommandAction, String> {
+        require_room(ctx)?;
+        let usage = || format!("Usage: {}", self.usage());
+
+        let mut poll = CreatePollDto {
+            question: String::new(),
+            options: Vec::new(),
+            multiple_choice: false,
+            anonymous: false,
+            closes_at: None,
+        };
+        let mut rest = args;
+        while let Some(flagged) = rest.strip_prefix("--") {
+            let (flag, after) = flagged.split_once(char::is_whitespace).unwrap_or((flagged, ""));
+            rest = after.trim_start();
+            match flag {
+                "multi" => poll.multiple_choice = true,
+                "anonymous" => poll.anonymous = true,
+                "minutes" => {
+                    let (minutes, after) = rest.split_once(char::is_whitespace).ok_or_else(usage)?;
+                    let minutes: u32 = minutes.parse().ok().filter(|minutes| *minutes > 0).ok_or_else(usage)?;
+                    let closes_at = i32::try_from(minutes)
+                        .ok()
+                        .and_then(|minutes| time::Duration::minutes(1).checked_mul(minutes))
+                        .and_then(|duration| OffsetDateTime::now_utc().checked_add(duration))
+                        .ok_or_else(usage)?;
+                    poll.closes_at = Some(closes_at);
+                    rest = after.trim_start();
+                }
+                _ => return Err(usage()),
+            }
+        }
+
+        let mut parts = rest.split('|').map(str::trim);
+        poll.question = parts.next().unwrap_or_default().to_string();
+        poll.options = parts.map(str::to_string).collect();
+        if poll.question.is_empty() || poll.options.len() < 2 {
+            return Err(usage());
+        }
+        Ok(CommandAction::CreatePoll(poll))
+    }
+}
+
+struct VoteCommand;
+
+impl ChatCommand for VoteCommand {
+    fn name(&self) -> &'static str {
+        "vote"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/vote [poll-id] <option>[,<option>...]"
+    }
+
+    fn execute(&self, ctx: &CommandContext<'_>, args: &str) -> Result<CommandAction, String> {
+        require_room(ctx)?;
+        let (poll, choices) = match args.split_once(char::is_whitespace) {
+            Some((poll, choices)) => (Some(poll.to_string()), choices.trim()),
+            None => (None, args),
+        };
+
+        // Options are numbered from 1 in chat
+        let options = choices
+            .split(',')
+            .map(|choice| choice.trim().parse::<usize>().ok().filter(|choice| *choice > 0).map(|choice| choice - 1))
+            .collect::<Option<Vec<_>>>()
+            .filter(|options| !options.is_empty())
+            .ok_or_else(|| format!("Usage: {}", self.usage()))?;
+        Ok(CommandAction::Vote { poll, options })
+    }
+}
+
+/// Parse a delay such as `90s`, `15m`, `2h`, `1d` or `1h30m`
+pub fn parse_delay(delay: &str) -> Option<time::Duration> {
+    let mut total = time::Duration::ZERO;
+    let mut rest = delay;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let amount: i64 = rest[..digits].parse().ok()?;
+        let unit = match rest[digits..].chars().next()? {
+            's' => time::Duration::seconds(1),
+            'm' => time::Duration::minutes(1),
+            'h' => time::Duration::hours(1),
+            'd' => time::Duration::days(1),
+            _ => return None,
+        };
+        total = total.checked_add(unit.checked_mul(i32::try_from(amount).ok()?)?)?;
+        rest = &rest[digits + 1..];
+    }
+    total.is_positive().then_some(total)
+}
+
+struct ScheduleCommand;
+
+impl ChatCommand for ScheduleCommand {
+    fn name(&self) -> &'static str {
+        "schedule"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/schedule <delay, e.g. 30m or 1h30m> <message>"
+    }
+
+    fn execute(&self, ctx: &CommandContext<'_>, args: &str) -> Result<CommandAction, String> {
+        require_room(ctx)?;
+        let usage = || format!("Usage: {}", self.usage());
+        let (delay, message) = args.split_once(char::is_whitespace).ok_or_else(usage)?;
+        let delay = parse_delay(&delay.to_lowercase())
+            .filter(|delay| *delay <= MAX_SCHEDULE_AHEAD)
+            .ok_or_else(usage)?;
+        let message = message.trim();
+        if message.is_empty() {
+            return Err(usage());
+        }
+        Ok(CommandAction::Schedule {
+            delay,
+            message: message.to_string(),
+        })
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A server-wide announcement, delivered with the "announcement:" prefix
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AnnouncementResponse {
+    pub id: String,
+    #[schema(example = "The server restarts for maintenance at 22:00 UTC")]
+    pub message: String,
+    /// Administrator who made the announcement
+    #[schema(example = "User_1a2b3c4d")]
+    pub author: String,
+    /// Milliseconds since the Unix epoch
+    #[schema(example = 1234567890)]
+    pub timestamp: u64,
+    /// Whether connections joining a room later receive it too
+    pub banner: bool,
+    /// Milliseconds since the Unix epoch after which the banner is no longer sent;
+    /// `null` keeps it until it is cleared or replaced
+    #[schema(example = 1234567890)]
+    pub expires_at: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateAnnouncementDto {
+    #[schema(example = "The server restarts for maintenance at 22:00 UTC")]
+    pub message: String,
+    /// Keep the announcement as a banner for connections joining a room later,
+    /// replacing the current one
+    #[serde(default)]
+    pub banner: bool,
+    /// Seconds the banner is kept; without it the banner stays until cleared
+    #[schema(example = 3600)]
+    pub expires_in: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AnnouncementDeliveryResponse {
+    pub announcement: AnnouncementResponse,
+    /// Connections the announcement was sent to
+    #[schema(example = 42)]
+    pub delivered: usize,
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AttachmentResponse {
+    pub id: String,
+    #[schema(example = "general")]
+    pub room_name: String,
+    #[schema(example = "report.pdf")]
+    pub file_name: String,
+    #[schema(example = "application/pdf")]
+    pub content_type: String,
+    #[schema(example = 52344)]
+    pub size_bytes: i64,
+    pub sha256: String,
+}
+
+/// Multipart form accepted by the attachment upload endpoint
+#[derive(ToSchema)]
+pub struct UploadAttachmentForm {
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BlockedUserResponse {
+    pub user_id: String,
+    #[schema(example = "User_1a2b3c4d")]
+    pub username: String,
+    #[schema(example = 1234567890)]
+    pub blocked_at: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BlockListResponse {
+    pub blocked: Vec<BlockedUserResponse>,
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct CreateInviteDto {
+    /// RFC 3339 time after which the invite can no longer be accepted
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    #[schema(value_type = Option<String>, example = "2025-01-08T00:00:00Z")]
+    pub expires_at: Option<OffsetDateTime>,
+    /// How many users can join with the invite; unlimited when omitted
+    #[schema(example = 10)]
+    pub max_uses: Option<i32>,
+    /// Role given to users joining with the invite, `member` (default) or `moderator`
+    #[schema(example = "member")]
+    pub role: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InviteResponse {
+    /// Code to share; accepted with `POST /api/invites/{code}/accept`
+    #[schema(example = "3f9c2a1b7d6e4f08a5b1c2d3e4f5a6b7")]
+    pub code: String,
+    #[schema(example = "general")]
+    pub room_name: String,
+    #[schema(example = "member")]
+    pub role: String,
+    pub max_uses: Option<i32>,
+    /// Users who joined with the invite so far
+    pub uses: i32,
+    #[schema(example = 1234567890)]
+    pub expires_at: Option<u64>,
+    pub created_by: Option<String>,
+    #[schema(example = 1234567890)]
+    pub created_at: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InviteListResponse {
+    pub invites: Vec<InviteResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AcceptInviteResponse {
+    #[schema(example = "general")]
+    pub room_name: String,
+    /// The caller's role in the room
+    #[schema(example = "member")]
+    pub role: String,
+    /// Whether the caller was already a member, in which case the invite was not used
+    pub already_member: bool,
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SearchMessagesQuery {
+    /// Search terms; supports quoted phrases, `or` and `-excluded` words
+    pub q: String,
+    /// Only search this room
+    pub room: Option<String>,
+    /// Only return messages written by this user ID
+    pub author: Option<Uuid>,
+    /// Only return messages sent at or after this RFC 3339 time
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    #[param(value_type = Option<String>)]
+    pub from: Option<OffsetDateTime>,
+    /// Only return messages sent before this RFC 3339 time
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    #[param(value_type = Option<String>)]
+    pub to: Option<OffsetDateTime>,
+    /// Maximum number of results (default 20, at most 100)
+    pub limit: Option<i64>,
+    /// Number of results to skip
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MessageSearchResult {
+    pub id: String,
+    #[schema(example = "general")]
+    pub room_name: String,
+    pub user_id: String,
+    #[schema(example = "User_1a2b3c4d")]
+    pub username: String,
+    #[schema(example = "The deploy failed on staging again")]
+    pub message: String,
+    /// HTML excerpt with matching terms wrapped in `<mark>` tags and the message text escaped
+    #[schema(example = "The <mark>deploy</mark> failed on staging again")]
+    pub snippet: String,
+    pub rank: f32,
+    #[schema(example = 1234567890)]
+    pub created_at: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MessageSearchResponse {
+    pub results: Vec<MessageSearchResult>,
+    pub limit: i64,
+    pub offset: i64,
+    /// Whether another page of results is available
+    pub has_more: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ExportTranscriptQuery {
+    /// `json` (default), `csv` or `txt`
+    pub format: Option<String>,
+    /// Only export messages sent at or after this RFC 3339 time
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    #[param(value_type = Option<String>)]
+    pub from: Option<OffsetDateTime>,
+    /// Only export messages sent before this RFC 3339 time
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    #[param(value_type = Option<String>)]
+    pub to: Option<OffsetDateTime>,
+}
@@ -0,0 +1,13 @@
+pub mod announcement_dto;
+pub mod attachment_dto;
+pub mod block_dto;
+pub mod invite_dto;
+pub mod message_dto;
+pub mod moderation_dto;
+pub mod notification_dto;
+pub mod poll_dto;
+pub mod retention_dto;
+pub mod room_dto;
+pub mod scheduled_message_dto;
+pub mod ticket_dto;
+pub mod webhook_dto;
\ No newline at end of file
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateModerationRuleDto {
+    /// One of `word`, `regex` or `link_domain`
+    #[schema(example = "link_domain")]
+    pub kind: String,
+    /// The word, regular expression or domain to match
+    #[schema(example = "spam.example.com")]
+    pub pattern: String,
+    /// One of `mask`, `reject` or `flag`
+    #[schema(example = "reject")]
+    pub action: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ModerationRuleResponse {
+    pub id: String,
+    #[schema(example = "general")]
+    pub room_name: String,
+    #[schema(example = "link_domain")]
+    pub kind: String,
+    #[schema(example = "spam.example.com")]
+    pub pattern: String,
+    #[schema(example = "reject")]
+    pub action: String,
+    pub created_by: Option<String>,
+    #[schema(example = 1234567890)]
+    pub created_at: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FlaggedMessageResponse {
+    pub id: String,
+    #[schema(example = "general")]
+    pub room_name: String,
+    pub user_id: String,
+    #[schema(example = "User_1a2b3c4d")]
+    pub username: String,
+    #[schema(example = "Cheap watches at http://spam.example.com")]
+    pub message: String,
+    pub attachment_id: Option<String>,
+    /// Why the filters flagged the message
+    pub reasons: Vec<String>,
+    /// One of `pending`, `approved` or `rejected`
+    #[schema(example = "pending")]
+    pub status: String,
+    pub reviewed_by: Option<String>,
+    /// ID of the chat message posted when the message was approved
+    pub message_id: Option<String>,
+    #[schema(example = 1234567890)]
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ModerationQueueQuery {
+    /// Only list messages with this status (default `pending`)
+    pub status: Option<String>,
+}
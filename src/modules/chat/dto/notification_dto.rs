@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Notification, also delivered over the WebSocket with the "<kind>:" prefix, e.g. "mention:"
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NotificationResponse {
+    pub id: String,
+    #[schema(example = "mention")]
+    pub kind: String,
+    #[schema(example = "general")]
+    pub room_name: String,
+    pub message_id: Option<String>,
+    pub actor_id: Option<String>,
+    #[schema(example = "User_1a2b3c4d")]
+    pub actor_username: String,
+    #[schema(example = "@alice can you take a look?")]
+    pub message: String,
+    #[schema(example = 1234567890)]
+    pub timestamp: u64,
+    pub read: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NotificationListResponse {
+    pub notifications: Vec<NotificationResponse>,
+    /// Total number of unread notifications
+    pub unread_count: i64,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListNotificationsQuery {
+    /// Only return unread notifications
+    pub unread_only: Option<bool>,
+    /// Maximum number of notifications (default 50, at most 100)
+    pub limit: Option<i64>,
+    /// Number of notifications to skip
+    pub offset: Option<i64>,
+}
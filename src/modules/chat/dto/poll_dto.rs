@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CreatePollDto {
+    #[schema(example = "Where should we have the team lunch?")]
+    pub question: String,
+    #[schema(example = json!(["Pizza", "Sushi", "Tacos"]))]
+    pub options: Vec<String>,
+    /// Allow voting for more than one option
+    #[serde(default)]
+    pub multiple_choice: bool,
+    /// Hide who voted for which option
+    #[serde(default)]
+    pub anonymous: bool,
+    /// RFC 3339 time at which the poll closes automatically
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    #[schema(value_type = Option<String>, example = "2025-01-01T12:00:00Z")]
+    pub closes_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VotePollDto {
+    /// Indexes of the chosen options, starting at 0; replaces any earlier vote
+    #[schema(example = json!([1]))]
+    pub options: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PollOptionResponse {
+    pub index: usize,
+    #[schema(example = "Sushi")]
+    pub text: String,
+    pub votes: i64,
+    /// Display names of the voters; omitted for anonymous polls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voters: Option<Vec<String>>,
+}
+
+/// A poll with its current tallies, also delivered over the WebSocket with the "poll:" prefix
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PollResponse {
+    pub id: String,
+    #[schema(example = "general")]
+    pub room_name: String,
+    #[schema(example = "Where should we have the team lunch?")]
+    pub question: String,
+    pub options: Vec<PollOptionResponse>,
+    pub multiple_choice: bool,
+    pub anonymous: bool,
+    pub created_by: Option<String>,
+    #[schema(example = 1234567890)]
+    pub closes_at: Option<u64>,
+    pub closed: bool,
+    /// Number of members who voted
+    pub total_voters: i64,
+    #[schema(example = 1234567890)]
+    pub created_at: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PollListResponse {
+    pub polls: Vec<PollResponse>,
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RetentionPolicyResponse {
+    #[schema(example = "general")]
+    pub room_name: String,
+    /// Days messages are kept in this room; `null` uses the server default and 0 keeps them forever
+    #[schema(example = 90)]
+    pub retention_days: Option<i32>,
+    /// Days after which messages are actually purged, taking the server default and
+    /// legal hold into account; `null` when messages are kept forever
+    #[schema(example = 90)]
+    pub effective_retention_days: Option<u32>,
+    /// Rooms under legal hold are never purged
+    pub legal_hold: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SetRetentionPolicyDto {
+    /// Days to keep messages; `null` uses the server default and 0 keeps them forever
+    #[schema(example = 90)]
+    pub retention_days: Option<i32>,
+    /// Place or lift a legal hold; admins only, left unchanged when omitted
+    pub legal_hold: Option<bool>,
+}
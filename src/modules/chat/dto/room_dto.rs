@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Room topic, also delivered over the WebSocket with the "topic:" prefix
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RoomTopicResponse {
+    #[schema(example = "general")]
+    pub room_name: String,
+    #[schema(example = "Release day - please keep the channel focused")]
+    pub topic: Option<String>,
+    pub set_by: Option<String>,
+    /// Milliseconds since the Unix epoch
+    #[schema(example = 1234567890123u64)]
+    pub set_at: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SetTopicDto {
+    #[schema(example = "Release day - please keep the channel focused")]
+    pub topic: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PinnedMessageResponse {
+    pub message_id: String,
+    pub user_id: String,
+    #[schema(example = "User_1a2b3c4d")]
+    pub username: String,
+    #[schema(example = "Deploy checklist: https://wiki.example.com/deploy")]
+    pub message: String,
+    #[schema(example = 1234567890)]
+    pub timestamp: u64,
+    pub pinned_by: Option<String>,
+    #[schema(example = 1234567890)]
+    pub pinned_at: u64,
+}
+
+/// Pinned messages of a room, also delivered over the WebSocket with the "pins:" prefix
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PinnedMessagesResponse {
+    #[schema(example = "general")]
+    pub room_name: String,
+    pub pinned: Vec<PinnedMessageResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PinMessageDto {
+    pub message_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SetMemberRoleDto {
+    /// One of `moderator` or `member`
+    #[schema(example = "moderator")]
+    pub role: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SetRoomPrivacyDto {
+    /// Private rooms admit only their members; others need an invite
+    pub private: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RoomPrivacyResponse {
+    #[schema(example = "general")]
+    pub room_name: String,
+    pub private: bool,
+}
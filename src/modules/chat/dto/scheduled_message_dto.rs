@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleMessageDto {
+    #[schema(example = "Standup starts in 5 minutes!")]
+    pub message: String,
+    /// RFC 3339 time at which the message is posted
+    #[serde(with = "time::serde::rfc3339")]
+    #[schema(value_type = String, example = "2025-01-01T09:55:00Z")]
+    pub send_at: OffsetDateTime,
+    /// Attachment already shared in the room
+    pub attachment_id: Option<Uuid>,
+    /// Post the message as a `/me` action
+    #[serde(default)]
+    pub action: bool,
+}
+
+/// Fields of a pending scheduled message to change; omitted fields are kept
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateScheduledMessageDto {
+    #[schema(example = "Standup starts in 10 minutes!")]
+    pub message: Option<String>,
+    /// RFC 3339 time at which the message is posted
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    #[schema(value_type = Option<String>, example = "2025-01-01T09:50:00Z")]
+    pub send_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ScheduledMessagesQuery {
+    /// Only list messages scheduled for this room
+    pub room: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScheduledMessageResponse {
+    pub id: String,
+    #[schema(example = "general")]
+    pub room_name: String,
+    #[schema(example = "Standup starts in 5 minutes!")]
+    pub message: String,
+    pub attachment_id: Option<String>,
+    pub action: bool,
+    #[schema(example = 1234567890)]
+    pub send_at: u64,
+    /// One of `pending`, `sending`, `sent`, `held` or `failed`
+    #[schema(example = "pending")]
+    pub status: String,
+    /// Why the message could not be posted, for failed messages
+    pub error: Option<String>,
+    /// ID of the chat message posted, once sent
+    pub message_id: Option<String>,
+    pub sent_at: Option<u64>,
+    #[schema(example = 1234567890)]
+    pub created_at: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScheduledMessageListResponse {
+    pub scheduled_messages: Vec<ScheduledMessageResponse>,
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChatTicketResponse {
+    /// Pass as `?ticket=` when opening `/ws`; valid for a single connection
+    #[schema(example = "wst_4f1c2a9e8b7d4c3a9e1f2b3c4d5e6f7a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6e")]
+    pub ticket: String,
+    /// Seconds until the ticket expires
+    #[schema(example = 30)]
+    pub expires_in: u64,
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateWebhookDto {
+    /// HTTP(S) URL that receives a POST for every room event
+    #[schema(example = "https://incidents.example.com/hooks/chat")]
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookResponse {
+    pub id: String,
+    #[schema(example = "ops")]
+    pub room_name: String,
+    #[schema(example = "https://incidents.example.com/hooks/chat")]
+    pub url: String,
+    /// Signing secret, only returned when the webhook is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub created_by: Option<String>,
+    #[schema(example = 1234567890)]
+    pub created_at: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookDeliveryResponse {
+    pub id: String,
+    pub event_id: String,
+    #[schema(example = "message")]
+    pub event_type: String,
+    /// One of `pending`, `delivered` or `failed`
+    #[schema(example = "delivered")]
+    pub status: String,
+    pub attempts: i32,
+    #[schema(example = 200)]
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    #[schema(example = 1234567890)]
+    pub next_attempt_at: u64,
+    #[schema(example = 1234567890)]
+    pub created_at: u64,
+    #[schema(example = 1234567890)]
+    pub delivered_at: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateIncomingWebhookDto {
+    /// Name shown as the author of messages posted with the webhook
+    #[schema(example = "CI")]
+    pub display_name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct IncomingWebhookResponse {
+    pub id: String,
+    #[schema(example = "ops")]
+    pub room_name: String,
+    #[schema(example = "CI")]
+    pub display_name: String,
+    /// Secret token, only returned when the webhook is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Path to POST messages to, only returned when the webhook is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "/hooks/ihk_0123456789abcdef")]
+    pub url: Option<String>,
+    pub created_by: Option<String>,
+    #[schema(example = 1234567890)]
+    pub created_at: u64,
+    #[schema(example = 1234567890)]
+    pub revoked_at: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct IncomingMessageDto {
+    /// Message text; `text` is accepted as an alias
+    #[serde(alias = "text")]
+    #[schema(example = "Build #1234 failed on main")]
+    pub message: String,
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Debug, FromRow, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: Uuid,
+    pub sha256: String,
+    pub room_name: String,
+    pub uploader_id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl Attachment {
+    pub fn to_response(&self) -> crate::modules::chat::dto::attachment_dto::AttachmentResponse {
+        crate::modules::chat::dto::attachment_dto::AttachmentResponse {
+            id: self.id.to_string(),
+            room_name: self.room_name.clone(),
+            file_name: self.file_name.clone(),
+            content_type: self.content_type.clone(),
+            size_bytes: self.size_bytes,
+            sha256: self.sha256.clone(),
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::modules::chat::dto::block_dto::BlockedUserResponse;
+use crate::modules::chat::server::display_name;
+
+/// A user hidden from another user's connections
+#[derive(Debug, FromRow)]
+pub struct UserBlock {
+    pub blocker_id: Uuid,
+    pub blocked_id: Uuid,
+    pub created_at: OffsetDateTime,
+}
+
+impl UserBlock {
+    pub fn to_response(&self) -> BlockedUserResponse {
+        BlockedUserResponse {
+            user_id: self.blocked_id.to_string(),
+            username: display_name(self.blocked_id),
+            blocked_at: self.created_at.unix_timestamp() as u64,
+        }
+    }
+}
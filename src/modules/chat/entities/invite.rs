@@ -0,0 +1,43 @@
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::modules::chat::dto::invite_dto::InviteResponse;
+
+/// A shareable code that adds whoever accepts it to a room
+#[derive(Debug, FromRow)]
+pub struct RoomInvite {
+    pub code: String,
+    pub room_name: String,
+    pub created_by: Option<Uuid>,
+    /// One of `moderator` or `member`
+    pub role: String,
+    pub max_uses: Option<i32>,
+    pub uses: i32,
+    pub expires_at: Option<OffsetDateTime>,
+    pub revoked_at: Option<OffsetDateTime>,
+    pub created_at: OffsetDateTime,
+}
+
+impl RoomInvite {
+    pub fn is_expired(&self, now: OffsetDateTime) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    pub fn is_used_up(&self) -> bool {
+        self.max_uses.is_some_and(|max_uses| self.uses >= max_uses)
+    }
+
+    pub fn to_response(&self) -> InviteResponse {
+        InviteResponse {
+            code: self.code.clone(),
+            room_name: self.room_name.clone(),
+            role: self.role.clone(),
+            max_uses: self.max_uses,
+            uses: self.uses,
+            expires_at: self.expires_at.map(|expires_at| expires_at.unix_timestamp() as u64),
+            created_by: self.created_by.map(|id| id.to_string()),
+            created_at: self.created_at.unix_timestamp() as u64,
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// A chat message as persisted in room history
+#[derive(Debug, FromRow, Serialize, Deserialize)]
+pub struct MessageRecord {
+    pub id: Uuid,
+    pub room_name: String,
+    /// Sending user; for bot messages the incoming webhook, or nil for plugins
+    pub user_id: Uuid,
+    pub is_bot: bool,
+    pub username: String,
+    pub body: String,
+    pub attachment_id: Option<Uuid>,
+    /// Position of the message in its room, starting at 1
+    pub seq: i64,
+    pub is_action: bool,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl MessageRecord {
+    /// The message as delivered over the WebSocket
+    pub fn to_chat_message(&self) -> crate::modules::chat::server::ChatMessage {
+        crate::modules::chat::server::ChatMessage {
+            id: Some(self.id.to_string()),
+            seq: Some(self.seq as u64),
+            user_id: self.user_id.to_string(),
+            username: self.username.clone(),
+            message: self.body.clone(),
+            timestamp: (self.created_at.unix_timestamp_nanos() / 1_000_000) as u64,
+            attachment_id: self.attachment_id.map(|id| id.to_string()),
+            action: self.is_action,
+            bot: self.is_bot,
+        }
+    }
+}
+
+/// A room event other than a chat message, e.g. a join or a topic change, as persisted
+/// for replay
+#[derive(Debug, FromRow)]
+pub struct RoomEventRecord {
+    /// Position of the event in its room, shared with the room's messages
+    pub seq: i64,
+    /// The frame as it was broadcast
+    pub frame: String,
+}
+
+/// A message matched by a full-text search, with a highlighted excerpt
+#[derive(Debug, FromRow)]
+pub struct MessageSearchHit {
+    pub id: Uuid,
+    pub room_name: String,
+    pub user_id: Uuid,
+    pub username: String,
+    pub body: String,
+    pub snippet: String,
+    pub rank: f32,
+    pub created_at: OffsetDateTime,
+}
+
+impl MessageSearchHit {
+    pub fn to_response(&self) -> crate::modules::chat::dto::message_dto::MessageSearchResult {
+        crate::modules::chat::dto::message_dto::MessageSearchResult {
+            id: self.id.to_string(),
+            room_name: self.room_name.clone(),
+            user_id: self.user_id.to_string(),
+            username: self.username.clone(),
+            message: self.body.clone(),
+            snippet: self.snippet.clone(),
+            rank: self.rank,
+            created_at: self.created_at.unix_timestamp() as u64,
+        }
+    }
+}
+
+/// A persisted message with the details of its attachment, as written to transcripts
+#[derive(Debug, FromRow)]
+pub struct TranscriptMessage {
+    pub id: Uuid,
+    pub seq: i64,
+    pub user_id: Uuid,
+    pub is_bot: bool,
+    pub username: String,
+    pub body: String,
+    pub is_action: bool,
+    pub created_at: OffsetDateTime,
+    pub attachment_id: Option<Uuid>,
+    pub attachment_file_name: Option<String>,
+    pub attachment_content_type: Option<String>,
+    pub attachment_size_bytes: Option<i64>,
+}
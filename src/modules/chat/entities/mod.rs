@@ -0,0 +1,11 @@
+pub mod attachment;
+pub mod block;
+pub mod invite;
+pub mod message;
+pub mod moderation;
+pub mod notification;
+pub mod poll;
+pub mod retention;
+pub mod room;
+pub mod scheduled_message;
+pub mod webhook;
\ No newline at end of file
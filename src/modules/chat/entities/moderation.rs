@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// A room's moderation rule, applied after the server-wide filters
+#[derive(Debug, FromRow, Serialize, Deserialize)]
+pub struct ModerationRule {
+    pub id: Uuid,
+    pub room_name: String,
+    /// One of `word`, `regex` or `link_domain`
+    pub kind: String,
+    pub pattern: String,
+    /// One of `mask`, `reject` or `flag`
+    pub action: String,
+    pub created_by: Option<Uuid>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl ModerationRule {
+    pub fn to_response(&self) -> crate::modules::chat::dto::moderation_dto::ModerationRuleResponse {
+        crate::modules::chat::dto::moderation_dto::ModerationRuleResponse {
+            id: self.id.to_string(),
+            room_name: self.room_name.clone(),
+            kind: self.kind.clone(),
+            pattern: self.pattern.clone(),
+            action: self.action.clone(),
+            created_by: self.created_by.map(|id| id.to_string()),
+            created_at: self.created_at.unix_timestamp() as u64,
+        }
+    }
+}
+
+/// A flagged message held for review
+#[derive(Debug, FromRow, Serialize, Deserialize)]
+pub struct FlaggedMessage {
+    pub id: Uuid,
+    pub room_name: String,
+    pub user_id: Uuid,
+    pub username: String,
+    pub body: String,
+    pub attachment_id: Option<Uuid>,
+    pub is_action: bool,
+    /// Reasons given by the filters, one per line
+    pub reasons: String,
+    pub status: String,
+    pub reviewed_by: Option<Uuid>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub reviewed_at: Option<OffsetDateTime>,
+    pub message_id: Option<Uuid>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl FlaggedMessage {
+    pub fn to_response(&self) -> crate::modules::chat::dto::moderation_dto::FlaggedMessageResponse {
+        crate::modules::chat::dto::moderation_dto::FlaggedMessageResponse {
+            id: self.id.to_string(),
+            room_name: self.room_name.clone(),
+            user_id: self.user_id.to_string(),
+            username: self.username.clone(),
+            message: self.body.clone(),
+            attachment_id: self.attachment_id.map(|id| id.to_string()),
+            reasons: self.reasons.lines().map(str::to_string).collect(),
+            status: self.status.clone(),
+            reviewed_by: self.reviewed_by.map(|id| id.to_string()),
+            message_id: self.message_id.map(|id| id.to_string()),
+            timestamp: self.created_at.unix_timestamp() as u64,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Debug, FromRow, Serialize, Deserialize)]
+pub struct Notification {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub room_name: String,
+    pub message_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub actor_username: String,
+    pub body: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub read_at: Option<OffsetDateTime>,
+}
+
+impl Notification {
+    pub fn to_response(&self) -> crate::modules::chat::dto::notification_dto::NotificationResponse {
+        crate::modules::chat::dto::notification_dto::NotificationResponse {
+            id: self.id.to_string(),
+            kind: self.kind.clone(),
+            room_name: self.room_name.clone(),
+            message_id: self.message_id.map(|id| id.to_string()),
+            actor_id: self.actor_id.map(|id| id.to_string()),
+            actor_username: self.actor_username.clone(),
+            message: self.body.clone(),
+            timestamp: self.created_at.unix_timestamp() as u64,
+            read: self.read_at.is_some(),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::modules::chat::dto::poll_dto::{PollOptionResponse, PollResponse};
+use crate::modules::chat::server::display_name;
+
+#[derive(Debug, FromRow)]
+pub struct Poll {
+    pub id: Uuid,
+    pub room_name: String,
+    pub created_by: Option<Uuid>,
+    pub question: String,
+    pub options: Vec<String>,
+    pub multiple_choice: bool,
+    pub anonymous: bool,
+    pub closes_at: Option<OffsetDateTime>,
+    pub closed_at: Option<OffsetDateTime>,
+    pub created_at: OffsetDateTime,
+}
+
+/// Votes cast for one option of a poll
+#[derive(Debug, FromRow)]
+pub struct PollTally {
+    pub option_index: i32,
+    pub votes: i64,
+    pub voters: Vec<Uuid>,
+}
+
+impl Poll {
+    /// The poll with its current tallies; voters are left out of anonymous polls
+    pub fn to_response(&self, tallies: &[PollTally], total_voters: i64) -> PollResponse {
+        let options = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(index, text)| {
+                let tally = tallies.iter().find(|tally| tally.option_index == index as i32);
+                PollOptionResponse {
+                    index,
+                    text: text.clone(),
+                    votes: tally.map(|tally| tally.votes).unwrap_or(0),
+                    voters: (!self.anonymous).then(|| {
+                        tally
+                            .map(|tally| tally.voters.iter().map(|id| display_name(*id)).collect())
+                            .unwrap_or_default()
+                    }),
+                }
+            })
+            .collect();
+
+        PollResponse {
+            id: self.id.to_string(),
+            room_name: self.room_name.clone(),
+            question: self.question.clone(),
+            options,
+            multiple_choice: self.multiple_choice,
+            anonymous: self.anonymous,
+            created_by: self.created_by.map(|id| id.to_string()),
+            closes_at: self.closes_at.map(|at| at.unix_timestamp() as u64),
+            closed: self.closed_at.is_some(),
+            total_voters,
+            created_at: self.created_at.unix_timestamp() as u64,
+        }
+    }
+}
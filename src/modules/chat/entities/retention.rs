@@ -0,0 +1,39 @@
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Retention settings of a room
+#[derive(Debug, FromRow)]
+pub struct RoomRetention {
+    pub name: String,
+    /// Days messages are kept; `None` uses the server default and 0 keeps them forever
+    pub retention_days: Option<i32>,
+    pub legal_hold: bool,
+}
+
+impl RoomRetention {
+    pub fn to_response(&self, default_days: u32) -> crate::modules::chat::dto::retention_dto::RetentionPolicyResponse {
+        let effective_days = self.retention_days.map(|days| days as u32).unwrap_or(default_days);
+        crate::modules::chat::dto::retention_dto::RetentionPolicyResponse {
+            room_name: self.name.clone(),
+            retention_days: self.retention_days,
+            effective_retention_days: (effective_days > 0 && !self.legal_hold).then_some(effective_days),
+            legal_hold: self.legal_hold,
+        }
+    }
+}
+
+/// Messages removed from one room by a purge batch
+#[derive(Debug, FromRow)]
+pub struct PurgedMessages {
+    pub room_name: String,
+    pub deleted: i64,
+}
+
+/// An attachment record removed because no message refers to it any more
+#[derive(Debug, FromRow)]
+pub struct PurgedAttachment {
+    pub id: Uuid,
+    pub sha256: String,
+    pub room_name: String,
+    pub size_bytes: i64,
+}
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::str::FromStr;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Room users join when they do not name one. Nobody owns it and it is always public.
+pub const DEFAULT_ROOM: &str = "general";
+
+/// Role of a user within a room
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoomRole {
+    Owner,
+    Moderator,
+    Member,
+}
+
+impl RoomRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoomRole::Owner => "owner",
+            RoomRole::Moderator => "moderator",
+            RoomRole::Member => "member",
+        }
+    }
+
+    /// Owners and moderators may change room settings such as the topic and pins
+    pub fn can_moderate(&self) -> bool {
+        matches!(self, RoomRole::Owner | RoomRole::Moderator)
+    }
+}
+
+impl FromStr for RoomRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "owner" => Ok(RoomRole::Owner),
+            "moderator" => Ok(RoomRole::Moderator),
+            "member" => Ok(RoomRole::Member),
+            other => Err(format!("Invalid room role: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, FromRow, Serialize, Deserialize)]
+pub struct Room {
+    pub name: String,
+    pub created_by: Option<Uuid>,
+    pub topic: Option<String>,
+    pub topic_set_by: Option<Uuid>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub topic_set_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl Room {
+    pub fn to_topic_response(&self) -> crate::modules::chat::dto::room_dto::RoomTopicResponse {
+        crate::modules::chat::dto::room_dto::RoomTopicResponse {
+            room_name: self.name.clone(),
+            topic: self.topic.clone(),
+            set_by: self.topic_set_by.map(|id| id.to_string()),
+            set_at: self.topic_set_at.map(|at| (at.unix_timestamp_nanos() / 1_000_000) as u64),
+        }
+    }
+}
+
+/// A pinned message joined with its content
+#[derive(Debug, FromRow)]
+pub struct PinnedMessage {
+    pub message_id: Uuid,
+    pub room_name: String,
+    pub user_id: Uuid,
+    pub username: String,
+    pub body: String,
+    pub created_at: OffsetDateTime,
+    pub pinned_by: Option<Uuid>,
+    pub pinned_at: OffsetDateTime,
+}
+
+impl PinnedMessage {
+    pub fn to_response(&self) -> crate::modules::chat::dto::room_dto::PinnedMessageResponse {
+        crate::modules::chat::dto::room_dto::PinnedMessageResponse {
+            message_id: self.message_id.to_string(),
+            user_id: self.user_id.to_string(),
+            username: self.username.clone(),
+            message: self.body.clone(),
+            timestamp: self.created_at.unix_timestamp() as u64,
+            pinned_by: self.pinned_by.map(|id| id.to_string()),
+            pinned_at: self.pinned_at.unix_timestamp() as u64,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::modules::chat::dto::scheduled_message_dto::ScheduledMessageResponse;
+
+/// A message a user scheduled to be posted to a room later
+#[derive(Debug, FromRow)]
+pub struct ScheduledMessage {
+    pub id: Uuid,
+    pub room_name: String,
+    pub user_id: Uuid,
+    pub body: String,
+    pub attachment_id: Option<Uuid>,
+    pub is_action: bool,
+    pub send_at: OffsetDateTime,
+    /// One of `pending`, `sending`, `sent`, `held` or `failed`
+    pub status: String,
+    /// Why the message could not be posted
+    pub error: Option<String>,
+    pub message_id: Option<Uuid>,
+    pub sent_at: Option<OffsetDateTime>,
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+}
+
+impl ScheduledMessage {
+    pub fn to_response(&self) -> ScheduledMessageResponse {
+        ScheduledMessageResponse {
+            id: self.id.to_string(),
+            room_name: self.room_name.clone(),
+            message: self.body.clone(),
+            attachment_id: self.attachment_id.map(|id| id.to_string()),
+            action: self.is_action,
+            send_at: self.send_at.unix_timestamp() as u64,
+            status: self.status.clone(),
+            error: self.error.clone(),
+            message_id: self.message_id.map(|id| id.to_string()),
+            sent_at: self.sent_at.map(|sent_at| sent_at.unix_timestamp() as u64),
+            created_at: self.created_at.unix_timestamp() as u64,
+        }
+    }
+}
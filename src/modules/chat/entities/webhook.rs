@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Outgoing webhook registered for a room
+#[derive(Debug, FromRow, Serialize, Deserialize)]
+pub struct RoomWebhook {
+    pub id: Uuid,
+    pub room_name: String,
+    pub url: String,
+    /// Key used to sign deliveries; only shown when the webhook is created
+    pub secret: String,
+    pub created_by: Option<Uuid>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl RoomWebhook {
+    pub fn to_response(&self, include_secret: bool) -> crate::modules::chat::dto::webhook_dto::WebhookResponse {
+        crate::modules::chat::dto::webhook_dto::WebhookResponse {
+            id: self.id.to_string(),
+            room_name: self.room_name.clone(),
+            url: self.url.clone(),
+            secret: include_secret.then(|| self.secret.clone()),
+            created_by: self.created_by.map(|id| id.to_string()),
+            created_at: self.created_at.unix_timestamp() as u64,
+        }
+    }
+}
+
+/// One event queued for one webhook, with the outcome of its latest attempt
+#[derive(Debug, FromRow, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub payload: String,
+    pub status: String,
+    pub attempts: i32,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub next_attempt_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub delivered_at: Option<OffsetDateTime>,
+}
+
+impl WebhookDelivery {
+    pub fn to_response(&self) -> crate::modules::chat::dto::webhook_dto::WebhookDeliveryResponse {
+        crate::modules::chat::dto::webhook_dto::WebhookDeliveryResponse {
+            id: self.id.to_string(),
+            event_id: self.event_id.to_string(),
+            event_type: self.event_type.clone(),
+            status: self.status.clone(),
+            attempts: self.attempts,
+            last_status_code: self.last_status_code,
+            last_error: self.last_error.clone(),
+            next_attempt_at: self.next_attempt_at.unix_timestamp() as u64,
+            created_at: self.created_at.unix_timestamp() as u64,
+            delivered_at: self.delivered_at.map(|at| at.unix_timestamp() as u64),
+        }
+    }
+}
+
+/// A delivery claimed by the delivery worker, with its webhook's target
+#[derive(Debug, FromRow)]
+pub struct ClaimedDelivery {
+    pub id: Uuid,
+    pub event_type: String,
+    pub payload: String,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+/// Token that lets an external system post into a room as a bot
+#[derive(Debug, FromRow, Serialize, Deserialize)]
+pub struct IncomingWebhook {
+    pub id: Uuid,
+    pub room_name: String,
+    pub token_hash: String,
+    pub display_name: String,
+    pub created_by: Option<Uuid>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub revoked_at: Option<OffsetDateTime>,
+}
+
+impl IncomingWebhook {
+    /// Response for the webhook; `token` is only passed when the webhook is created
+    pub fn to_response(&self, token: Option<String>) -> crate::modules::chat::dto::webhook_dto::IncomingWebhookResponse {
+        crate::modules::chat::dto::webhook_dto::IncomingWebhookResponse {
+            id: self.id.to_string(),
+            room_name: self.room_name.clone(),
+            display_name: self.display_name.clone(),
+            url: token.as_ref().map(|token| format!("/hooks/{}", token)),
+            token,
+            created_by: self.created_by.map(|id| id.to_string()),
+            created_at: self.created_at.unix_timestamp() as u64,
+            revoked_at: self.revoked_at.map(|at| at.unix_timestamp() as u64),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Room transcripts for compliance exports
+use std::str::FromStr;
+
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+
+use crate::modules::chat::entities::message::TranscriptMessage;
+
+/// File format of a transcript
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    /// A JSON array with one object per message
+    Json,
+    /// A header row, then one row per message
+    Csv,
+    /// One human-readable line per message
+    Txt,
+}
+
+impl ExportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Txt => "txt",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "application/json",
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Txt => "text/plain; charset=utf-8",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(ExportFormat::Json),
+            "csv" => Ok(ExportFormat::Csv),
+            "txt" => Ok(ExportFormat::Txt),
+            other => Err(format!("Invalid export format: {}", other)),
+        }
+    }
+}
+
+const CSV_HEADER: &str =
+    "id,seq,created_at,user_id,username,bot,action,message,attachment_id,attachment_file_name,attachment_content_type,attachment_size_bytes\r\n";
+
+/// Writes a transcript piece by piece, so it can be streamed as it is read
+#[derive(Debug)]
+pub struct TranscriptEncoder {
+    format: ExportFormat,
+    written: usize,
+}
+
+impl TranscriptEncoder {
+    pub fn new(format: ExportFormat) -> Self {
+        Self { format, written: 0 }
+    }
+
+    /// Text preceding the first message
+    pub fn header(&self) -> String {
+        match self.format {
+            ExportFormat::Json => "[".to_string(),
+            ExportFormat::Csv => CSV_HEADER.to_string(),
+            ExportFormat::Txt => String::new(),
+        }
+    }
+
+    /// Encode the next batch of messages
+    pub fn encode(&mut self, messages: &[TranscriptMessage]) -> String {
+        let mut out = String::new();
+        for message in messages {
+            match self.format {
+                ExportFormat::Json => {
+                    if self.written > 0 {
+                        out.push(',');
+                    }
+                    out.push('\n');
+                    out.push_str(&json_row(message));
+                }
+                ExportFormat::Csv => out.push_str(&csv_row(message)),
+                ExportFormat::Txt => out.push_str(&txt_line(message)),
+            }
+            self.written += 1;
+        }
+        out
+    }
+
+    /// Text following the last message
+    pub fn footer(&self) -> String {
+        match self.format {
+            ExportFormat::Json => "\n]\n".to_string(),
+            ExportFormat::Csv | ExportFormat::Txt => String::new(),
+        }
+    }
+}
+
+fn timestamp(message: &TranscriptMessage) -> String {
+    message.created_at.format(&Rfc3339).unwrap_or_default()
+}
+
+fn json_row(message: &TranscriptMessage) -> String {
+    let attachment = message.attachment_id.map(|id| {
+        serde_json::json!({
+            "id": id,
+            "file_name": message.attachment_file_name,
+            "content_type": message.attachment_content_type,
+            "size_bytes": message.attachment_size_bytes,
+        })
+    });
+    serde_json::json!({
+        "id": message.id,
+        "seq": message.seq,
+        "created_at": timestamp(message),
+        "user_id": message.user_id,
+        "username": message.username,
+        "bot": message.is_bot,
+        "action": message.is_action,
+        "message": message.body,
+        "attachment": attachment,
+    })
+    .to_string()
+}
+
+fn csv_row(message: &TranscriptMessage) -> String {
+    let fields = [
+        message.id.to_string(),
+        message.seq.to_string(),
+        timestamp(message),
+        message.user_id.to_string(),
+        message.username.clone(),
+        message.is_bot.to_string(),
+        message.is_action.to_string(),
+        message.body.clone(),
+        message.attachment_id.map(|id| id.to_string()).unwrap_or_default(),
+        message.attachment_file_name.clone().unwrap_or_default(),
+        message.attachment_content_type.clone().unwrap_or_default(),
+        message.attachment_size_bytes.map(|size| size.to_string()).unwrap_or_default(),
+    ];
+    let mut row = fields.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(",");
+    row.push_str("\r\n");
+    row
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn txt_line(message: &TranscriptMessage) -> String {
+    let time = message
+        .created_at
+        .format(format_description!("[year]-[month]-[day] [hour]:[minute]:[second] UTC"))
+        .unwrap_or_default();
+    // Keep one message per line; continuation lines are indented
+    let body = message.body.replace('\n', "\n    ");
+    let mut line = if message.is_action {
+        format!("[{}] * {} {}", time, message.username, body)
+    } else {
+        format!("[{}] <{}> {}", time, message.username, body)
+    };
+    if let Some(id) = message.attachment_id {
+        let name = message.attachment_file_name.as_deref().unwrap_or("unknown file");
+        match (&message.attachment_content_type, message.attachment_size_bytes) {
+            (Some(content_type), Some(size)) => {
+                line.push_str(&format!(" [attachment {}: {} ({}, {} bytes)]", id, name, content_type, size))
+            }
+            _ => line.push_str(&format!(" [attachment {}: {}]", id, name)),
+        }
+    }
+    line.push('\n');
+    line
+}
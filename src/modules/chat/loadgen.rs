@@ -0,0 +1,101 @@
+//! Delivery accounting for the `chat_loadgen` load generator
+
+/// What one simulated user sent and received
+#[derive(Debug, Default)]
+pub struct ClientStats {
+    pub sent: u64,
+    /// Messages the server answered with an error frame
+    pub rejected: u64,
+    pub received: u64,
+    pub latencies_us: Vec<u64>,
+    /// Messages skipped in a sender's sequence, e.g. because the connection lagged
+    pub gaps: u64,
+    /// Messages received more than once or out of order
+    pub duplicates: u64,
+    pub disconnects: u64,
+}
+
+impl ClientStats {
+    pub fn merge(&mut self, other: ClientStats) {
+        self.sent += other.sent;
+        self.rejected += other.rejected;
+        self.received += other.received;
+        self.latencies_us.extend(other.latencies_us);
+        self.gaps += other.gaps;
+        self.duplicates += other.duplicates;
+        self.disconnects += other.disconnects;
+    }
+}
+
+/// Delivery latencies in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencyPercentiles {
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub p999: f64,
+    pub max: f64,
+}
+
+/// Totals of a load test run
+#[derive(Debug, PartialEq)]
+pub struct LoadReport {
+    pub sent: u64,
+    pub rejected: u64,
+    /// Deliveries that should have happened: every accepted message to each member of
+    /// the sender's room, the sender included
+    pub expected: u64,
+    pub received: u64,
+    pub missing: u64,
+    /// Share of the expected deliveries that arrived; 100 when none were expected
+    pub delivered_percent: f64,
+    pub gaps: u64,
+    pub duplicates: u64,
+    pub disconnects: u64,
+    /// `None` when no message was delivered
+    pub latency_ms: Option<LatencyPercentiles>,
+}
+
+impl LoadReport {
+    /// Summarize the stats of every client, each with the number of members in its room
+    pub fn from_clients(clients: impl IntoIterator<Item = (ClientStats, u64)>) -> Self {
+        let mut total = ClientStats::default();
+        let mut expected = 0;
+        for (stats, room_size) in clients {
+            expected += (stats.sent - stats.rejected.min(stats.sent)) * room_size;
+            total.merge(stats);
+        }
+
+        let mut latencies = std::mem::take(&mut total.latencies_us);
+        latencies.sort_unstable();
+        let latency_ms = (!latencies.is_empty()).then(|| LatencyPercentiles {
+            p50: percentile(&latencies, 50.0),
+            p90: percentile(&latencies, 90.0),
+            p99: percentile(&latencies, 99.0),
+            p999: percentile(&latencies, 99.9),
+            max: percentile(&latencies, 100.0),
+        });
+
+        Self {
+            sent: total.sent,
+            rejected: total.rejected,
+            expected,
+            received: total.received,
+            missing: expected.saturating_sub(total.received),
+            delivered_percent: if expected == 0 { 100.0 } else { total.received as f64 * 100.0 / expected as f64 },
+            gaps: total.gaps,
+            duplicates: total.duplicates,
+            disconnects: total.disconnects,
+            latency_ms,
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted microsecond samples, in milliseconds; 0 without samples
+pub fn percentile(sorted: &[u64], percent: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((percent / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1] as f64 / 1000.0
+}
@@ -0,0 +1,38 @@
+//! Extraction of `@name` mentions from chat messages
+
+/// Upper bound on distinct mentions resolved for a single message
+pub const MAX_MENTIONS_PER_MESSAGE: usize = 20;
+
+/// Extract the distinct names mentioned with `@name` in a message, in order of appearance.
+///
+/// A mention must start the message or follow whitespace or an opening bracket, so
+/// e-mail addresses such as `ops@example.com` are not treated as mentions. Trailing
+/// punctuation is not part of the name.
+pub fn extract_mentions(text: &str) -> Vec<String> {
+    let mut mentions: Vec<String> = Vec::new();
+    let mut previous: Option<char> = None;
+
+    for (index, c) in text.char_indices() {
+        let starts_mention = c == '@'
+            && previous.is_none_or(|p| p.is_whitespace() || matches!(p, '(' | '[' | '{' | '"' | '\''));
+        previous = Some(c);
+        if !starts_mention {
+            continue;
+        }
+
+        let rest = &text[index + 1..];
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '.')))
+            .unwrap_or(rest.len());
+        let name = rest[..end].trim_end_matches(['.', '-']);
+
+        if !name.is_empty() && !mentions.iter().any(|m| m.eq_ignore_ascii_case(name)) {
+            mentions.push(name.to_string());
+            if mentions.len() == MAX_MENTIONS_PER_MESSAGE {
+                break;
+            }
+        }
+    }
+
+    mentions
+}
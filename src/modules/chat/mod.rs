@@ -1 +1,15 @@
-pub mod server;
\ No newline at end of file
+pub mod client;
+pub mod commands;
+pub mod dto;
+pub mod entities;
+pub mod export;
+pub mod loadgen;
+pub mod mentions;
+pub mod moderation;
+pub mod plugins;
+pub mod rate_limit;
+pub mod repositories;
+pub mod server;
+pub mod service;
+pub mod tui;
+pub mod webhooks;
\ No newline at end of file
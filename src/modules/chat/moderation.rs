@@ -0,0 +1,328 @@
+//! Content moderation filters run on user messages before they are broadcast
+use std::str::FromStr;
+
+use regex::Regex;
+
+use crate::modules::chat::entities::moderation::ModerationRule;
+
+/// Replacement for masked secrets
+const REDACTED: &str = "[redacted]";
+/// Replacement for masked links
+const LINK_REMOVED: &str = "[link removed]";
+/// Largest compiled size accepted for a room's regex rule
+pub const MAX_RULE_REGEX_SIZE: usize = 1 << 16;
+
+/// What a single filter decided about a message
+#[derive(Debug, PartialEq)]
+pub enum FilterAction {
+    /// Leave the message as it is
+    Allow,
+    /// Continue with this text instead; later filters see the rewritten text
+    Rewrite(String),
+    /// Drop the message, telling the sender why
+    Reject(String),
+    /// Hold the message for a moderator to review
+    Flag(String),
+}
+
+/// What happens to a message after the whole pipeline has run
+#[derive(Debug, PartialEq)]
+pub enum ModerationOutcome {
+    /// Broadcast this, possibly rewritten, text
+    Allow(String),
+    /// Drop the message with the reason of the first filter that rejected it
+    Reject(String),
+    /// Queue the message for review instead of broadcasting it
+    Flag { message: String, reasons: Vec<String> },
+}
+
+/// One step of the moderation pipeline
+pub trait MessageFilter: Send + Sync {
+    fn check(&self, message: &str) -> FilterAction;
+}
+
+/// How a room rule treats a match
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuleAction {
+    /// Replace the matched text
+    Mask,
+    Reject,
+    Flag,
+}
+
+impl RuleAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleAction::Mask => "mask",
+            RuleAction::Reject => "reject",
+            RuleAction::Flag => "flag",
+        }
+    }
+}
+
+impl FromStr for RuleAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mask" => Ok(RuleAction::Mask),
+            "reject" => Ok(RuleAction::Reject),
+            "flag" => Ok(RuleAction::Flag),
+            other => Err(format!("Invalid rule action: {}", other)),
+        }
+    }
+}
+
+/// What a room rule matches
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuleKind {
+    /// A whole word, case-insensitively
+    Word,
+    /// A regular expression
+    Regex,
+    /// Links to a domain or any of its subdomains
+    LinkDomain,
+}
+
+impl RuleKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleKind::Word => "word",
+            RuleKind::Regex => "regex",
+            RuleKind::LinkDomain => "link_domain",
+        }
+    }
+}
+
+impl FromStr for RuleKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "word" => Ok(RuleKind::Word),
+            "regex" => Ok(RuleKind::Regex),
+            "link_domain" => Ok(RuleKind::LinkDomain),
+            other => Err(format!("Invalid rule kind: {}", other)),
+        }
+    }
+}
+
+/// Masks credentials so they never reach other users or the message history
+pub struct SecretFilter {
+    patterns: Vec<Regex>,
+}
+
+impl Default for SecretFilter {
+    fn default() -> Self {
+        let patterns = [
+            // AWS access key IDs
+            r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b",
+            // AWS secret access keys, when labelled
+            r"(?i)aws_?secret_?access_?key\s*[:=]\s*[A-Za-z0-9/+=]{40}",
+            // JSON Web Tokens
+            r"\beyJ[A-Za-z0-9_-]+\.eyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+",
+        ];
+        Self {
+            patterns: patterns.iter().map(|pattern| Regex::new(pattern).unwrap()).collect(),
+        }
+    }
+}
+
+impl MessageFilter for SecretFilter {
+    fn check(&self, message: &str) -> FilterAction {
+        let mut text = message.to_string();
+        for pattern in &self.patterns {
+            if pattern.is_match(&text) {
+                text = pattern.replace_all(&text, REDACTED).into_owned();
+            }
+        }
+        if text == message {
+            FilterAction::Allow
+        } else {
+            FilterAction::Rewrite(text)
+        }
+    }
+}
+
+/// Applies a rule's action wherever a regular expression matches
+pub struct PatternFilter {
+    regex: Regex,
+    action: RuleAction,
+    reason: String,
+}
+
+impl PatternFilter {
+    pub fn new(regex: Regex, action: RuleAction, reason: String) -> Self {
+        Self { regex, action, reason }
+    }
+
+    /// Match any of `words` as whole words, case-insensitively
+    pub fn words(words: &[String], action: RuleAction, reason: String) -> Option<Self> {
+        let alternatives: Vec<String> = words
+            .iter()
+            .map(|word| word.trim())
+            .filter(|word| !word.is_empty())
+            .map(regex::escape)
+            .collect();
+        if alternatives.is_empty() {
+            return None;
+        }
+        let regex = Regex::new(&format!(r"(?i)\b(?:{})\b", alternatives.join("|"))).ok()?;
+        Some(Self::new(regex, action, reason))
+    }
+}
+
+impl MessageFilter for PatternFilter {
+    fn check(&self, message: &str) -> FilterAction {
+        if !self.regex.is_match(message) {
+            return FilterAction::Allow;
+        }
+        match self.action {
+            RuleAction::Mask => FilterAction::Rewrite(
+                self.regex
+                    .replace_all(message, |caps: &regex::Captures| "*".repeat(caps[0].chars().count()))
+                    .into_owned(),
+            ),
+            RuleAction::Reject => FilterAction::Reject(self.reason.clone()),
+            RuleAction::Flag => FilterAction::Flag(self.reason.clone()),
+        }
+    }
+}
+
+/// Applies a rule's action to links pointing at blocked domains
+pub struct LinkDomainFilter {
+    domains: Vec<String>,
+    action: RuleAction,
+    links: Regex,
+}
+
+impl LinkDomainFilter {
+    pub fn new(domains: Vec<String>, action: RuleAction) -> Self {
+        Self {
+            domains: domains.into_iter().map(|domain| domain.to_lowercase()).collect(),
+            action,
+            links: Regex::new(r"(?i)\b(?:https?://|www\.)([^\s/?#:]+)[^\s]*").unwrap(),
+        }
+    }
+
+    fn is_blocked(&self, host: &str) -> bool {
+        let host = host.to_lowercase();
+        let host = host.trim_start_matches("www.");
+        self.domains
+            .iter()
+            .any(|domain| host == domain || host.ends_with(&format!(".{}", domain)))
+    }
+}
+
+impl MessageFilter for LinkDomainFilter {
+    fn check(&self, message: &str) -> FilterAction {
+        let Some(blocked) = self
+            .links
+            .captures_iter(message)
+            .map(|caps| caps[1].to_string())
+            .find(|host| self.is_blocked(host))
+        else {
+            return FilterAction::Allow;
+        };
+
+        match self.action {
+            RuleAction::Mask => FilterAction::Rewrite(
+                self.links
+                    .replace_all(message, |caps: &regex::Captures| {
+                        if self.is_blocked(&caps[1]) {
+                            LINK_REMOVED.to_string()
+                        } else {
+                            caps[0].to_string()
+                        }
+                    })
+                    .into_owned(),
+            ),
+            RuleAction::Reject => FilterAction::Reject(format!("Links to {} are not allowed here", blocked)),
+            RuleAction::Flag => FilterAction::Flag(format!("Link to {}", blocked)),
+        }
+    }
+}
+
+/// Ordered filters for one room
+#[derive(Default)]
+pub struct ModerationPipeline {
+    filters: Vec<Box<dyn MessageFilter>>,
+}
+
+impl std::fmt::Debug for ModerationPipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModerationPipeline")
+            .field("filters", &self.filters.len())
+            .finish()
+    }
+}
+
+impl ModerationPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Secrets are masked first, then server-wide profanity, then the room's rules in
+    /// the order they were created. Rules that no longer compile are skipped.
+    pub fn for_room(profanity: &[String], rules: &[ModerationRule]) -> Self {
+        let mut pipeline = Self::new();
+        pipeline.push(SecretFilter::default());
+        if let Some(filter) = PatternFilter::words(profanity, RuleAction::Mask, "Profanity".to_string()) {
+            pipeline.push(filter);
+        }
+
+        for rule in rules {
+            let (Ok(kind), Ok(action)) = (rule.kind.parse::<RuleKind>(), rule.action.parse::<RuleAction>()) else {
+                continue;
+            };
+            match kind {
+                RuleKind::Word => {
+                    let reason = format!("\"{}\" is not allowed here", rule.pattern);
+                    if let Some(filter) = PatternFilter::words(std::slice::from_ref(&rule.pattern), action, reason) {
+                        pipeline.push(filter);
+                    }
+                }
+                RuleKind::Regex => {
+                    if let Ok(regex) = compile_rule_regex(&rule.pattern) {
+                        let reason = "Message matches a blocked pattern".to_string();
+                        pipeline.push(PatternFilter::new(regex, action, reason));
+                    }
+                }
+                RuleKind::LinkDomain => pipeline.push(LinkDomainFilter::new(vec![rule.pattern.clone()], action)),
+            }
+        }
+        pipeline
+    }
+
+    /// Add a filter after the existing ones
+    pub fn push(&mut self, filter: impl MessageFilter + 'static) {
+        self.filters.push(Box::new(filter));
+    }
+
+    /// Run every filter in order; a rejection stops the pipeline, flags are collected
+    pub fn run(&self, message: &str) -> ModerationOutcome {
+        let mut text = message.to_string();
+        let mut reasons = Vec::new();
+        for filter in &self.filters {
+            match filter.check(&text) {
+                FilterAction::Allow => {}
+                FilterAction::Rewrite(rewritten) => text = rewritten,
+                FilterAction::Reject(reason) => return ModerationOutcome::Reject(reason),
+                FilterAction::Flag(reason) => reasons.push(reason),
+            }
+        }
+
+        if reasons.is_empty() {
+            ModerationOutcome::Allow(text)
+        } else {
+            ModerationOutcome::Flag { message: text, reasons }
+        }
+    }
+}
+
+/// Compile a room's regex rule, bounding its size so rules cannot exhaust memory
+pub fn compile_rule_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    regex::RegexBuilder::new(pattern)
+        .size_limit(MAX_RULE_REGEX_SIZE)
+        .build()
+}
@@ -0,0 +1,241 @@
+//! In-process bots and message hooks
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use uuid::Uuid;
+
+use crate::modules::chat::server::{ChatState, MessageSender, OutgoingMessage};
+
+/// A message a user is about to send to a room
+#[derive(Debug)]
+pub struct PluginMessage<'a> {
+    pub room_name: &'a str,
+    pub user_id: Uuid,
+    pub username: &'a str,
+    pub message: &'a str,
+}
+
+/// A user joining or leaving a room
+#[derive(Debug)]
+pub struct MembershipEvent<'a> {
+    pub room_name: &'a str,
+    pub user_id: Uuid,
+    pub username: &'a str,
+}
+
+/// What should happen to a message after a plugin has seen it
+#[derive(Debug, PartialEq)]
+pub enum MessageVerdict {
+    /// Send the message unchanged
+    Allow,
+    /// Send this text instead; later plugins see the rewritten text
+    Rewrite(String),
+    /// Drop the message, telling the sender why
+    Veto(String),
+}
+
+/// Gives plugins access to the server while a hook runs.
+///
+/// Messages posted from a hook are sent once the event that triggered it has been
+/// handled, so a bot's reply always follows the message it replies to.
+pub struct PluginContext<'a> {
+    pub state: &'a ChatState,
+    /// Room of the event being handled, if any
+    pub room_name: Option<&'a str>,
+    plugin_name: &'static str,
+    posts: &'a Mutex<Vec<OutgoingMessage>>,
+}
+
+impl PluginContext<'_> {
+    /// Post a message as this plugin's bot to the room of the current event
+    pub fn post(&self, message: impl Into<String>) {
+        if let Some(room_name) = self.room_name {
+            self.post_to(room_name, message);
+        }
+    }
+
+    /// Post a message as this plugin's bot to any room
+    pub fn post_to(&self, room_name: &str, message: impl Into<String>) {
+        self.posts.lock().unwrap().push(OutgoingMessage {
+            room_name: room_name.to_string(),
+            sender: MessageSender::Plugin,
+            username: self.plugin_name.to_string(),
+            message: message.into(),
+            attachment_id: None,
+            action: false,
+        });
+    }
+}
+
+/// A bot or message hook running inside the server.
+///
+/// Every hook has a default that does nothing, so plugins only implement what they need.
+pub trait ChatPlugin: Send + Sync {
+    /// Name of the plugin, also shown as the author of the messages it posts
+    fn name(&self) -> &'static str;
+
+    /// Inspect a user's message before it is broadcast
+    fn on_message(&self, _ctx: &PluginContext<'_>, _message: &PluginMessage<'_>) -> MessageVerdict {
+        MessageVerdict::Allow
+    }
+
+    /// Called after a user joins a room
+    fn on_join(&self, _ctx: &PluginContext<'_>, _event: &MembershipEvent<'_>) {}
+
+    /// Called after a user leaves a room
+    fn on_leave(&self, _ctx: &PluginContext<'_>, _event: &MembershipEvent<'_>) {}
+
+    /// How often [`ChatPlugin::on_timer`] runs; `None` disables the timer
+    fn timer_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    fn on_timer(&self, _ctx: &PluginContext<'_>) {}
+}
+
+/// Plugins in the order their hooks run
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: Vec<Arc<dyn ChatPlugin>>,
+}
+
+impl std::fmt::Debug for PluginRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginRegistry")
+            .field("plugins", &self.plugins.iter().map(|plugin| plugin.name()).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry with the built-in plugins named in `names`, e.g. from `CHAT_PLUGINS`
+    pub fn with_builtin_plugins(names: &[String]) -> Self {
+        let mut registry = Self::new();
+        for name in names {
+            match name.as_str() {
+                "echo" => registry.register(Arc::new(EchoPlugin)),
+                unknown => eprintln!("Unknown chat plugin: {}", unknown),
+            }
+        }
+        registry
+    }
+
+    /// Add a plugin after the already registered ones
+    pub fn register(&mut self, plugin: Arc<dyn ChatPlugin>) {
+        self.plugins.push(plugin);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Run every plugin's message hook in order.
+    ///
+    /// Returns the text to send, or the veto reason of the first plugin that rejected
+    /// the message, together with the messages plugins posted.
+    pub fn on_message(
+        &self,
+        state: &ChatState,
+        message: &PluginMessage<'_>,
+    ) -> (Result<String, String>, Vec<OutgoingMessage>) {
+        let posts = Mutex::new(Vec::new());
+        let mut text = message.message.to_string();
+        for plugin in &self.plugins {
+            let ctx = self.context(state, plugin.as_ref(), Some(message.room_name), &posts);
+            let current = PluginMessage {
+                message: &text,
+                ..*message
+            };
+            match plugin.on_message(&ctx, &current) {
+                MessageVerdict::Allow => {}
+                MessageVerdict::Rewrite(rewritten) => text = rewritten,
+                MessageVerdict::Veto(reason) => return (Err(reason), posts.into_inner().unwrap()),
+            }
+        }
+        (Ok(text), posts.into_inner().unwrap())
+    }
+
+    /// Run every plugin's join hook, returning the messages they posted
+    pub fn on_join(&self, state: &ChatState, event: &MembershipEvent<'_>) -> Vec<OutgoingMessage> {
+        let posts = Mutex::new(Vec::new());
+        for plugin in &self.plugins {
+            plugin.on_join(&self.context(state, plugin.as_ref(), Some(event.room_name), &posts), event);
+        }
+        posts.into_inner().unwrap()
+    }
+
+    /// Run every plugin's leave hook, returning the messages they posted
+    pub fn on_leave(&self, state: &ChatState, event: &MembershipEvent<'_>) -> Vec<OutgoingMessage> {
+        let posts = Mutex::new(Vec::new());
+        for plugin in &self.plugins {
+            plugin.on_leave(&self.context(state, plugin.as_ref(), Some(event.room_name), &posts), event);
+        }
+        posts.into_inner().unwrap()
+    }
+
+    /// Spawn a task per plugin with a timer that runs its timer hook until the process exits
+    pub fn start_timers(&self, state: &ChatState) {
+        for plugin in &self.plugins {
+            let Some(interval) = plugin.timer_interval() else {
+                continue;
+            };
+            let plugin = plugin.clone();
+            let state = state.clone();
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                // The first tick completes immediately; timers fire after one full interval
+                ticker.tick().await;
+                loop {
+                    ticker.tick().await;
+                    let posts = Mutex::new(Vec::new());
+                    let ctx = PluginContext {
+                        state: &state,
+                        room_name: None,
+                        plugin_name: plugin.name(),
+                        posts: &posts,
+                    };
+                    plugin.on_timer(&ctx);
+                    state.post_plugin_messages(posts.into_inner().unwrap()).await;
+                }
+            });
+        }
+    }
+
+    fn context<'a>(
+        &self,
+        state: &'a ChatState,
+        plugin: &dyn ChatPlugin,
+        room_name: Option<&'a str>,
+        posts: &'a Mutex<Vec<OutgoingMessage>>,
+    ) -> PluginContext<'a> {
+        PluginContext {
+            state,
+            room_name,
+            plugin_name: plugin.name(),
+            posts,
+        }
+    }
+}
+
+/// Example plugin: `!echo <text>` makes the bot repeat `<text>`
+pub struct EchoPlugin;
+
+impl ChatPlugin for EchoPlugin {
+    fn name(&self) -> &'static str {
+        "echo"
+    }
+
+    fn on_message(&self, ctx: &PluginContext<'_>, message: &PluginMessage<'_>) -> MessageVerdict {
+        if let Some(text) = message.message.strip_prefix("!echo ") {
+            let text = text.trim();
+            if !text.is_empty() {
+                ctx.post(text);
+            }
+        }
+        MessageVerdict::Allow
+    }
+}
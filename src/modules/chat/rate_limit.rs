@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Fixed-window rate limiter allowing `limit` hits per key in each `window`
+#[derive(Debug)]
+pub struct RateLimiter<K> {
+    limit: u32,
+    window: Duration,
+    windows: Mutex<HashMap<K, (Instant, u32)>>,
+}
+
+impl<K: Eq + Hash> RateLimiter<K> {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a hit for the key, or return how long until it is allowed again
+    pub fn check(&self, key: K) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap();
+        // Forget expired windows so keys that stop sending do not accumulate
+        windows.retain(|_, (started, _)| now.duration_since(*started) < self.window);
+
+        let (started, hits) = windows.entry(key).or_insert((now, 0));
+        if *hits >= self.limit {
+            return Err(self.window - now.duration_since(*started));
+        }
+        *hits += 1;
+        Ok(())
+    }
+}
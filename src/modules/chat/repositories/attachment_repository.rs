@@ -0,0 +1,53 @@
+use crate::modules::chat::entities::attachment::Attachment;
+use sqlx::{Pool, Postgres, Error};
+use uuid::Uuid;
+
+pub struct AttachmentRepository {
+    db_pool: Pool<Postgres>,
+}
+
+impl AttachmentRepository {
+    pub fn new(db_pool: Pool<Postgres>) -> Self {
+        Self { db_pool }
+    }
+
+    /// Record an uploaded attachment shared in a room
+    pub async fn create_attachment(
+        &self,
+        sha256: &str,
+        room_name: &str,
+        uploader_id: Uuid,
+        file_name: &str,
+        content_type: &str,
+        size_bytes: i64,
+    ) -> Result<Attachment, Error> {
+        let attachment = sqlx::query_as::<_, Attachment>(
+            "INSERT INTO attachments (sha256, room_name, uploader_id, file_name, content_type, size_bytes)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING id, sha256, room_name, uploader_id, file_name, content_type, size_bytes, created_at"
+        )
+        .bind(sha256)
+        .bind(room_name)
+        .bind(uploader_id)
+        .bind(file_name)
+        .bind(content_type)
+        .bind(size_bytes)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(attachment)
+    }
+
+    /// Find an attachment by ID
+    pub async fn find_attachment_by_id(&self, id: Uuid) -> Result<Option<Attachment>, Error> {
+        let attachment = sqlx::query_as::<_, Attachment>(
+            "SELECT id, sha256, room_name, uploader_id, file_name, content_type, size_bytes, created_at
+             FROM attachments WHERE id = $1"
+        )
+        .bind(id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(attachment)
+    }
+}
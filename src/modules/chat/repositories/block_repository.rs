@@ -0,0 +1,67 @@
+use crate::modules::chat::entities::block::UserBlock;
+use sqlx::{Pool, Postgres, Error};
+use uuid::Uuid;
+
+pub struct BlockRepository {
+    db_pool: Pool<Postgres>,
+}
+
+impl BlockRepository {
+    pub fn new(db_pool: Pool<Postgres>) -> Self {
+        Self { db_pool }
+    }
+
+    /// Block a user, returning `None` if there is no such user
+    pub async fn block_user(&self, blocker_id: Uuid, blocked_id: Uuid) -> Result<Option<UserBlock>, Error> {
+        let block = sqlx::query_as::<_, UserBlock>(
+            "INSERT INTO user_blocks (blocker_id, blocked_id)
+             SELECT $1, id FROM users WHERE id = $2
+             ON CONFLICT (blocker_id, blocked_id) DO UPDATE SET created_at = user_blocks.created_at
+             RETURNING blocker_id, blocked_id, created_at"
+        )
+        .bind(blocker_id)
+        .bind(blocked_id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(block)
+    }
+
+    /// Unblock a user, returning whether they were blocked
+    pub async fn unblock_user(&self, blocker_id: Uuid, blocked_id: Uuid) -> Result<bool, Error> {
+        let result = sqlx::query("DELETE FROM user_blocks WHERE blocker_id = $1 AND blocked_id = $2")
+            .bind(blocker_id)
+            .bind(blocked_id)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Users blocked by a user, most recently blocked first
+    pub async fn list_blocked(&self, blocker_id: Uuid) -> Result<Vec<UserBlock>, Error> {
+        let blocks = sqlx::query_as::<_, UserBlock>(
+            "SELECT blocker_id, blocked_id, created_at FROM user_blocks
+             WHERE blocker_id = $1
+             ORDER BY created_at DESC"
+        )
+        .bind(blocker_id)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(blocks)
+    }
+
+    /// Check whether one user has blocked another
+    pub async fn is_blocked(&self, blocker_id: Uuid, blocked_id: Uuid) -> Result<bool, Error> {
+        let blocked = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM user_blocks WHERE blocker_id = $1 AND blocked_id = $2)"
+        )
+        .bind(blocker_id)
+        .bind(blocked_id)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(blocked)
+    }
+}
@@ -0,0 +1,75 @@
+use crate::modules::chat::entities::webhook::IncomingWebhook;
+use sqlx::{Pool, Postgres, Error};
+use uuid::Uuid;
+
+pub struct IncomingWebhookRepository {
+    db_pool: Pool<Postgres>,
+}
+
+impl IncomingWebhookRepository {
+    pub fn new(db_pool: Pool<Postgres>) -> Self {
+        Self { db_pool }
+    }
+
+    pub async fn create_incoming_webhook(
+        &self,
+        room_name: &str,
+        token_hash: &str,
+        display_name: &str,
+        created_by: Uuid,
+    ) -> Result<IncomingWebhook, Error> {
+        let webhook = sqlx::query_as::<_, IncomingWebhook>(
+            "INSERT INTO incoming_webhooks (room_name, token_hash, display_name, created_by)
+             VALUES ($1, $2, $3, $4)
+             RETURNING id, room_name, token_hash, display_name, created_by, created_at, revoked_at"
+        )
+        .bind(room_name)
+        .bind(token_hash)
+        .bind(display_name)
+        .bind(created_by)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(webhook)
+    }
+
+    pub async fn list_incoming_webhooks(&self, room_name: &str) -> Result<Vec<IncomingWebhook>, Error> {
+        let webhooks = sqlx::query_as::<_, IncomingWebhook>(
+            "SELECT id, room_name, token_hash, display_name, created_by, created_at, revoked_at
+             FROM incoming_webhooks WHERE room_name = $1
+             ORDER BY created_at"
+        )
+        .bind(room_name)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(webhooks)
+    }
+
+    /// Find a webhook that has not been revoked by the hash of its token
+    pub async fn find_active_by_token_hash(&self, token_hash: &str) -> Result<Option<IncomingWebhook>, Error> {
+        let webhook = sqlx::query_as::<_, IncomingWebhook>(
+            "SELECT id, room_name, token_hash, display_name, created_by, created_at, revoked_at
+             FROM incoming_webhooks WHERE token_hash = $1 AND revoked_at IS NULL"
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(webhook)
+    }
+
+    /// Revoke a webhook, returning false if it does not exist or is already revoked
+    pub async fn revoke_incoming_webhook(&self, room_name: &str, id: Uuid) -> Result<bool, Error> {
+        let result = sqlx::query(
+            "UPDATE incoming_webhooks SET revoked_at = NOW()
+             WHERE room_name = $1 AND id = $2 AND revoked_at IS NULL"
+        )
+        .bind(room_name)
+        .bind(id)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
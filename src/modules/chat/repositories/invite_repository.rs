@@ -0,0 +1,143 @@
+use crate::modules::chat::entities::invite::RoomInvite;
+use sqlx::{Pool, Postgres, Error};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+const INVITE_COLUMNS: &str = "code, room_name, created_by, role, max_uses, uses, expires_at, revoked_at, created_at";
+
+/// Outcome of accepting an invite
+pub enum AcceptOutcome {
+    /// The user joined the room with the given role
+    Joined { room_name: String, role: String },
+    /// The user was already a member; the invite was not used
+    AlreadyMember { room_name: String, role: String },
+    NotFound,
+    Expired,
+    UsedUp,
+}
+
+pub struct InviteRepository {
+    db_pool: Pool<Postgres>,
+}
+
+impl InviteRepository {
+    pub fn new(db_pool: Pool<Postgres>) -> Self {
+        Self { db_pool }
+    }
+
+    pub async fn create_invite(
+        &self,
+        code: &str,
+        room_name: &str,
+        created_by: Uuid,
+        role: &str,
+        max_uses: Option<i32>,
+        expires_at: Option<OffsetDateTime>,
+    ) -> Result<RoomInvite, Error> {
+        let invite = sqlx::query_as::<_, RoomInvite>(&format!(
+            "INSERT INTO room_invites (code, room_name, created_by, role, max_uses, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING {}",
+            INVITE_COLUMNS
+        ))
+        .bind(code)
+        .bind(room_name)
+        .bind(created_by)
+        .bind(role)
+        .bind(max_uses)
+        .bind(expires_at)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(invite)
+    }
+
+    /// Invites of a room that have not been revoked, newest first
+    pub async fn list_invites(&self, room_name: &str) -> Result<Vec<RoomInvite>, Error> {
+        let invites = sqlx::query_as::<_, RoomInvite>(&format!(
+            "SELECT {} FROM room_invites
+             WHERE room_name = $1 AND revoked_at IS NULL
+             ORDER BY created_at DESC",
+            INVITE_COLUMNS
+        ))
+        .bind(room_name)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(invites)
+    }
+
+    /// Revoke an invite, returning whether it existed and was not yet revoked
+    pub async fn revoke_invite(&self, room_name: &str, code: &str) -> Result<bool, Error> {
+        let result = sqlx::query(
+            "UPDATE room_invites SET revoked_at = NOW()
+             WHERE room_name = $1 AND code = $2 AND revoked_at IS NULL"
+        )
+        .bind(room_name)
+        .bind(code)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Add a user to the invite's room and count the use.
+    ///
+    /// The invite row is locked meanwhile, so concurrent accepts cannot exceed its
+    /// maximum number of uses.
+    pub async fn accept_invite(&self, code: &str, user_id: Uuid) -> Result<AcceptOutcome, Error> {
+        let mut tx = self.db_pool.begin().await?;
+
+        let invite = sqlx::query_as::<_, RoomInvite>(&format!(
+            "SELECT {} FROM room_invites WHERE code = $1 AND revoked_at IS NULL FOR UPDATE",
+            INVITE_COLUMNS
+        ))
+        .bind(code)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(invite) = invite else {
+            return Ok(AcceptOutcome::NotFound);
+        };
+
+        let role = sqlx::query_scalar::<_, String>(
+            "SELECT role FROM room_members WHERE room_name = $1 AND user_id = $2"
+        )
+        .bind(&invite.room_name)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some(role) = role {
+            return Ok(AcceptOutcome::AlreadyMember {
+                room_name: invite.room_name,
+                role,
+            });
+        }
+        if invite.is_expired(OffsetDateTime::now_utc()) {
+            return Ok(AcceptOutcome::Expired);
+        }
+        if invite.is_used_up() {
+            return Ok(AcceptOutcome::UsedUp);
+        }
+
+        sqlx::query(
+            "INSERT INTO room_members (room_name, user_id, role)
+             VALUES ($1, $2, $3)"
+        )
+        .bind(&invite.room_name)
+        .bind(user_id)
+        .bind(&invite.role)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE room_invites SET uses = uses + 1 WHERE code = $1")
+            .bind(code)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(AcceptOutcome::Joined {
+            room_name: invite.room_name,
+            role: invite.role,
+        })
+    }
+}
@@ -0,0 +1,216 @@
+use crate::modules::chat::entities::message::{MessageRecord, MessageSearchHit, RoomEventRecord, TranscriptMessage};
+use sqlx::{Pool, Postgres, Error};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Filters applied to a full-text message search
+pub struct MessageSearchFilter<'a> {
+    pub query: &'a str,
+    pub room_name: Option<&'a str>,
+    pub author_id: Option<Uuid>,
+    pub from: Option<OffsetDateTime>,
+    pub to: Option<OffsetDateTime>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Fields of a chat message to be persisted
+pub struct NewMessage<'a> {
+    pub room_name: &'a str,
+    /// Sending user, unset for bot messages
+    pub user_id: Option<Uuid>,
+    pub incoming_webhook_id: Option<Uuid>,
+    pub username: &'a str,
+    pub body: &'a str,
+    pub attachment_id: Option<Uuid>,
+    pub seq: i64,
+    pub is_action: bool,
+}
+
+/// Messages of a room to include in a transcript
+pub struct TranscriptFilter<'a> {
+    pub room_name: &'a str,
+    pub from: Option<OffsetDateTime>,
+    pub to: Option<OffsetDateTime>,
+}
+
+#[derive(Clone)]
+pub struct MessageRepository {
+    db_pool: Pool<Postgres>,
+}
+
+impl MessageRepository {
+    pub fn new(db_pool: Pool<Postgres>) -> Self {
+        Self { db_pool }
+    }
+
+    /// Persist a chat message to room history
+    pub async fn create_message(&self, message: &NewMessage<'_>) -> Result<MessageRecord, Error> {
+        let message = sqlx::query_as::<_, MessageRecord>(
+            "INSERT INTO messages (room_name, user_id, incoming_webhook_id, username, body, attachment_id, seq, is_action)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             RETURNING id, room_name, COALESCE(user_id, incoming_webhook_id, uuid_nil()) AS user_id,
+                       user_id IS NULL AS is_bot, username, body, attachment_id, seq, is_action, created_at"
+        )
+        .bind(message.room_name)
+        .bind(message.user_id)
+        .bind(message.incoming_webhook_id)
+        .bind(message.username)
+        .bind(message.body)
+        .bind(message.attachment_id)
+        .bind(message.seq)
+        .bind(message.is_action)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(message)
+    }
+
+    /// Persist a room event other than a chat message, for replay
+    pub async fn create_room_event(&self, room_name: &str, seq: i64, frame: &str) -> Result<(), Error> {
+        sqlx::query("INSERT INTO room_events (room_name, seq, frame) VALUES ($1, $2, $3)")
+            .bind(room_name)
+            .bind(seq)
+            .bind(frame)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The most recent room events after a sequence number, oldest first
+    pub async fn list_room_events_after(
+        &self,
+        room_name: &str,
+        after_seq: i64,
+        limit: i64,
+    ) -> Result<Vec<RoomEventRecord>, Error> {
+        let mut events = sqlx::query_as::<_, RoomEventRecord>(
+            "SELECT seq, frame
+             FROM room_events
+             WHERE room_name = $1 AND seq > $2
+             ORDER BY seq DESC
+             LIMIT $3"
+        )
+        .bind(room_name)
+        .bind(after_seq)
+        .bind(limit)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        events.reverse();
+        Ok(events)
+    }
+
+    /// Highest sequence number used in a room, including room events and purged
+    /// messages, or 0 for a room without any
+    pub async fn last_seq(&self, room_name: &str) -> Result<i64, Error> {
+        let seq = sqlx::query_scalar::<_, i64>(
+            "SELECT GREATEST(
+                 (SELECT COALESCE(MAX(seq), 0) FROM messages WHERE room_name = $1),
+                 (SELECT COALESCE(MAX(seq), 0) FROM room_events WHERE room_name = $1),
+                 (SELECT COALESCE(MAX(last_purged_seq), 0) FROM rooms WHERE name = $1)
+             )::BIGINT"
+        )
+        .bind(room_name)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(seq)
+    }
+
+    /// The most recent messages after a sequence number, oldest first
+    pub async fn list_messages_after(
+        &self,
+        room_name: &str,
+        after_seq: i64,
+        limit: i64,
+    ) -> Result<Vec<MessageRecord>, Error> {
+        let mut messages = sqlx::query_as::<_, MessageRecord>(
+            "SELECT id, room_name, COALESCE(user_id, incoming_webhook_id, uuid_nil()) AS user_id,
+                    user_id IS NULL AS is_bot, username, body, attachment_id, seq, is_action, created_at
+             FROM messages
+             WHERE room_name = $1 AND seq > $2
+             ORDER BY seq DESC
+             LIMIT $3"
+        )
+        .bind(room_name)
+        .bind(after_seq)
+        .bind(limit)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        messages.reverse();
+        Ok(messages)
+    }
+
+    /// The next page of a room's transcript after a sequence number, oldest first
+    pub async fn list_transcript_page(
+        &self,
+        filter: &TranscriptFilter<'_>,
+        after_seq: i64,
+        limit: i64,
+    ) -> Result<Vec<TranscriptMessage>, Error> {
+        let messages = sqlx::query_as::<_, TranscriptMessage>(
+            "SELECT m.id, m.seq, COALESCE(m.user_id, m.incoming_webhook_id, uuid_nil()) AS user_id,
+                    m.user_id IS NULL AS is_bot, m.username, m.body, m.is_action, m.created_at,
+                    m.attachment_id, a.file_name AS attachment_file_name,
+                    a.content_type AS attachment_content_type, a.size_bytes AS attachment_size_bytes
+             FROM messages m
+             LEFT JOIN attachments a ON a.id = m.attachment_id
+             WHERE m.room_name = $1 AND m.seq > $2
+               AND ($3::TIMESTAMPTZ IS NULL OR m.created_at >= $3)
+               AND ($4::TIMESTAMPTZ IS NULL OR m.created_at < $4)
+             ORDER BY m.seq
+             LIMIT $5"
+        )
+        .bind(filter.room_name)
+        .bind(after_seq)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(limit)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(messages)
+    }
+
+    /// Search messages in the rooms a user belongs to. The snippet is HTML: the body is
+    /// escaped before the matches are wrapped in `<mark>` tags
+    pub async fn search_messages(
+        &self,
+        user_id: Uuid,
+        filter: &MessageSearchFilter<'_>,
+    ) -> Result<Vec<MessageSearchHit>, Error> {
+        let hits = sqlx::query_as::<_, MessageSearchHit>(
+            "SELECT m.id, m.room_name, COALESCE(m.user_id, m.incoming_webhook_id, uuid_nil()) AS user_id, m.username, m.body, m.created_at,
+                    ts_headline('english',
+                                replace(replace(replace(replace(m.body, '&', '&amp;'), '<', '&lt;'), '>', '&gt;'), '\"', '&quot;'),
+                                q.query,
+                                'StartSel=<mark>, StopSel=</mark>, MaxFragments=2, MaxWords=20, MinWords=5') AS snippet,
+                    ts_rank(m.search_vector, q.query) AS rank
+             FROM messages m
+             CROSS JOIN websearch_to_tsquery('english', $2) AS q(query)
+             JOIN room_members rm ON rm.room_name = m.room_name AND rm.user_id = $1
+             WHERE m.search_vector @@ q.query
+               AND ($3::VARCHAR IS NULL OR m.room_name = $3)
+               AND ($4::UUID IS NULL OR COALESCE(m.user_id, m.incoming_webhook_id) = $4)
+               AND ($5::TIMESTAMPTZ IS NULL OR m.created_at >= $5)
+               AND ($6::TIMESTAMPTZ IS NULL OR m.created_at < $6)
+             ORDER BY rank DESC, m.created_at DESC
+             LIMIT $7 OFFSET $8"
+        )
+        .bind(user_id)
+        .bind(filter.query)
+        .bind(filter.room_name)
+        .bind(filter.author_id)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(filter.limit)
+        .bind(filter.offset)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(hits)
+    }
+}
@@ -0,0 +1,25 @@
+pub mod attachment_repository;
+pub mod block_repository;
+pub mod incoming_webhook_repository;
+pub mod invite_repository;
+pub mod message_repository;
+pub mod moderation_repository;
+pub mod notification_repository;
+pub mod poll_repository;
+pub mod retention_repository;
+pub mod room_repository;
+pub mod scheduled_message_repository;
+pub mod webhook_repository;
+
+pub use attachment_repository::AttachmentRepository;
+pub use block_repository::BlockRepository;
+pub use incoming_webhook_repository::IncomingWebhookRepository;
+pub use invite_repository::InviteRepository;
+pub use message_repository::MessageRepository;
+pub use moderation_repository::ModerationRepository;
+pub use notification_repository::NotificationRepository;
+pub use poll_repository::PollRepository;
+pub use retention_repository::RetentionRepository;
+pub use room_repository::RoomRepository;
+pub use scheduled_message_repository::ScheduledMessageRepository;
+pub use webhook_repository::WebhookRepository;
\ No newline at end of file
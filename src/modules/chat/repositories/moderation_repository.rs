@@ -0,0 +1,149 @@
+use crate::modules::chat::entities::moderation::{FlaggedMessage, ModerationRule};
+use sqlx::{Pool, Postgres, Error};
+use uuid::Uuid;
+
+const FLAGGED_MESSAGE_COLUMNS: &str = "id, room_name, user_id, username, body, attachment_id, is_action, reasons, \
+     status, reviewed_by, reviewed_at, message_id, created_at";
+
+/// Fields of a message to hold for review
+pub struct NewFlaggedMessage<'a> {
+    pub room_name: &'a str,
+    pub user_id: Uuid,
+    pub username: &'a str,
+    pub body: &'a str,
+    pub attachment_id: Option<Uuid>,
+    pub is_action: bool,
+    pub reasons: &'a [String],
+}
+
+pub struct ModerationRepository {
+    db_pool: Pool<Postgres>,
+}
+
+impl ModerationRepository {
+    pub fn new(db_pool: Pool<Postgres>) -> Self {
+        Self { db_pool }
+    }
+
+    pub async fn create_rule(
+        &self,
+        room_name: &str,
+        kind: &str,
+        pattern: &str,
+        action: &str,
+        created_by: Uuid,
+    ) -> Result<ModerationRule, Error> {
+        let rule = sqlx::query_as::<_, ModerationRule>(
+            "INSERT INTO moderation_rules (room_name, kind, pattern, action, created_by)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING id, room_name, kind, pattern, action, created_by, created_at"
+        )
+        .bind(room_name)
+        .bind(kind)
+        .bind(pattern)
+        .bind(action)
+        .bind(created_by)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(rule)
+    }
+
+    /// Rules of a room in the order they are applied
+    pub async fn list_rules(&self, room_name: &str) -> Result<Vec<ModerationRule>, Error> {
+        let rules = sqlx::query_as::<_, ModerationRule>(
+            "SELECT id, room_name, kind, pattern, action, created_by, created_at
+             FROM moderation_rules WHERE room_name = $1
+             ORDER BY created_at, id"
+        )
+        .bind(room_name)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(rules)
+    }
+
+    /// Delete a rule, returning whether it existed
+    pub async fn delete_rule(&self, room_name: &str, id: Uuid) -> Result<bool, Error> {
+        let result = sqlx::query("DELETE FROM moderation_rules WHERE room_name = $1 AND id = $2")
+            .bind(room_name)
+            .bind(id)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn create_flagged_message(&self, flagged: &NewFlaggedMessage<'_>) -> Result<FlaggedMessage, Error> {
+        let flagged = sqlx::query_as::<_, FlaggedMessage>(&format!(
+            "INSERT INTO moderation_queue (room_name, user_id, username, body, attachment_id, is_action, reasons)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING {}",
+            FLAGGED_MESSAGE_COLUMNS
+        ))
+        .bind(flagged.room_name)
+        .bind(flagged.user_id)
+        .bind(flagged.username)
+        .bind(flagged.body)
+        .bind(flagged.attachment_id)
+        .bind(flagged.is_action)
+        .bind(flagged.reasons.join("\n"))
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(flagged)
+    }
+
+    /// Flagged messages of a room with the given status, oldest first
+    pub async fn list_flagged_messages(&self, room_name: &str, status: &str) -> Result<Vec<FlaggedMessage>, Error> {
+        let flagged = sqlx::query_as::<_, FlaggedMessage>(&format!(
+            "SELECT {} FROM moderation_queue
+             WHERE room_name = $1 AND status = $2
+             ORDER BY created_at
+             LIMIT 200",
+            FLAGGED_MESSAGE_COLUMNS
+        ))
+        .bind(room_name)
+        .bind(status)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(flagged)
+    }
+
+    /// Record a decision on a pending message; `None` if it does not exist or was already reviewed
+    pub async fn review_flagged_message(
+        &self,
+        room_name: &str,
+        id: Uuid,
+        status: &str,
+        reviewed_by: Uuid,
+    ) -> Result<Option<FlaggedMessage>, Error> {
+        let flagged = sqlx::query_as::<_, FlaggedMessage>(&format!(
+            "UPDATE moderation_queue
+             SET status = $3, reviewed_by = $4, reviewed_at = NOW()
+             WHERE room_name = $1 AND id = $2 AND status = 'pending'
+             RETURNING {}",
+            FLAGGED_MESSAGE_COLUMNS
+        ))
+        .bind(room_name)
+        .bind(id)
+        .bind(status)
+        .bind(reviewed_by)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(flagged)
+    }
+
+    /// Link an approved message to the chat message it was posted as
+    pub async fn set_posted_message(&self, id: Uuid, message_id: Uuid) -> Result<(), Error> {
+        sqlx::query("UPDATE moderation_queue SET message_id = $2 WHERE id = $1")
+            .bind(id)
+            .bind(message_id)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(())
+    }
+}
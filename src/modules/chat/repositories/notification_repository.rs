@@ -0,0 +1,107 @@
+use crate::modules::chat::entities::notification::Notification;
+use sqlx::{Pool, Postgres, Error};
+use uuid::Uuid;
+
+/// Fields of a notification to be created
+pub struct NewNotification<'a> {
+    pub user_id: Uuid,
+    pub kind: &'a str,
+    pub room_name: &'a str,
+    pub message_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub actor_username: &'a str,
+    pub body: &'a str,
+}
+
+pub struct NotificationRepository {
+    db_pool: Pool<Postgres>,
+}
+
+impl NotificationRepository {
+    pub fn new(db_pool: Pool<Postgres>) -> Self {
+        Self { db_pool }
+    }
+
+    /// Add a notification to a user's inbox
+    pub async fn create_notification(&self, notification: &NewNotification<'_>) -> Result<Notification, Error> {
+        let notification = sqlx::query_as::<_, Notification>(
+            "INSERT INTO notifications (user_id, kind, room_name, message_id, actor_id, actor_username, body)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING id, user_id, kind, room_name, message_id, actor_id, actor_username, body, created_at, read_at"
+        )
+        .bind(notification.user_id)
+        .bind(notification.kind)
+        .bind(notification.room_name)
+        .bind(notification.message_id)
+        .bind(notification.actor_id)
+        .bind(notification.actor_username)
+        .bind(notification.body)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(notification)
+    }
+
+    /// List a user's notifications, newest first
+    pub async fn list_notifications(
+        &self,
+        user_id: Uuid,
+        unread_only: bool,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Notification>, Error> {
+        let notifications = sqlx::query_as::<_, Notification>(
+            "SELECT id, user_id, kind, room_name, message_id, actor_id, actor_username, body, created_at, read_at
+             FROM notifications
+             WHERE user_id = $1 AND (NOT $2 OR read_at IS NULL)
+             ORDER BY created_at DESC
+             LIMIT $3 OFFSET $4"
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(notifications)
+    }
+
+    /// Count a user's unread notifications
+    pub async fn count_unread(&self, user_id: Uuid) -> Result<i64, Error> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL"
+        )
+        .bind(user_id)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Mark one of the user's notifications as read, returning whether it exists
+    pub async fn mark_read(&self, user_id: Uuid, id: Uuid) -> Result<bool, Error> {
+        let result = sqlx::query(
+            "UPDATE notifications SET read_at = COALESCE(read_at, NOW())
+             WHERE id = $1 AND user_id = $2"
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Mark all of the user's notifications as read, returning how many changed
+    pub async fn mark_all_read(&self, user_id: Uuid) -> Result<u64, Error> {
+        let result = sqlx::query(
+            "UPDATE notifications SET read_at = NOW() WHERE user_id = $1 AND read_at IS NULL"
+        )
+        .bind(user_id)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
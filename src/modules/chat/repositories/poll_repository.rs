@@ -0,0 +1,195 @@
+use crate::modules::chat::entities::poll::{Poll, PollTally};
+use sqlx::{Pool, Postgres, Error};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+const POLL_COLUMNS: &str =
+    "id, room_name, created_by, question, options, multiple_choice, anonymous, closes_at, closed_at, created_at";
+
+/// Fields of a poll to be created
+pub struct NewPoll<'a> {
+    pub room_name: &'a str,
+    pub created_by: Uuid,
+    pub question: &'a str,
+    pub options: &'a [String],
+    pub multiple_choice: bool,
+    pub anonymous: bool,
+    pub closes_at: Option<OffsetDateTime>,
+}
+
+/// Outcome of casting a vote
+pub enum VoteOutcome {
+    Recorded,
+    PollNotFound,
+    PollClosed,
+}
+
+pub struct PollRepository {
+    db_pool: Pool<Postgres>,
+}
+
+impl PollRepository {
+    pub fn new(db_pool: Pool<Postgres>) -> Self {
+        Self { db_pool }
+    }
+
+    pub async fn create_poll(&self, poll: &NewPoll<'_>) -> Result<Poll, Error> {
+        let poll = sqlx::query_as::<_, Poll>(&format!(
+            "INSERT INTO polls (room_name, created_by, question, options, multiple_choice, anonymous, closes_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING {}",
+            POLL_COLUMNS
+        ))
+        .bind(poll.room_name)
+        .bind(poll.created_by)
+        .bind(poll.question)
+        .bind(poll.options)
+        .bind(poll.multiple_choice)
+        .bind(poll.anonymous)
+        .bind(poll.closes_at)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(poll)
+    }
+
+    pub async fn find_poll(&self, poll_id: Uuid) -> Result<Option<Poll>, Error> {
+        let poll = sqlx::query_as::<_, Poll>(&format!("SELECT {} FROM polls WHERE id = $1", POLL_COLUMNS))
+            .bind(poll_id)
+            .fetch_optional(&self.db_pool)
+            .await?;
+
+        Ok(poll)
+    }
+
+    /// Most recent polls of a room, newest first
+    pub async fn list_polls(&self, room_name: &str, open_only: bool, limit: i64) -> Result<Vec<Poll>, Error> {
+        let polls = sqlx::query_as::<_, Poll>(&format!(
+            "SELECT {} FROM polls
+             WHERE room_name = $1 AND (NOT $2 OR closed_at IS NULL)
+             ORDER BY created_at DESC
+             LIMIT $3",
+            POLL_COLUMNS
+        ))
+        .bind(room_name)
+        .bind(open_only)
+        .bind(limit)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(polls)
+    }
+
+    /// Votes per option, and the number of distinct voters
+    pub async fn tally(&self, poll_id: Uuid) -> Result<(Vec<PollTally>, i64), Error> {
+        let tallies = sqlx::query_as::<_, PollTally>(
+            "SELECT option_index, COUNT(*) AS votes, ARRAY_AGG(user_id ORDER BY voted_at) AS voters
+             FROM poll_votes
+             WHERE poll_id = $1
+             GROUP BY option_index"
+        )
+        .bind(poll_id)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let total_voters = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(DISTINCT user_id) FROM poll_votes WHERE poll_id = $1"
+        )
+        .bind(poll_id)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok((tallies, total_voters))
+    }
+
+    /// Replace a user's vote, unless the poll has closed or passed its deadline.
+    ///
+    /// The poll row is locked while voting, so a vote cannot slip in after the poll
+    /// has been closed.
+    pub async fn replace_vote(&self, poll_id: Uuid, user_id: Uuid, options: &[i32]) -> Result<VoteOutcome, Error> {
+        let mut tx = self.db_pool.begin().await?;
+
+        let open = sqlx::query_scalar::<_, bool>(
+            "SELECT closed_at IS NULL AND (closes_at IS NULL OR closes_at > NOW())
+             FROM polls WHERE id = $1
+             FOR UPDATE"
+        )
+        .bind(poll_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        match open {
+            None => return Ok(VoteOutcome::PollNotFound),
+            Some(false) => return Ok(VoteOutcome::PollClosed),
+            Some(true) => {}
+        }
+
+        sqlx::query("DELETE FROM poll_votes WHERE poll_id = $1 AND user_id = $2")
+            .bind(poll_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO poll_votes (poll_id, user_id, option_index)
+             SELECT $1, $2, UNNEST($3::INT[])"
+        )
+        .bind(poll_id)
+        .bind(user_id)
+        .bind(options)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(VoteOutcome::Recorded)
+    }
+
+    /// Close a poll, returning it only if this call closed it
+    pub async fn close_poll(&self, poll_id: Uuid) -> Result<Option<Poll>, Error> {
+        let poll = sqlx::query_as::<_, Poll>(&format!(
+            "UPDATE polls SET closed_at = NOW()
+             WHERE id = $1 AND closed_at IS NULL
+             RETURNING {}",
+            POLL_COLUMNS
+        ))
+        .bind(poll_id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(poll)
+    }
+
+    /// Close every open poll whose deadline has passed
+    pub async fn close_due_polls(&self) -> Result<Vec<Poll>, Error> {
+        let polls = sqlx::query_as::<_, Poll>(&format!(
+            "UPDATE polls SET closed_at = NOW()
+             WHERE closed_at IS NULL AND closes_at <= NOW()
+             RETURNING {}",
+            POLL_COLUMNS
+        ))
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(polls)
+    }
+
+    /// Earliest deadline of the open polls
+    pub async fn next_deadline(&self) -> Result<Option<OffsetDateTime>, Error> {
+        let deadline = sqlx::query_scalar::<_, Option<OffsetDateTime>>(
+            "SELECT MIN(closes_at) FROM polls WHERE closed_at IS NULL"
+        )
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(deadline)
+    }
+
+    pub async fn set_results_message(&self, poll_id: Uuid, message_id: Uuid) -> Result<(), Error> {
+        sqlx::query("UPDATE polls SET results_message_id = $2 WHERE id = $1")
+            .bind(poll_id)
+            .bind(message_id)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(())
+    }
+}
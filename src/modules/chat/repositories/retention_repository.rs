@@ -0,0 +1,179 @@
+use crate::modules::chat::entities::retention::{PurgedAttachment, PurgedMessages, RoomRetention};
+use sqlx::{Pool, Postgres, Error};
+use time::OffsetDateTime;
+
+pub struct RetentionRepository {
+    db_pool: Pool<Postgres>,
+}
+
+impl RetentionRepository {
+    pub fn new(db_pool: Pool<Postgres>) -> Self {
+        Self { db_pool }
+    }
+
+    /// Get the retention settings of a room
+    pub async fn find_retention(&self, room_name: &str) -> Result<Option<RoomRetention>, Error> {
+        let retention = sqlx::query_as::<_, RoomRetention>(
+            "SELECT name, retention_days, legal_hold FROM rooms WHERE name = $1"
+        )
+        .bind(room_name)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(retention)
+    }
+
+    /// Set a room's retention period, and its legal hold if given
+    pub async fn set_retention(
+        &self,
+        room_name: &str,
+        retention_days: Option<i32>,
+        legal_hold: Option<bool>,
+    ) -> Result<Option<RoomRetention>, Error> {
+        let retention = sqlx::query_as::<_, RoomRetention>(
+            "UPDATE rooms
+             SET retention_days = $2, legal_hold = COALESCE($3, legal_hold)
+             WHERE name = $1
+             RETURNING name, retention_days, legal_hold"
+        )
+        .bind(room_name)
+        .bind(retention_days)
+        .bind(legal_hold)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(retention)
+    }
+
+    /// Delete up to `limit` messages older than their room's retention period.
+    ///
+    /// Rooms under legal hold are skipped, and `default_days` applies to rooms without
+    /// their own period. The highest purged sequence number is kept on the room so new
+    /// messages never reuse the numbers of purged ones.
+    pub async fn purge_expired_messages(
+        &self,
+        default_days: Option<i32>,
+        limit: i64,
+    ) -> Result<Vec<PurgedMessages>, Error> {
+        let purged = sqlx::query_as::<_, PurgedMessages>(
+            "WITH expired AS (
+                 SELECT m.id
+                 FROM messages m
+                 LEFT JOIN rooms r ON r.name = m.room_name
+                 WHERE NOT COALESCE(r.legal_hold, FALSE)
+                   AND COALESCE(r.retention_days, $1::INT) > 0
+                   AND m.created_at < NOW() - make_interval(days => COALESCE(r.retention_days, $1::INT))
+                 LIMIT $2
+             ), deleted AS (
+                 DELETE FROM messages m
+                 USING expired e
+                 WHERE m.id = e.id
+                 RETURNING m.room_name, m.seq
+             ), purged AS (
+                 SELECT room_name, COUNT(*) AS deleted, MAX(seq) AS max_seq
+                 FROM deleted
+                 GROUP BY room_name
+             ), marked AS (
+                 UPDATE rooms r
+                 SET last_purged_seq = GREATEST(r.last_purged_seq, p.max_seq)
+                 FROM purged p
+                 WHERE r.name = p.room_name
+             )
+             SELECT room_name, deleted FROM purged"
+        )
+        .bind(default_days)
+        .bind(limit)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(purged)
+    }
+
+    /// Delete up to `limit` room events, such as joins and topic changes, that have
+    /// outlived their room's retention period, and return how many were deleted. Rooms
+    /// under legal hold are skipped.
+    pub async fn purge_expired_room_events(&self, default_days: Option<i32>, limit: i64) -> Result<i64, Error> {
+        let deleted: i64 = sqlx::query_scalar(
+            "WITH expired AS (
+                 SELECT e.room_name, e.seq
+                 FROM room_events e
+                 LEFT JOIN rooms r ON r.name = e.room_name
+                 WHERE NOT COALESCE(r.legal_hold, FALSE)
+                   AND COALESCE(r.retention_days, $1::INT) > 0
+                   AND e.created_at < NOW() - make_interval(days => COALESCE(r.retention_days, $1::INT))
+                 LIMIT $2
+             ), deleted AS (
+                 DELETE FROM room_events e
+                 USING expired x
+                 WHERE e.room_name = x.room_name AND e.seq = x.seq
+                 RETURNING e.room_name, e.seq
+             ), purged AS (
+                 SELECT room_name, MAX(seq) AS max_seq
+                 FROM deleted
+                 GROUP BY room_name
+             ), marked AS (
+                 UPDATE rooms r
+                 SET last_purged_seq = GREATEST(r.last_purged_seq, p.max_seq)
+                 FROM purged p
+                 WHERE r.name = p.room_name
+             )
+             SELECT COUNT(*) FROM deleted"
+        )
+        .bind(default_days)
+        .bind(limit)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(deleted)
+    }
+
+    /// Delete up to `limit` attachments created before `uploaded_before` that no message,
+    /// pending review or unsent scheduled message refers to. Attachments of rooms under legal hold are kept.
+    pub async fn purge_orphaned_attachments(
+        &self,
+        uploaded_before: OffsetDateTime,
+        limit: i64,
+    ) -> Result<Vec<PurgedAttachment>, Error> {
+        let purged = sqlx::query_as::<_, PurgedAttachment>(
+            "WITH orphaned AS (
+                 SELECT a.id
+                 FROM attachments a
+                 LEFT JOIN rooms r ON r.name = a.room_name
+                 WHERE a.created_at < $1
+                   AND NOT COALESCE(r.legal_hold, FALSE)
+                   AND NOT EXISTS (SELECT 1 FROM messages m WHERE m.attachment_id = a.id)
+                   AND NOT EXISTS (
+                       SELECT 1 FROM moderation_queue q
+                       WHERE q.attachment_id = a.id AND q.status = 'pending'
+                   )
+                   AND NOT EXISTS (
+                       SELECT 1 FROM scheduled_messages s
+                       WHERE s.attachment_id = a.id AND s.status IN ('pending', 'sending')
+                   )
+                 LIMIT $2
+             )
+             DELETE FROM attachments a
+             USING orphaned o
+             WHERE a.id = o.id
+             RETURNING a.id, a.sha256, a.room_name, a.size_bytes"
+        )
+        .bind(uploaded_before)
+        .bind(limit)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(purged)
+    }
+
+    /// Check whether any attachment still uses a stored blob
+    pub async fn is_blob_referenced(&self, sha256: &str) -> Result<bool, Error> {
+        let referenced = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM attachments WHERE sha256 = $1)"
+        )
+        .bind(sha256)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(referenced)
+    }
+}
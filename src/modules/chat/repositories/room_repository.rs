@@ -0,0 +1,244 @@
+use crate::modules::chat::entities::room::{PinnedMessage, Room, DEFAULT_ROOM};
+use sqlx::{Pool, Postgres, Error};
+use uuid::Uuid;
+
+/// Outcome of pinning a message
+pub enum PinOutcome {
+    Pinned,
+    AlreadyPinned,
+    /// The room already has the maximum number of pinned messages
+    LimitReached,
+    /// No message with this ID was sent in the room
+    MessageNotFound,
+}
+
+pub struct RoomRepository {
+    db_pool: Pool<Postgres>,
+}
+
+impl RoomRepository {
+    pub fn new(db_pool: Pool<Postgres>) -> Self {
+        Self { db_pool }
+    }
+
+    /// Record that a user has joined a room, creating the room on first use.
+    ///
+    /// The first member of a room becomes its owner, except in the default room, which
+    /// nobody owns. Private rooms only admit users who are already members; returns
+    /// whether the user is a member afterwards.
+    pub async fn add_member(&self, room_name: &str, user_id: Uuid) -> Result<bool, Error> {
+        let mut tx = self.db_pool.begin().await?;
+
+        let created = sqlx::query(
+            "INSERT INTO rooms (name, created_by)
+             VALUES ($1, $2)
+             ON CONFLICT (name) DO NOTHING"
+        )
+        .bind(room_name)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            == 1;
+
+        let joined = sqlx::query(
+            "INSERT INTO room_members (room_name, user_id, role)
+             SELECT $1, $2, $3
+             FROM rooms WHERE name = $1 AND NOT private
+             ON CONFLICT (room_name, user_id) DO NOTHING"
+        )
+        .bind(room_name)
+        .bind(user_id)
+        .bind(if created && room_name != DEFAULT_ROOM { "owner" } else { "member" })
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            == 1;
+        let is_member = joined
+            || sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS (SELECT 1 FROM room_members WHERE room_name = $1 AND user_id = $2)"
+            )
+            .bind(room_name)
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(is_member)
+    }
+
+    /// Check whether a user may enter a room: any room that does not exist yet or is
+    /// public, and private rooms they are a member of
+    pub async fn can_join(&self, room_name: &str, user_id: Uuid) -> Result<bool, Error> {
+        let can_join = sqlx::query_scalar::<_, bool>(
+            "SELECT NOT EXISTS (SELECT 1 FROM rooms WHERE name = $1 AND private)
+                 OR EXISTS (SELECT 1 FROM room_members WHERE room_name = $1 AND user_id = $2)"
+        )
+        .bind(room_name)
+        .bind(user_id)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(can_join)
+    }
+
+    /// Make a room private or public, returning `None` if it does not exist
+    pub async fn set_private(&self, room_name: &str, private: bool) -> Result<Option<bool>, Error> {
+        let private = sqlx::query_scalar::<_, bool>(
+            "UPDATE rooms SET private = $2 WHERE name = $1 RETURNING private"
+        )
+        .bind(room_name)
+        .bind(private)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(private)
+    }
+
+    /// Check whether a user has joined a room
+    pub async fn is_member(&self, room_name: &str, user_id: Uuid) -> Result<bool, Error> {
+        let is_member = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM room_members WHERE room_name = $1 AND user_id = $2)"
+        )
+        .bind(room_name)
+        .bind(user_id)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(is_member)
+    }
+
+    /// Get a member's role in a room, or `None` if they are not a member
+    pub async fn find_member_role(&self, room_name: &str, user_id: Uuid) -> Result<Option<String>, Error> {
+        let role = sqlx::query_scalar::<_, String>(
+            "SELECT role FROM room_members WHERE room_name = $1 AND user_id = $2"
+        )
+        .bind(room_name)
+        .bind(user_id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(role)
+    }
+
+    /// Change a member's role, returning whether the member exists
+    pub async fn set_member_role(&self, room_name: &str, user_id: Uuid, role: &str) -> Result<bool, Error> {
+        let result = sqlx::query(
+            "UPDATE room_members SET role = $3 WHERE room_name = $1 AND user_id = $2"
+        )
+        .bind(room_name)
+        .bind(user_id)
+        .bind(role)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Find a room by name
+    pub async fn find_room(&self, room_name: &str) -> Result<Option<Room>, Error> {
+        let room = sqlx::query_as::<_, Room>(
+            "SELECT name, created_by, topic, topic_set_by, topic_set_at, created_at
+             FROM rooms WHERE name = $1"
+        )
+        .bind(room_name)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(room)
+    }
+
+    /// Set or clear the topic of a room
+    pub async fn set_topic(&self, room_name: &str, topic: Option<&str>, set_by: Uuid) -> Result<Option<Room>, Error> {
+        let room = sqlx::query_as::<_, Room>(
+            "UPDATE rooms SET topic = $2, topic_set_by = $3, topic_set_at = NOW()
+             WHERE name = $1
+             RETURNING name, created_by, topic, topic_set_by, topic_set_at, created_at"
+        )
+        .bind(room_name)
+        .bind(topic)
+        .bind(set_by)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(room)
+    }
+
+    /// List pinned messages of a room, oldest pin first
+    pub async fn list_pinned_messages(&self, room_name: &str) -> Result<Vec<PinnedMessage>, Error> {
+        let pinned = sqlx::query_as::<_, PinnedMessage>(
+            "SELECT m.id AS message_id, m.room_name, COALESCE(m.user_id, m.incoming_webhook_id, uuid_nil()) AS user_id, m.username, m.body, m.created_at,
+                    p.pinned_by, p.pinned_at
+             FROM pinned_messages p
+             JOIN messages m ON m.id = p.message_id
+             WHERE p.room_name = $1
+             ORDER BY p.pinned_at"
+        )
+        .bind(room_name)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(pinned)
+    }
+
+    /// Pin a message of the room unless it already has `max_pins` pinned messages.
+    ///
+    /// The room row is locked meanwhile, so concurrent pins cannot exceed the limit.
+    pub async fn pin_message(&self, room_name: &str, message_id: Uuid, pinned_by: Uuid, max_pins: i64) -> Result<PinOutcome, Error> {
+        let mut tx = self.db_pool.begin().await?;
+
+        let room = sqlx::query_scalar::<_, String>("SELECT name FROM rooms WHERE name = $1 FOR UPDATE")
+            .bind(room_name)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if room.is_none() {
+            return Ok(PinOutcome::MessageNotFound);
+        }
+
+        let (pinned, already_pinned) = sqlx::query_as::<_, (i64, bool)>(
+            "SELECT COUNT(*), COALESCE(BOOL_OR(message_id = $2), FALSE)
+             FROM pinned_messages WHERE room_name = $1"
+        )
+        .bind(room_name)
+        .bind(message_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if already_pinned {
+            return Ok(PinOutcome::AlreadyPinned);
+        }
+        if pinned >= max_pins {
+            return Ok(PinOutcome::LimitReached);
+        }
+
+        let inserted = sqlx::query(
+            "INSERT INTO pinned_messages (room_name, message_id, pinned_by)
+             SELECT room_name, id, $3 FROM messages WHERE id = $2 AND room_name = $1"
+        )
+        .bind(room_name)
+        .bind(message_id)
+        .bind(pinned_by)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            == 1;
+        if !inserted {
+            return Ok(PinOutcome::MessageNotFound);
+        }
+
+        tx.commit().await?;
+        Ok(PinOutcome::Pinned)
+    }
+
+    /// Unpin a message, returning whether it was pinned
+    pub async fn unpin_message(&self, room_name: &str, message_id: Uuid) -> Result<bool, Error> {
+        let result = sqlx::query(
+            "DELETE FROM pinned_messages WHERE room_name = $1 AND message_id = $2"
+        )
+        .bind(room_name)
+        .bind(message_id)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
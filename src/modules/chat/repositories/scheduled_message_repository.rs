@@ -0,0 +1,179 @@
+use crate::modules::chat::entities::scheduled_message::ScheduledMessage;
+use sqlx::{Pool, Postgres, Error};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+const SCHEDULED_MESSAGE_COLUMNS: &str =
+    "id, room_name, user_id, body, attachment_id, is_action, send_at, status, error, message_id, sent_at, created_at, updated_at";
+
+/// Fields of a message to be scheduled
+pub struct NewScheduledMessage<'a> {
+    pub room_name: &'a str,
+    pub user_id: Uuid,
+    pub body: &'a str,
+    pub attachment_id: Option<Uuid>,
+    pub is_action: bool,
+    pub send_at: OffsetDateTime,
+}
+
+pub struct ScheduledMessageRepository {
+    db_pool: Pool<Postgres>,
+}
+
+impl ScheduledMessageRepository {
+    pub fn new(db_pool: Pool<Postgres>) -> Self {
+        Self { db_pool }
+    }
+
+    pub async fn create_scheduled_message(&self, message: &NewScheduledMessage<'_>) -> Result<ScheduledMessage, Error> {
+        let scheduled = sqlx::query_as::<_, ScheduledMessage>(&format!(
+            "INSERT INTO scheduled_messages (room_name, user_id, body, attachment_id, is_action, send_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING {}",
+            SCHEDULED_MESSAGE_COLUMNS
+        ))
+        .bind(message.room_name)
+        .bind(message.user_id)
+        .bind(message.body)
+        .bind(message.attachment_id)
+        .bind(message.is_action)
+        .bind(message.send_at)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(scheduled)
+    }
+
+    /// A user's scheduled message
+    pub async fn find_scheduled_message(&self, id: Uuid, user_id: Uuid) -> Result<Option<ScheduledMessage>, Error> {
+        let scheduled = sqlx::query_as::<_, ScheduledMessage>(&format!(
+            "SELECT {} FROM scheduled_messages WHERE id = $1 AND user_id = $2",
+            SCHEDULED_MESSAGE_COLUMNS
+        ))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(scheduled)
+    }
+
+    /// A user's messages still waiting to be sent, soonest first
+    pub async fn list_pending(&self, user_id: Uuid, room_name: Option<&str>) -> Result<Vec<ScheduledMessage>, Error> {
+        let scheduled = sqlx::query_as::<_, ScheduledMessage>(&format!(
+            "SELECT {} FROM scheduled_messages
+             WHERE user_id = $1 AND status = 'pending' AND ($2::VARCHAR IS NULL OR room_name = $2)
+             ORDER BY send_at",
+            SCHEDULED_MESSAGE_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(room_name)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(scheduled)
+    }
+
+    /// Change a pending message, returning it only if it was still pending
+    pub async fn update_pending(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        body: Option<&str>,
+        send_at: Option<OffsetDateTime>,
+    ) -> Result<Option<ScheduledMessage>, Error> {
+        let scheduled = sqlx::query_as::<_, ScheduledMessage>(&format!(
+            "UPDATE scheduled_messages
+             SET body = COALESCE($3, body), send_at = COALESCE($4, send_at), updated_at = NOW()
+             WHERE id = $1 AND user_id = $2 AND status = 'pending'
+             RETURNING {}",
+            SCHEDULED_MESSAGE_COLUMNS
+        ))
+        .bind(id)
+        .bind(user_id)
+        .bind(body)
+        .bind(send_at)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(scheduled)
+    }
+
+    /// Delete a pending message; returns whether it was still pending
+    pub async fn delete_pending(&self, id: Uuid, user_id: Uuid) -> Result<bool, Error> {
+        let result = sqlx::query(
+            "DELETE FROM scheduled_messages WHERE id = $1 AND user_id = $2 AND status = 'pending'"
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Claim the earliest due message for sending, so it can no longer be edited or cancelled
+    pub async fn claim_due(&self) -> Result<Option<ScheduledMessage>, Error> {
+        let scheduled = sqlx::query_as::<_, ScheduledMessage>(&format!(
+            "UPDATE scheduled_messages SET status = 'sending', updated_at = NOW()
+             WHERE id = (
+                 SELECT id FROM scheduled_messages
+                 WHERE status = 'pending' AND send_at <= NOW()
+                 ORDER BY send_at
+                 LIMIT 1
+                 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING {}",
+            SCHEDULED_MESSAGE_COLUMNS
+        ))
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(scheduled)
+    }
+
+    /// Return messages claimed before a restart to the queue
+    pub async fn release_claimed(&self) -> Result<u64, Error> {
+        let result = sqlx::query(
+            "UPDATE scheduled_messages SET status = 'pending', updated_at = NOW() WHERE status = 'sending'"
+        )
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Record what became of a claimed message
+    pub async fn finish(
+        &self,
+        id: Uuid,
+        status: &str,
+        message_id: Option<Uuid>,
+        error: Option<&str>,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            "UPDATE scheduled_messages
+             SET status = $2, message_id = $3, error = $4, sent_at = NOW(), updated_at = NOW()
+             WHERE id = $1"
+        )
+        .bind(id)
+        .bind(status)
+        .bind(message_id)
+        .bind(error)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Earliest send time of the pending messages
+    pub async fn next_send_at(&self) -> Result<Option<OffsetDateTime>, Error> {
+        let send_at = sqlx::query_scalar::<_, Option<OffsetDateTime>>(
+            "SELECT MIN(send_at) FROM scheduled_messages WHERE status = 'pending'"
+        )
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(send_at)
+    }
+}
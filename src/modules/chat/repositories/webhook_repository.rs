@@ -0,0 +1,184 @@
+use crate::modules::chat::entities::webhook::{ClaimedDelivery, RoomWebhook, WebhookDelivery};
+use sqlx::{Pool, Postgres, Error};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+pub struct WebhookRepository {
+    db_pool: Pool<Postgres>,
+}
+
+impl WebhookRepository {
+    pub fn new(db_pool: Pool<Postgres>) -> Self {
+        Self { db_pool }
+    }
+
+    pub async fn create_webhook(
+        &self,
+        room_name: &str,
+        url: &str,
+        secret: &str,
+        created_by: Uuid,
+    ) -> Result<RoomWebhook, Error> {
+        let webhook = sqlx::query_as::<_, RoomWebhook>(
+            "INSERT INTO room_webhooks (room_name, url, secret, created_by)
+             VALUES ($1, $2, $3, $4)
+             RETURNING id, room_name, url, secret, created_by, created_at"
+        )
+        .bind(room_name)
+        .bind(url)
+        .bind(secret)
+        .bind(created_by)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(webhook)
+    }
+
+    pub async fn list_webhooks(&self, room_name: &str) -> Result<Vec<RoomWebhook>, Error> {
+        let webhooks = sqlx::query_as::<_, RoomWebhook>(
+            "SELECT id, room_name, url, secret, created_by, created_at
+             FROM room_webhooks WHERE room_name = $1
+             ORDER BY created_at"
+        )
+        .bind(room_name)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(webhooks)
+    }
+
+    pub async fn find_webhook(&self, room_name: &str, id: Uuid) -> Result<Option<RoomWebhook>, Error> {
+        let webhook = sqlx::query_as::<_, RoomWebhook>(
+            "SELECT id, room_name, url, secret, created_by, created_at
+             FROM room_webhooks WHERE room_name = $1 AND id = $2"
+        )
+        .bind(room_name)
+        .bind(id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(webhook)
+    }
+
+    /// Delete a webhook and its delivery log, returning whether it existed
+    pub async fn delete_webhook(&self, room_name: &str, id: Uuid) -> Result<bool, Error> {
+        let result = sqlx::query("DELETE FROM room_webhooks WHERE room_name = $1 AND id = $2")
+            .bind(room_name)
+            .bind(id)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Queue an event for every webhook of the room, returning how many deliveries were queued
+    pub async fn enqueue_deliveries(
+        &self,
+        room_name: &str,
+        event_id: Uuid,
+        event_type: &str,
+        payload: &str,
+    ) -> Result<u64, Error> {
+        let result = sqlx::query(
+            "INSERT INTO webhook_deliveries (webhook_id, event_id, event_type, payload)
+             SELECT id, $2, $3, $4 FROM room_webhooks WHERE room_name = $1"
+        )
+        .bind(room_name)
+        .bind(event_id)
+        .bind(event_type)
+        .bind(payload)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Claim pending deliveries that are due, counting the attempt up front.
+    ///
+    /// Claimed deliveries are leased until `lease_until`, so a delivery whose worker
+    /// dies mid-attempt is picked up again once the lease expires.
+    pub async fn claim_due_deliveries(
+        &self,
+        limit: i64,
+        lease_until: OffsetDateTime,
+    ) -> Result<Vec<ClaimedDelivery>, Error> {
+        let deliveries = sqlx::query_as::<_, ClaimedDelivery>(
+            "UPDATE webhook_deliveries d
+             SET attempts = d.attempts + 1, next_attempt_at = $2
+             FROM room_webhooks w
+             WHERE w.id = d.webhook_id
+               AND d.id IN (
+                   SELECT id FROM webhook_deliveries
+                   WHERE status = 'pending' AND next_attempt_at <= NOW()
+                   ORDER BY next_attempt_at
+                   LIMIT $1
+                   FOR UPDATE SKIP LOCKED
+               )
+             RETURNING d.id, d.event_type, d.payload, d.attempts, w.url, w.secret"
+        )
+        .bind(limit)
+        .bind(lease_until)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(deliveries)
+    }
+
+    pub async fn mark_delivered(&self, id: Uuid, status_code: i32) -> Result<(), Error> {
+        sqlx::query(
+            "UPDATE webhook_deliveries
+             SET status = 'delivered', last_status_code = $2, last_error = NULL, delivered_at = NOW()
+             WHERE id = $1"
+        )
+        .bind(id)
+        .bind(status_code)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record a failed attempt; without a retry time the delivery is given up on
+    pub async fn mark_attempt_failed(
+        &self,
+        id: Uuid,
+        status_code: Option<i32>,
+        error: &str,
+        retry_at: Option<OffsetDateTime>,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            "UPDATE webhook_deliveries
+             SET status = CASE WHEN $4::TIMESTAMPTZ IS NULL THEN 'failed' ELSE 'pending' END,
+                 last_status_code = $2,
+                 last_error = $3,
+                 next_attempt_at = COALESCE($4, next_attempt_at)
+             WHERE id = $1"
+        )
+        .bind(id)
+        .bind(status_code)
+        .bind(error)
+        .bind(retry_at)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Most recent deliveries of a webhook, newest first
+    pub async fn list_deliveries(&self, webhook_id: Uuid, limit: i64) -> Result<Vec<WebhookDelivery>, Error> {
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(
+            "SELECT id, webhook_id, event_id, event_type, payload, status, attempts, last_status_code,
+                    last_error, next_attempt_at, created_at, delivered_at
+             FROM webhook_deliveries
+             WHERE webhook_id = $1
+             ORDER BY created_at DESC
+             LIMIT $2"
+        )
+        .bind(webhook_id)
+        .bind(limit)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(deliveries)
+    }
+}
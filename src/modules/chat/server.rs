@@ -1,6 +1,6 @@
 use axum::{
     extract::{
-        ws::{Message, WebSocket, WebSocketUpgrade},
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
         Query, State,
     },
     http::{StatusCode, header},
@@ -8,55 +8,1015 @@ use axum::{
 };
 use futures::{sink::SinkExt, stream::StreamExt};
 use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
 };
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 use uuid::Uuid;
 
+use crate::config::env::ChatConfig;
+use crate::modules::auth::service::AuthService;
 use crate::modules::auth::utils::jwt::JwtUtil;
+use crate::modules::chat::commands::{parse_command, CommandAction, CommandContext, CommandRegistry};
+use crate::modules::chat::dto::announcement_dto::AnnouncementResponse;
+use crate::modules::chat::dto::poll_dto::{CreatePollDto, PollResponse};
+use crate::modules::chat::dto::room_dto::{PinnedMessagesResponse, RoomTopicResponse};
+use crate::modules::chat::dto::scheduled_message_dto::ScheduleMessageDto;
+use crate::modules::chat::entities::room::DEFAULT_ROOM;
+use crate::modules::chat::moderation::{ModerationOutcome, ModerationPipeline};
+use crate::modules::chat::plugins::{MembershipEvent, PluginMessage, PluginRegistry};
+use crate::modules::chat::rate_limit::RateLimiter;
+use crate::modules::chat::repositories::message_repository::NewMessage;
+use crate::modules::chat::repositories::moderation_repository::NewFlaggedMessage;
+use crate::modules::chat::repositories::{MessageRepository, RoomRepository};
+use crate::modules::chat::service::poll_service::{announcement_text, results_text, validate_poll};
+use crate::modules::chat::service::{
+    AttachmentService, BlockService, ModerationService, NotificationService, PollService, RoomService, ScheduledMessageService,
+    WebhookService,
+};
+use crate::modules::chat::webhooks::{WebhookEvent, WebhookEventType};
+use crate::utils::origin::OriginPolicy;
+
+/// Most chat messages replayed to a resuming connection
+const MAX_REPLAYED_MESSAGES: i64 = 500;
+/// How long a WebSocket ticket can be redeemed after it was issued
+pub const TICKET_TTL: std::time::Duration = std::time::Duration::from_secs(30);
+/// How long before its token expires a connection is asked to send a `reauth` frame
+const REAUTH_WARNING_SECS: u64 = 60;
 
 type RoomName = String;
 type UserName = String; 
 type UserId = Uuid;
+type ConnectionId = Uuid;
+/// Last chat message sequence number of a room, `None` until loaded from persistence
+pub type RoomSequence = Arc<tokio::sync::Mutex<Option<u64>>>;
 
 #[derive(Debug, Clone)]
 pub struct ConnectedUser {
+    pub user_id: UserId,
+    pub username: UserName,
+    pub room_name: RoomName,
+}
+
+/// Control events delivered to a single connection's send task
+#[derive(Debug)]
+pub enum ConnectionEvent {
+    /// A frame for this connection only, e.g. a command reply or direct message
+    Frame(Message),
+    /// Replace the room subscription, or drop it when leaving a room
+    SwitchRoom(Option<RoomSubscription>),
+    /// Tell the client why, then close the socket
+    Close(String),
+}
+
+/// A frame broadcast to everyone in a room
+#[derive(Debug, Clone)]
+pub struct RoomEvent {
+    /// Room sequence number, unset only for events that could not be persisted
+    pub seq: Option<u64>,
+    /// Author of a user's chat message, hidden from connections of users who blocked them
+    pub sender: Option<UserId>,
+    pub frame: Message,
+}
+
+impl From<Message> for RoomEvent {
+    fn from(frame: Message) -> Self {
+        Self {
+            seq: None,
+            sender: None,
+            frame,
+        }
+    }
+}
+
+/// Users a user has blocked, shared by all of that user's connections
+pub type BlockList = Arc<std::sync::RwLock<HashSet<UserId>>>;
+
+/// Whether a room event comes from a user in the block list
+fn is_hidden(blocked: &BlockList, event: &RoomEvent) -> bool {
+    event
+        .sender
+        .is_some_and(|sender| blocked.read().unwrap().contains(&sender))
+}
+
+/// A connection's subscription to a room's broadcasts
+#[derive(Debug)]
+pub struct RoomSubscription {
+    pub receiver: broadcast::Receiver<RoomEvent>,
+    /// Highest sequence number already delivered; older events are skipped
+    pub last_seq: u64,
+}
+
+impl RoomSubscription {
+    /// Whether an event should be delivered, advancing the cursor for sequenced events
+    pub fn accept(&mut self, event: &RoomEvent) -> bool {
+        match event.seq {
+            Some(seq) if seq <= self.last_seq => false,
+            Some(seq) => {
+                self.last_seq = seq;
+                true
+            }
+            None => true,
+        }
+    }
+}
+
+/// A single open WebSocket; one user may hold several at once
+#[derive(Debug, Clone)]
+pub struct ConnectionHandle {
+    pub user_id: UserId,
     pub username: UserName,
+    pub room_name: Option<RoomName>,
+    /// Issue time of the access token the connection last authenticated with, in milliseconds
+    pub token_issued_at: u64,
+    pub sender: mpsc::UnboundedSender<ConnectionEvent>,
+}
+
+/// Single-use credential for opening a WebSocket without putting the access token in the URL
+#[derive(Debug, Clone)]
+pub struct WsTicket {
+    pub user_id: UserId,
+    /// Issue time of the access token the ticket was issued for, in milliseconds
+    pub token_issued_at: u64,
+    /// `exp` of the access token; the connection lives no longer than the token
+    pub token_expires_at: u64,
+    /// Ticket cannot be redeemed after this instant
+    pub expires_at: std::time::Instant,
 }
 
 #[derive(Debug, Clone)]
 pub struct ChatState {
     pub connected_users: Arc<Mutex<HashMap<UserId, ConnectedUser>>>,
-    pub rooms: Arc<Mutex<HashMap<RoomName, broadcast::Sender<Message>>>>,
+    pub connections: Arc<Mutex<HashMap<ConnectionId, ConnectionHandle>>>,
+    pub rooms: Arc<Mutex<HashMap<RoomName, broadcast::Sender<RoomEvent>>>>,
+    /// Last sequence number per room; held while a message is persisted and broadcast
+    /// so rooms see chat messages in sequence order
+    pub sequences: Arc<Mutex<HashMap<RoomName, RoomSequence>>>,
+    /// Topics changed since startup; the database is authoritative when configured
+    pub topics: Arc<Mutex<HashMap<RoomName, RoomTopicResponse>>>,
+    pub commands: Arc<CommandRegistry>,
+    pub plugins: Arc<PluginRegistry>,
+    /// Wakes the webhook delivery worker when events are queued
+    pub webhook_notify: Arc<tokio::sync::Notify>,
+    /// Wakes the poll close job when a poll with a deadline is created
+    pub poll_notify: Arc<tokio::sync::Notify>,
+    /// Wakes the scheduler when a message is scheduled or rescheduled
+    pub schedule_notify: Arc<tokio::sync::Notify>,
+    /// Messages posted per incoming webhook
+    pub incoming_webhook_limiter: Arc<RateLimiter<Uuid>>,
+    /// Words masked in every room
+    pub profanity_words: Arc<Vec<String>>,
+    /// Server administrators, who may act on any room and make announcements
+    pub admin_users: Arc<Vec<UserId>>,
+    /// Moderation pipelines built from each room's rules, dropped when the rules change
+    pub moderation: Arc<Mutex<HashMap<RoomName, Arc<ModerationPipeline>>>>,
+    /// Unredeemed WebSocket tickets
+    pub tickets: Arc<Mutex<HashMap<String, WsTicket>>>,
+    /// Browser origins allowed to open sockets
+    pub origin_policy: Arc<OriginPolicy>,
+    /// Block lists of connected users, loaded when they connect
+    pub block_lists: Arc<Mutex<HashMap<UserId, BlockList>>>,
+    /// Announcement sent to every connection that joins a room until it expires;
+    /// kept in memory only, so a restart clears it
+    pub banner: Arc<Mutex<Option<AnnouncementResponse>>>,
+    /// Database used for room membership and attachments; chat runs in-memory only without it
+    pub db: Option<PgPool>,
+}
+
+impl Default for ChatState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ChatState {
     pub fn new() -> Self {
+        let config = ChatConfig::from_env();
         Self {
             connected_users: Arc::new(Mutex::new(HashMap::new())),
+            connections: Arc::new(Mutex::new(HashMap::new())),
             rooms: Arc::new(Mutex::new(HashMap::new())),
+            sequences: Arc::new(Mutex::new(HashMap::new())),
+            topics: Arc::new(Mutex::new(HashMap::new())),
+            commands: Arc::new(CommandRegistry::with_builtin_commands()),
+            plugins: Arc::new(PluginRegistry::new()),
+            webhook_notify: Arc::new(tokio::sync::Notify::new()),
+            poll_notify: Arc::new(tokio::sync::Notify::new()),
+            schedule_notify: Arc::new(tokio::sync::Notify::new()),
+            incoming_webhook_limiter: Arc::new(RateLimiter::new(
+                config.incoming_webhook_rate_limit,
+                std::time::Duration::from_secs(60),
+            )),
+            profanity_words: Arc::new(config.profanity_words),
+            admin_users: Arc::new(config.admin_users),
+            moderation: Arc::new(Mutex::new(HashMap::new())),
+            tickets: Arc::new(Mutex::new(HashMap::new())),
+            origin_policy: Arc::new(OriginPolicy::default()),
+            block_lists: Arc::new(Mutex::new(HashMap::new())),
+            banner: Arc::new(Mutex::new(None)),
+            db: None,
+        }
+    }
+
+    pub fn with_pool(db: PgPool) -> Self {
+        Self {
+            db: Some(db),
+            ..Self::new()
         }
     }
 
-    pub fn add_user_to_room(&self, user_id: UserId, username: UserName, _room_name: RoomName) -> Result<(), String> {
+    /// Replace the plugins run for every connection
+    pub fn with_plugins(self, plugins: PluginRegistry) -> Self {
+        Self {
+            plugins: Arc::new(plugins),
+            ..self
+        }
+    }
+
+    /// Replace the origins allowed to open sockets, which only include the server's own
+    /// by default
+    pub fn with_origin_policy(self, origin_policy: Arc<OriginPolicy>) -> Self {
+        Self { origin_policy, ..self }
+    }
+
+    /// Replace the server administrators
+    pub fn with_admins(self, admin_users: Vec<UserId>) -> Self {
+        Self {
+            admin_users: Arc::new(admin_users),
+            ..self
+        }
+    }
+
+    pub fn is_admin(&self, user_id: UserId) -> bool {
+        self.admin_users.contains(&user_id)
+    }
+
+    pub fn add_user_to_room(&self, user_id: UserId, username: UserName, room_name: RoomName) -> Result<(), String> {
         let mut users = self.connected_users.lock().unwrap();
         users.insert(
             user_id,
             ConnectedUser {
+                user_id,
                 username,
+                room_name,
             },
         );
         Ok(())
     }
 
+    pub fn get_user(&self, user_id: UserId) -> Option<ConnectedUser> {
+        let users = self.connected_users.lock().unwrap();
+        users.get(&user_id).cloned()
+    }
+
     pub fn remove_user(&self, user_id: UserId) -> Option<ConnectedUser> {
         let mut users = self.connected_users.lock().unwrap();
         users.remove(&user_id)
     }
 
-    pub fn get_room_broadcaster(&self, room_name: &str) -> broadcast::Sender<Message> {
+    pub fn register_connection(&self, connection_id: ConnectionId, handle: ConnectionHandle) {
+        let mut connections = self.connections.lock().unwrap();
+        connections.insert(connection_id, handle);
+    }
+
+    pub fn unregister_connection(&self, connection_id: ConnectionId) -> Option<ConnectionHandle> {
+        let mut connections = self.connections.lock().unwrap();
+        connections.remove(&connection_id)
+    }
+
+    pub fn set_connection_room(&self, connection_id: ConnectionId, room_name: Option<RoomName>) {
+        let mut connections = self.connections.lock().unwrap();
+        if let Some(connection) = connections.get_mut(&connection_id) {
+            connection.room_name = room_name;
+        }
+    }
+
+    pub fn has_connections(&self, user_id: UserId) -> bool {
+        let connections = self.connections.lock().unwrap();
+        connections.values().any(|connection| connection.user_id == user_id)
+    }
+
+    /// Sorted, de-duplicated names of users with a connection in the room
+    pub fn usernames_in_room(&self, room_name: &str) -> Vec<UserName> {
+        let connections = self.connections.lock().unwrap();
+        let mut usernames: Vec<UserName> = connections
+            .values()
+            .filter(|connection| connection.room_name.as_deref() == Some(room_name))
+            .map(|connection| connection.username.clone())
+            .collect();
+        usernames.sort();
+        usernames.dedup();
+        usernames
+    }
+
+    /// Look up a connected user by display name, ignoring case, returning their ID and
+    /// the name as they appear under
+    pub fn find_user_by_username(&self, username: &str) -> Option<(UserId, UserName)> {
+        let connections = self.connections.lock().unwrap();
+        connections
+            .values()
+            .find(|connection| connection.username.eq_ignore_ascii_case(username))
+            .map(|connection| (connection.user_id, connection.username.clone()))
+    }
+
+    /// Deliver a frame to every connection of a user, returning how many received it
+    pub fn send_to_user(&self, user_id: UserId, frame: Message) -> usize {
+        let connections = self.connections.lock().unwrap();
+        connections
+            .values()
+            .filter(|connection| connection.user_id == user_id)
+            .filter(|connection| connection.sender.send(ConnectionEvent::Frame(frame.clone())).is_ok())
+            .count()
+    }
+
+    /// Issue a single-use WebSocket ticket for an authenticated access token
+    pub fn issue_ticket(&self, user_id: UserId, token_issued_at: u64, token_expires_at: u64) -> String {
+        let ticket = format!("wst_{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let now = std::time::Instant::now();
+        let mut tickets = self.tickets.lock().unwrap();
+        tickets.retain(|_, issued| issued.expires_at > now);
+        tickets.insert(
+            ticket.clone(),
+            WsTicket {
+                user_id,
+                token_issued_at,
+                token_expires_at,
+                expires_at: now + TICKET_TTL,
+            },
+        );
+        ticket
+    }
+
+    /// Consume a ticket; it is removed whether or not it is still valid
+    pub fn redeem_ticket(&self, ticket: &str) -> Option<WsTicket> {
+        let redeemed = self.tickets.lock().unwrap().remove(ticket)?;
+        (redeemed.expires_at > std::time::Instant::now()).then_some(redeemed)
+    }
+
+    /// Reject tokens of deleted users and tokens issued before the user revoked them
+    pub async fn check_not_revoked(&self, user_id: UserId, issued_at_ms: u64) -> Result<(), String> {
+        let Some(db) = &self.db else {
+            return Ok(());
+        };
+        let auth_service = AuthService::new(db.clone(), crate::config::environment::Environment::from_env());
+        match auth_service.is_token_revoked(user_id, issued_at_ms).await {
+            Ok(false) => Ok(()),
+            Ok(true) => Err("Token has been revoked".to_string()),
+            Err(e) => {
+                eprintln!("Failed to check token revocation: {}", e);
+                Err("Token could not be verified, please try again".to_string())
+            }
+        }
+    }
+
+    /// Record the access token a connection re-authenticated with
+    pub fn set_connection_token(&self, connection_id: ConnectionId, token_issued_at: u64) {
+        if let Some(connection) = self.connections.lock().unwrap().get_mut(&connection_id) {
+            connection.token_issued_at = token_issued_at;
+        }
+    }
+
+    /// Close the connections whose tokens were revoked within `within`, whichever process
+    /// revoked them, e.g. `user revoke-tokens`. Returns how many were closed.
+    pub async fn close_revoked_connections(&self, within: std::time::Duration) -> usize {
+        let Some(db) = &self.db else {
+            return 0;
+        };
+        let auth_service = AuthService::new(db.clone(), crate::config::environment::Environment::from_env());
+        let revocations = match auth_service.recent_revocations(within).await {
+            Ok(revocations) => revocations,
+            Err(e) => {
+                eprintln!("Failed to load token revocations: {}", e);
+                return 0;
+            }
+        };
+
+        let connections = self.connections.lock().unwrap();
+        revocations
+            .iter()
+            .map(|(user_id, revoked_before)| {
+                connections
+                    .values()
+                    .filter(|connection| connection.user_id == *user_id && connection.token_issued_at <= *revoked_before)
+                    .filter(|connection| {
+                        let reason = "Your tokens have been revoked".to_string();
+                        connection.sender.send(ConnectionEvent::Close(reason)).is_ok()
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Close connections of revoked tokens every `interval` until the process exits.
+    ///
+    /// Each check looks back two intervals, so a revocation committed while the previous
+    /// check ran is still seen; connections opened since use newer tokens and stay open.
+    pub async fn run_revocation_watch(self, interval: std::time::Duration) {
+        loop {
+            tokio::time::sleep(interval).await;
+            self.close_revoked_connections(interval * 2).await;
+        }
+    }
+
+    /// Close every connection of a user, e.g. after their tokens were revoked
+    pub fn disconnect_user(&self, user_id: UserId, reason: &str) -> usize {
+        let connections = self.connections.lock().unwrap();
+        connections
+            .values()
+            .filter(|connection| connection.user_id == user_id)
+            .filter(|connection| connection.sender.send(ConnectionEvent::Close(reason.to_string())).is_ok())
+            .count()
+    }
+
+    /// Block list of a user, loaded from the database unless one of their connections
+    /// already has it
+    pub async fn block_list(&self, user_id: UserId) -> BlockList {
+        if let Some(blocked) = self.block_lists.lock().unwrap().get(&user_id) {
+            return blocked.clone();
+        }
+
+        let blocked = match &self.db {
+            Some(db) => BlockService::new(db.clone())
+                .blocked_ids(user_id)
+                .await
+                .map_err(|e| e.to_string())
+                .unwrap_or_else(|e| {
+                    eprintln!("Failed to load block list: {}", e);
+                    Vec::new()
+                }),
+            None => Vec::new(),
+        };
+        self.block_lists
+            .lock()
+            .unwrap()
+            .entry(user_id)
+            .or_insert_with(|| Arc::new(std::sync::RwLock::new(blocked.into_iter().collect())))
+            .clone()
+    }
+
+    /// Apply a block or unblock to the user's live connections
+    pub fn set_blocked(&self, user_id: UserId, blocked_id: UserId, blocked: bool) {
+        if let Some(block_list) = self.block_lists.lock().unwrap().get(&user_id) {
+            let mut block_list = block_list.write().unwrap();
+            if blocked {
+                block_list.insert(blocked_id);
+            } else {
+                block_list.remove(&blocked_id);
+            }
+        }
+    }
+
+    /// Whether either user has blocked the other
+    pub async fn is_blocked_between(&self, user_id: UserId, other_id: UserId) -> bool {
+        let blocked_by_user = self.block_list(user_id).await.read().unwrap().contains(&other_id);
+        blocked_by_user || self.block_list(other_id).await.read().unwrap().contains(&user_id)
+    }
+
+    /// Forget the block list of a user whose last connection closed
+    fn release_block_list(&self, user_id: UserId) {
+        let mut block_lists = self.block_lists.lock().unwrap();
+        if !self.has_connections(user_id) {
+            block_lists.remove(&user_id);
+        }
+    }
+
+    /// Deliver a `system:` notice to every connection of a user
+    pub fn send_system_to_user(&self, user_id: UserId, message: String) -> usize {
+        self.send_to_user(user_id, system_frame("system", message))
+    }
+
+    /// Database pool for REST handlers that need chat persistence
+    pub fn db_pool(&self) -> Result<PgPool, (StatusCode, String)> {
+        self.db
+            .clone()
+            .ok_or((StatusCode::SERVICE_UNAVAILABLE, "Chat persistence is not configured".to_string()))
+    }
+
+    pub fn get_topic(&self, room_name: &str) -> Option<RoomTopicResponse> {
+        let topics = self.topics.lock().unwrap();
+        topics.get(room_name).cloned()
+    }
+
+    pub fn set_topic(&self, topic: RoomTopicResponse) {
+        let mut topics = self.topics.lock().unwrap();
+        topics.insert(topic.room_name.clone(), topic);
+    }
+
+    /// Send a frame to every connection currently in the room
+    pub fn broadcast_to_room(&self, room_name: &str, frame: Message) {
+        let room_sender = self.get_room_broadcaster(room_name);
+        let _ = room_sender.send(frame.into());
+    }
+
+    /// Sequence, persist and broadcast a room event other than a chat message so that
+    /// reconnecting members can replay it. An event that cannot be persisted is still
+    /// sent live, without a sequence number.
+    pub async fn publish_room_event(&self, room_name: &str, prefix: &str, payload: &impl Serialize) {
+        let message_repository = self.db.clone().map(MessageRepository::new);
+
+        let sequence = self.room_sequence(room_name);
+        let mut last_seq = sequence.lock().await;
+        let seq = match next_seq(room_name, *last_seq, message_repository.as_ref()).await {
+            Ok(seq) => seq,
+            Err(e) => {
+                eprintln!("Failed to load room sequence: {}", e);
+                self.broadcast_to_room(room_name, room_event_frame(prefix, payload, None));
+                return;
+            }
+        };
+
+        let frame = room_event_frame(prefix, payload, Some(seq));
+        if let (Some(repository), Message::Text(text)) = (&message_repository, &frame) {
+            if let Err(e) = repository.create_room_event(room_name, seq as i64, text).await {
+                eprintln!("Failed to persist room event: {}", e);
+                *last_seq = None;
+                self.broadcast_to_room(room_name, room_event_frame(prefix, payload, None));
+                return;
+            }
+        }
+        *last_seq = Some(seq);
+
+        let _ = self.get_room_broadcaster(room_name).send(RoomEvent {
+            seq: Some(seq),
+            sender: None,
+            frame,
+        });
+    }
+
+    /// Publish a system notice, such as a join, as a room event
+    pub async fn publish_system_message(&self, room_name: &str, message: String) {
+        let system_msg = SystemMessage {
+            message,
+            timestamp: unix_timestamp_ms(),
+        };
+        self.publish_room_event(room_name, "system", &system_msg).await;
+    }
+
+    /// Record a topic change and announce it to the room
+    pub async fn publish_topic(&self, topic: RoomTopicResponse, changed_by: &str) {
+        let announcement = match &topic.topic {
+            Some(text) => format!("{} changed the topic to: {}", changed_by, text),
+            None => format!("{} cleared the topic", changed_by),
+        };
+        self.publish_room_event(&topic.room_name, "topic", &topic).await;
+        self.publish_system_message(&topic.room_name, announcement).await;
+        self.set_topic(topic);
+    }
+
+    /// Announce the updated pinned messages to the room
+    pub async fn publish_pins(&self, pins: &PinnedMessagesResponse) {
+        self.publish_room_event(&pins.room_name, "pins", pins).await;
+    }
+
+    /// Announce a poll's current tallies to the room
+    pub async fn publish_poll(&self, poll: &PollResponse) {
+        self.publish_room_event(&poll.room_name, "poll", poll).await;
+    }
+
+    /// Send an announcement to every connection, in a room or not, and keep it as the
+    /// banner if it is one. Returns how many connections it was sent to.
+    pub fn announce(&self, announcement: AnnouncementResponse) -> usize {
+        let frame = announcement_frame(&announcement);
+        if announcement.banner {
+            *self.banner.lock().unwrap() = Some(announcement);
+        }
+
+        // Connections in a room get it through the room, in order with the room's other events
+        let rooms: Vec<broadcast::Sender<RoomEvent>> = self.rooms.lock().unwrap().values().cloned().collect();
+        let in_rooms: usize = rooms
+            .iter()
+            .map(|room_sender| room_sender.send(frame.clone().into()).unwrap_or(0))
+            .sum();
+
+        let connections = self.connections.lock().unwrap();
+        let outside_rooms = connections
+            .values()
+            .filter(|connection| connection.room_name.is_none())
+            .filter(|connection| connection.sender.send(ConnectionEvent::Frame(frame.clone())).is_ok())
+            .count();
+        in_rooms + outside_rooms
+    }
+
+    /// The banner, unless it has expired
+    pub fn current_banner(&self) -> Option<AnnouncementResponse> {
+        let mut banner = self.banner.lock().unwrap();
+        let expired = banner
+            .as_ref()
+            .and_then(|banner| banner.expires_at)
+            .is_some_and(|expires_at| expires_at <= unix_timestamp_ms());
+        if expired {
+            *banner = None;
+        }
+        banner.clone()
+    }
+
+    /// Remove the banner, returning whether one was shown
+    pub fn clear_banner(&self) -> bool {
+        let shown = self.current_banner().is_some();
+        *self.banner.lock().unwrap() = None;
+        shown
+    }
+
+    /// Create a poll in a room and announce it in the room's history.
+    ///
+    /// The question and options pass the room's moderation first; a poll that would be
+    /// rejected or held for review is not created.
+    pub async fn create_poll(
+        &self,
+        user_id: UserId,
+        username: &str,
+        room_name: &str,
+        poll: CreatePollDto,
+    ) -> Result<PollResponse, String> {
+        let db = self.db.clone().ok_or("Polls are not available without persistence")?;
+        let (question, options) = validate_poll(&poll)?;
+
+        let pipeline = self.moderation_pipeline(room_name).await;
+        let mut moderate = |text: String| match pipeline.run(&text) {
+            ModerationOutcome::Allow(text) => Ok(text),
+            ModerationOutcome::Reject(reason) => Err(reason),
+            ModerationOutcome::Flag { reasons, .. } => Err(format!("Poll was not posted: {}", reasons.join(", "))),
+        };
+        let question = moderate(question)?;
+        let options = options.into_iter().map(&mut moderate).collect::<Result<Vec<_>, _>>()?;
+
+        let created = PollService::new(db)
+            .create_poll(user_id, room_name, &question, &options, &poll)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        self.publish_poll(&created).await;
+        if created.closes_at.is_some() {
+            self.poll_notify.notify_one();
+        }
+        let announcement = OutgoingMessage {
+            room_name: room_name.to_string(),
+            sender: MessageSender::Plugin,
+            username: "poll".to_string(),
+            message: announcement_text(&created, username),
+            attachment_id: None,
+            action: false,
+        };
+        if let Err(e) = self.post_message(announcement).await {
+            eprintln!("Failed to announce poll: {}", e);
+        }
+        Ok(created)
+    }
+
+    /// Record a member's vote and broadcast the new tallies
+    pub async fn vote_poll(&self, user_id: UserId, poll_id: Uuid, choices: &[usize]) -> Result<PollResponse, String> {
+        let db = self.db.clone().ok_or("Polls are not available without persistence")?;
+        let poll = PollService::new(db)
+            .vote(user_id, poll_id, choices)
+            .await
+            .map_err(|e| e.to_string())?;
+        self.publish_poll(&poll).await;
+        Ok(poll)
+    }
+
+    /// Close a poll before its deadline and announce the results
+    pub async fn close_poll(&self, user_id: UserId, poll_id: Uuid) -> Result<PollResponse, String> {
+        let db = self.db.clone().ok_or("Polls are not available without persistence")?;
+        let poll = PollService::new(db)
+            .close_poll(user_id, poll_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        self.finish_poll(&poll).await;
+        Ok(poll)
+    }
+
+    /// Broadcast the final tallies of a closed poll and post its results to the room's history
+    pub async fn finish_poll(&self, poll: &PollResponse) {
+        self.publish_poll(poll).await;
+        let results = OutgoingMessage {
+            room_name: poll.room_name.clone(),
+            sender: MessageSender::Plugin,
+            username: "poll".to_string(),
+            message: results_text(poll),
+            attachment_id: None,
+            action: false,
+        };
+        let message_id = match self.post_message(results).await {
+            Ok(message) => message.id.and_then(|id| Uuid::parse_str(&id).ok()),
+            Err(e) => {
+                eprintln!("Failed to post poll results: {}", e);
+                None
+            }
+        };
+        if let (Some(db), Some(message_id), Ok(poll_id)) = (&self.db, message_id, Uuid::parse_str(&poll.id)) {
+            if let Err(e) = PollService::new(db.clone()).set_results_message(poll_id, message_id).await.map_err(|e| e.to_string()) {
+                eprintln!("Failed to link poll results: {}", e);
+            }
+        }
+    }
+
+    /// Moderation pipeline for a room, built from its rules on first use
+    pub async fn moderation_pipeline(&self, room_name: &str) -> Arc<ModerationPipeline> {
+        if let Some(pipeline) = self.moderation.lock().unwrap().get(room_name) {
+            return pipeline.clone();
+        }
+
+        let rules = match &self.db {
+            Some(db) => match ModerationService::new(db.clone()).room_rules(room_name).await {
+                Ok(rules) => rules,
+                Err(e) => {
+                    // Fall back to the server-wide filters without caching, so the rules
+                    // are loaded again for the next message
+                    eprintln!("Failed to load moderation rules: {}", e);
+                    return Arc::new(ModerationPipeline::for_room(&self.profanity_words, &[]));
+                }
+            },
+            None => Vec::new(),
+        };
+        let pipeline = Arc::new(ModerationPipeline::for_room(&self.profanity_words, &rules));
+        self.moderation
+            .lock()
+            .unwrap()
+            .insert(room_name.to_string(), pipeline.clone());
+        pipeline
+    }
+
+    /// Rebuild a room's moderation pipeline on its next message
+    pub fn invalidate_moderation(&self, room_name: &str) {
+        self.moderation.lock().unwrap().remove(room_name);
+    }
+
+    /// Sequence, persist and broadcast a chat message, then notify mentioned users and
+    /// the room's webhooks
+    pub async fn post_message(&self, outgoing: OutgoingMessage) -> Result<ChatMessage, String> {
+        let room_name = outgoing.room_name.as_str();
+        let message_repository = self.db.clone().map(MessageRepository::new);
+
+        // Hold the room's sequence while persisting and broadcasting so live members
+        // receive messages in sequence order
+        let sequence = self.room_sequence(room_name);
+        let mut last_seq = sequence.lock().await;
+        let seq = match next_seq(room_name, *last_seq, message_repository.as_ref()).await {
+            Ok(seq) => seq,
+            Err(e) => {
+                eprintln!("Failed to load room sequence: {}", e);
+                return Err("Message could not be sent, please try again".to_string());
+            }
+        };
+
+        let mut chat_msg = ChatMessage {
+            id: None,
+            seq: Some(seq),
+            user_id: outgoing.sender.id().to_string(),
+            username: outgoing.username.clone(),
+            message: outgoing.message,
+            timestamp: unix_timestamp_ms(),
+            attachment_id: outgoing.attachment_id.map(|id| id.to_string()),
+            action: outgoing.action,
+            bot: outgoing.sender.is_bot(),
+        };
+
+        // A message missing from history could not be replayed, so it is not sent live either
+        if let Some(repository) = &message_repository {
+            let new_message = NewMessage {
+                room_name,
+                user_id: match outgoing.sender {
+                    MessageSender::User(user_id) => Some(user_id),
+                    _ => None,
+                },
+                incoming_webhook_id: match outgoing.sender {
+                    MessageSender::IncomingWebhook(webhook_id) => Some(webhook_id),
+                    _ => None,
+                },
+                username: &outgoing.username,
+                body: &chat_msg.message,
+                attachment_id: outgoing.attachment_id,
+                seq: seq as i64,
+                is_action: outgoing.action,
+            };
+            match repository.create_message(&new_message).await {
+                Ok(record) => chat_msg = record.to_chat_message(),
+                Err(e) => {
+                    eprintln!("Failed to persist message: {}", e);
+                    *last_seq = None;
+                    return Err("Message could not be sent, please try again".to_string());
+                }
+            }
+        }
+        *last_seq = Some(seq);
+
+        let chat_msg_json = serde_json::to_string(&chat_msg).unwrap();
+        let _ = self.get_room_broadcaster(room_name).send(RoomEvent {
+            seq: Some(seq),
+            sender: match outgoing.sender {
+                MessageSender::User(user_id) => Some(user_id),
+                _ => None,
+            },
+            frame: Message::Text(chat_msg_json),
+        });
+        drop(last_seq);
+
+        let actor_id = match outgoing.sender {
+            MessageSender::User(user_id) => Some(user_id),
+            _ => None,
+        };
+        self.notify_mentions(room_name, actor_id, &chat_msg);
+        self.emit_webhook_event(WebhookEvent {
+            id: Uuid::new_v4(),
+            event_type: WebhookEventType::Message,
+            room_name: room_name.to_string(),
+            user_id: chat_msg.user_id.clone(),
+            username: chat_msg.username.clone(),
+            timestamp: chat_msg.timestamp,
+            message: Some(chat_msg.clone()),
+        });
+        Ok(chat_msg)
+    }
+
+    /// Send a user's chat message to a room the way a live connection does: check the
+    /// attachment, run the room's moderation and the plugins, then post the message.
+    ///
+    /// Messages posted by plugins in response are posted afterwards, even when the
+    /// user's message is rejected.
+    pub async fn send_user_message(&self, message: UserMessage<'_>) -> Result<SendOutcome, String> {
+        let UserMessage {
+            room_name,
+            user_id,
+            username,
+            message: text,
+            attachment_id,
+            action,
+        } = message;
+
+        // Only attachments shared in this room may be referenced from it
+        if let Some(attachment_id) = attachment_id {
+            let found = match &self.db {
+                Some(db) => AttachmentService::new(db.clone(), crate::config::environment::Environment::from_env())
+                    .find_in_room(attachment_id, room_name)
+                    .await
+                    .map(|attachment| attachment.is_some())
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to look up attachment: {}", e);
+                        false
+                    }),
+                None => false,
+            };
+            if !found {
+                return Err(format!("Attachment {} was not shared in {}", attachment_id, room_name));
+            }
+        }
+
+        let text = match self.moderation_pipeline(room_name).await.run(&text) {
+            ModerationOutcome::Allow(text) => text,
+            ModerationOutcome::Reject(reason) => return Err(reason),
+            ModerationOutcome::Flag { message, reasons } => {
+                let flagged = NewFlaggedMessage {
+                    room_name,
+                    user_id,
+                    username,
+                    body: &message,
+                    attachment_id,
+                    is_action: action,
+                    reasons: &reasons,
+                };
+                return self.hold_for_review(flagged).await.map(|_| SendOutcome::HeldForReview);
+            }
+        };
+
+        let (text, plugin_posts) = if self.plugins.is_empty() {
+            (Ok(text), Vec::new())
+        } else {
+            let message = PluginMessage {
+                room_name,
+                user_id,
+                username,
+                message: &text,
+            };
+            self.plugins.on_message(self, &message)
+        };
+
+        let result = match text {
+            Ok(text) => {
+                let outgoing = OutgoingMessage {
+                    room_name: room_name.to_string(),
+                    sender: MessageSender::User(user_id),
+                    username: username.to_string(),
+                    message: text,
+                    attachment_id,
+                    action,
+                };
+                self.post_message(outgoing).await.map(SendOutcome::Sent)
+            }
+            Err(reason) => Err(reason),
+        };
+        self.post_plugin_messages(plugin_posts).await;
+        result
+    }
+
+    /// Queue a flagged message for the room's moderators instead of broadcasting it
+    async fn hold_for_review(&self, flagged: NewFlaggedMessage<'_>) -> Result<(), String> {
+        let Some(db) = &self.db else {
+            return Err(format!("Message was not sent: {}", flagged.reasons.join(", ")));
+        };
+
+        ModerationService::new(db.clone())
+            .flag_message(&flagged)
+            .await
+            .map(|_| ())
+            .map_err(|e| {
+                eprintln!("Failed to queue flagged message: {}", e);
+                "Message could not be sent, please try again".to_string()
+            })
+    }
+
+    /// Post messages queued by plugin hooks, in order
+    pub async fn post_plugin_messages(&self, posts: Vec<OutgoingMessage>) {
+        for post in posts {
+            if let Err(e) = self.post_message(post).await {
+                eprintln!("Failed to post plugin message: {}", e);
+            }
+        }
+    }
+
+    /// Announce that a connection left a room to its members, plugins and webhooks
+    async fn announce_leave(&self, room_name: &str, user_id: UserId, username: &str) {
+        self.publish_system_message(room_name, format!("{} has left the chat.", username))
+            .await;
+        self.emit_membership_event(WebhookEventType::Leave, room_name, user_id, username);
+
+        if !self.plugins.is_empty() {
+            let event = MembershipEvent { room_name, user_id, username };
+            let posts = self.plugins.on_leave(self, &event);
+            tokio::spawn({
+                let state = self.clone();
+                async move { state.post_plugin_messages(posts).await }
+            });
+        }
+    }
+
+    /// Record mention notifications off the message path and push them to
+    /// mentioned users who are online
+    fn notify_mentions(&self, room_name: &str, actor_id: Option<UserId>, chat_msg: &ChatMessage) {
+        let Some(db) = self.db.clone() else {
+            return;
+        };
+        if !chat_msg.message.contains('@') {
+            return;
+        }
+
+        let state = self.clone();
+        let room_name = room_name.to_string();
+        let actor_username = chat_msg.username.clone();
+        let body = chat_msg.message.clone();
+        let message_id = chat_msg.id.as_deref().and_then(|id| Uuid::parse_str(id).ok());
+        tokio::spawn(async move {
+            let service = NotificationService::new(db);
+            match service
+                .notify_mentions(&room_name, message_id, actor_id, &actor_username, &body)
+                .await
+            {
+                Ok(notifications) => {
+                    for notification in notifications {
+                        let json = serde_json::to_string(&notification.to_response()).unwrap();
+                        state.send_to_user(notification.user_id, Message::Text(format!("mention:{}", json)));
+                    }
+                }
+                Err(e) => eprintln!("Failed to record mentions: {}", e),
+            }
+        });
+    }
+
+    /// Queue an event for the room's outgoing webhooks without blocking the caller
+    pub fn emit_webhook_event(&self, event: WebhookEvent) {
+        let Some(db) = self.db.clone() else {
+            return;
+        };
+        let notify = self.webhook_notify.clone();
+        tokio::spawn(async move {
+            match WebhookService::new(db).enqueue_event(&event).await {
+                Ok(0) => {}
+                Ok(_) => notify.notify_one(),
+                Err(e) => eprintln!("Failed to queue webhook event: {}", e),
+            }
+        });
+    }
+
+    /// Queue a join or leave event for the room's outgoing webhooks
+    pub fn emit_membership_event(&self, event_type: WebhookEventType, room_name: &str, user_id: UserId, username: &str) {
+        self.emit_webhook_event(WebhookEvent {
+            id: Uuid::new_v4(),
+            event_type,
+            room_name: room_name.to_string(),
+            user_id: user_id.to_string(),
+            username: username.to_string(),
+            timestamp: unix_timestamp_ms(),
+            message: None,
+        });
+    }
+
+    pub fn room_sequence(&self, room_name: &str) -> RoomSequence {
+        let mut sequences = self.sequences.lock().unwrap();
+        sequences.entry(room_name.to_string()).or_default().clone()
+    }
+
+    pub fn get_room_broadcaster(&self, room_name: &str) -> broadcast::Sender<RoomEvent> {
         let mut rooms = self.rooms.lock().unwrap();
         if let Some(sender) = rooms.get(room_name) {
             sender.clone()
@@ -70,39 +1030,275 @@ impl ChatState {
 
 #[derive(Debug, Deserialize)]
 pub struct ConnectionQuery {
+    /// Single-use ticket from `POST /api/chat/ticket`
+    pub ticket: Option<String>,
     pub token: Option<String>,
     pub room: Option<String>,
+    /// Sequence number of the last chat message seen in the room; missed messages are replayed
+    pub since: Option<u64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
+    /// Persisted message ID, used e.g. to pin the message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Position of the message in its room, increasing by one per message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
     pub user_id: String,
     pub username: String,
     pub message: String,
+    /// Milliseconds since the Unix epoch
     pub timestamp: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachment_id: Option<String>,
+    /// Set for `/me` action messages
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub action: bool,
+    /// Set for bot messages; `user_id` is then the incoming webhook's ID, or nil for plugins
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub bot: bool,
+}
+
+/// Author of a message posted with [`ChatState::post_message`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageSender {
+    User(UserId),
+    /// A bot posting through an incoming webhook
+    IncomingWebhook(Uuid),
+    /// A bot running as a server plugin
+    Plugin,
+}
+
+impl MessageSender {
+    /// ID reported as the message's `user_id`; nil for plugins
+    pub fn id(&self) -> Uuid {
+        match self {
+            MessageSender::User(id) | MessageSender::IncomingWebhook(id) => *id,
+            MessageSender::Plugin => Uuid::nil(),
+        }
+    }
+
+    pub fn is_bot(&self) -> bool {
+        !matches!(self, MessageSender::User(_))
+    }
+}
+
+/// A chat message to be posted to a room with [`ChatState::post_message`]
+#[derive(Debug)]
+pub struct OutgoingMessage {
+    pub room_name: RoomName,
+    pub sender: MessageSender,
+    pub username: UserName,
+    pub message: String,
+    pub attachment_id: Option<Uuid>,
+    pub action: bool,
+}
+
+/// A chat message sent by a user with [`ChatState::send_user_message`]
+#[derive(Debug)]
+pub struct UserMessage<'a> {
+    pub room_name: &'a str,
+    pub user_id: UserId,
+    pub username: &'a str,
+    pub message: String,
+    pub attachment_id: Option<Uuid>,
+    pub action: bool,
+}
+
+/// What became of a message sent with [`ChatState::send_user_message`]
+#[derive(Debug)]
+pub enum SendOutcome {
+    /// Posted to the room
+    Sent(ChatMessage),
+    /// Flagged by moderation and queued for the room's moderators
+    HeldForReview,
+}
+
+/// Structured frame a client may send instead of plain text, e.g. to share an attachment
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IncomingChatMessage {
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachment_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SystemMessage {
     pub message: String,
+    /// Milliseconds since the Unix epoch
     pub timestamp: u64,
 }
 
+/// Session lifetime, delivered with the "session:" prefix on connect and after a
+/// successful reauth, and with the "reauth:" prefix shortly before it expires
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionInfo {
+    /// Unix time in seconds at which the connection is closed unless it reauthenticates
+    pub expires_at: u64,
+}
+
+/// `reauth:{"token": "..."}` frame sent by clients to extend their session
+#[derive(Debug, Deserialize)]
+struct ReauthRequest {
+    token: String,
+}
+
+/// Private message sent with `/dm`, delivered with the "dm:" prefix
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DirectMessage {
+    pub from_user_id: String,
+    pub from_username: String,
+    pub to_user_id: String,
+    pub to_username: String,
+    pub message: String,
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+/// Name shown for a user in chat
+pub fn display_name(user_id: UserId) -> UserName {
+    format!("User_{}", &user_id.to_string()[..8])
+}
+
+fn unix_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn unix_timestamp_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+/// Sequence number for the next event in a room, continuing from history when the room
+/// has none in memory yet
+async fn next_seq(
+    room_name: &str,
+    last_seq: Option<u64>,
+    message_repository: Option<&MessageRepository>,
+) -> Result<u64, sqlx::Error> {
+    match (last_seq, message_repository) {
+        (Some(last_seq), _) => Ok(last_seq + 1),
+        (None, Some(repository)) => Ok(repository.last_seq(room_name).await? as u64 + 1),
+        (None, None) => Ok(1),
+    }
+}
+
+/// Serialize a room event as a `<prefix>:{json}` frame, with its room sequence number
+/// added to the JSON object
+fn room_event_frame(prefix: &str, payload: &impl Serialize, seq: Option<u64>) -> Message {
+    let mut json = serde_json::to_value(payload).unwrap();
+    if let (Some(seq), Some(object)) = (seq, json.as_object_mut()) {
+        object.insert("seq".to_string(), seq.into());
+    }
+    Message::Text(format!("{}:{}", prefix, json))
+}
+
+/// Close a connection that fell too far behind its room to deliver every event; the
+/// client resumes by reconnecting with the last sequence number it received
+fn lagged_close_frame(last_seq: u64) -> Message {
+    Message::Close(Some(CloseFrame {
+        code: close_code::AGAIN,
+        reason: format!("Connection fell behind the room; reconnect with since={}", last_seq).into(),
+    }))
+}
+
+/// Serialize a system message as a `<prefix>:{json}` frame
+fn session_frame(prefix: &str, expires_at: u64) -> Message {
+    let session_json = serde_json::to_string(&SessionInfo { expires_at }).unwrap();
+    Message::Text(format!("{}:{}", prefix, session_json))
+}
+
+fn announcement_frame(announcement: &AnnouncementResponse) -> Message {
+    let announcement_json = serde_json::to_string(announcement).unwrap();
+    Message::Text(format!("announcement:{}", announcement_json))
+}
+
+fn system_frame(prefix: &str, message: String) -> Message {
+    let system_msg = SystemMessage {
+        message,
+        timestamp: unix_timestamp_ms(),
+    };
+    let system_msg_json = serde_json::to_string(&system_msg).unwrap();
+    Message::Text(format!("{}:{}", prefix, system_msg_json))
+}
+
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
     Query(query): Query<ConnectionQuery>,
     State(state): State<ChatState>,
     headers: header::HeaderMap,
 ) -> Result<Response, StatusCode> {
+    // Browsers attach cookies and ambient credentials to cross-site socket requests, so
+    // foreign pages must not be able to open a socket at all
+    if !state.origin_policy.allows(&headers) {
+        eprintln!("Rejected WebSocket from disallowed origin {:?}", headers.get(header::ORIGIN));
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let (user_id, issued_at, expires_at) = if let Some(ticket) = &query.ticket {
+        let ticket = state.redeem_ticket(ticket).ok_or_else(|| {
+            eprintln!("Unknown, used or expired ticket");
+            StatusCode::UNAUTHORIZED
+        })?;
+        (ticket.user_id, ticket.token_issued_at, ticket.token_expires_at)
+    } else {
+        authenticate_token(query.token.as_deref(), &headers)?
+    };
+
+    if let Err(e) = state.check_not_revoked(user_id, issued_at).await {
+        eprintln!("Rejected connection: {}", e);
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let username = display_name(user_id);
+    let room_name = query.room.unwrap_or_else(|| DEFAULT_ROOM.to_string());
+
+    // Private rooms admit only their members, who join through an invite
+    if let Some(db) = &state.db {
+        match RoomRepository::new(db.clone()).can_join(&room_name, user_id).await {
+            Ok(true) => {}
+            Ok(false) => {
+                eprintln!("Rejected {} from private room {}", username, room_name);
+                return Err(StatusCode::FORBIDDEN);
+            }
+            Err(e) => {
+                eprintln!("Failed to check room access: {}", e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+    }
+
+    tracing::info!("User {} connecting to room {}", username, room_name);
+
+    let since = query.since;
+
+    Ok(ws.on_upgrade(move |socket| {
+        handle_socket(socket, state, user_id, room_name, since, issued_at, expires_at)
+    }))
+}
+
+/// Validate an access token from the query string or Authorization header, returning
+/// the user ID, the token's issue time in milliseconds and its `exp`
+fn authenticate_token(
+    query_token: Option<&str>,
+    headers: &header::HeaderMap,
+) -> Result<(UserId, u64, u64), StatusCode> {
     // Extract token from query parameter or Authorization header
-    let token = if let Some(token) = query.token {
-        println!("Using token from query parameter");
+    let token = if let Some(token) = query_token {
+        tracing::debug!("Using token from query parameter");
         token
     } else if let Some(auth_header) = headers.get("Authorization") {
         if let Ok(auth_str) = auth_header.to_str() {
-            if auth_str.starts_with("Bearer ") {
-                println!("Using token from Authorization header");
-                auth_str[7..].to_string()
+            if let Some(token) = auth_str.strip_prefix("Bearer ") {
+                tracing::debug!("Using token from Authorization header");
+                token
             } else {
                 eprintln!("Invalid Authorization header format");
                 return Err(StatusCode::UNAUTHORIZED);
@@ -112,71 +1308,110 @@ pub async fn websocket_handler(
             return Err(StatusCode::UNAUTHORIZED);
         }
     } else {
-        eprintln!("No token provided in query or Authorization header");
+        eprintln!("No ticket or token provided in query or Authorization header");
         return Err(StatusCode::UNAUTHORIZED);
     };
 
     // Validate JWT token
-    let env = crate::config::environment::Environment::from_env();
-    println!("Attempting to validate token: {}", &token[..std::cmp::min(token.len(), 20)]); // Log first 20 chars of token
-    let claims = JwtUtil::validate_access_token(&token, &env.auth);
-    
-    match claims {
-        Ok(claims) => {
-            println!("Token validation successful");
-            let user_id = Uuid::parse_str(&claims.sub)
-                .map_err(|_| {
-                    eprintln!("Failed to parse user ID from token");
-                    StatusCode::UNAUTHORIZED
-                })?;
+    let auth_config = crate::config::env::AuthConfig::from_env();
+    let claims = JwtUtil::validate_access_token(token, &auth_config).map_err(|e| {
+        eprintln!("Token validation failed: {:?}", e);
+        StatusCode::UNAUTHORIZED
+    })?;
+    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| {
+        eprintln!("Failed to parse user ID from token");
+        StatusCode::UNAUTHORIZED
+    })?;
 
-            let username = format!("User_{}", &user_id.to_string()[..8]);
-            let room_name = query.room.unwrap_or_else(|| "general".to_string());
-
-            println!("User {} connecting to room {}", username, room_name);
-
-            Ok(ws.on_upgrade(move |socket| handle_socket(socket, state, user_id, username, room_name)))
-        }
-        Err(e) => {
-            eprintln!("Token validation failed: {:?}", e);
-            Err(StatusCode::UNAUTHORIZED)
-        }
-    }
+    Ok((user_id, claims.issued_at_ms(), claims.exp as u64))
 }
 
 async fn handle_socket(
     socket: WebSocket,
     state: ChatState,
     user_id: UserId,
-    username: UserName,
     room_name: RoomName,
+    since: Option<u64>,
+    issued_at: u64,
+    expires_at: u64,
 ) {
-    if let Err(e) = state.add_user_to_room(user_id, username.clone(), room_name.clone()) {
+    let username = display_name(user_id);
+    let connection_id = Uuid::new_v4();
+    let (direct_sender, mut direct_receiver) = mpsc::unbounded_channel();
+    state.register_connection(
+        connection_id,
+        ConnectionHandle {
+            user_id,
+            username: username.clone(),
+            room_name: None,
+            token_issued_at: issued_at,
+            sender: direct_sender.clone(),
+        },
+    );
+
+    let mut session = ChatSession {
+        state: state.clone(),
+        connection_id,
+        user_id,
+        username: username.clone(),
+        room_name: None,
+        room_sender: None,
+        direct_sender,
+        message_repository: state.db.clone().map(MessageRepository::new),
+        expires_at,
+        reauth_requested: false,
+        banner_sent: None,
+    };
+    session.reply(session_frame("session", expires_at));
+
+    if let Err(e) = session.join(room_name.clone(), since).await {
         eprintln!("Failed to add user to room: {}", e);
+        state.unregister_connection(connection_id);
         return;
     }
 
-    let room_sender = state.get_room_broadcaster(&room_name);
-    let mut room_receiver = room_sender.subscribe();
-
-    let join_msg = SystemMessage {
-        message: format!("{} has joined the chat.", username),
-        timestamp: std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs(),
-    };
-    
-    let join_msg_json = serde_json::to_string(&join_msg).unwrap();
-    let _ = room_sender.send(Message::Text(format!("system:{}", join_msg_json)));
-
     let (mut sender, mut receiver) = socket.split();
 
-    let room_sender_clone = room_sender.clone();
-    let username_clone = username.clone();
-
+    let blocked = state.block_list(user_id).await;
     let mut send_task = tokio::spawn(async move {
-        while let Ok(msg) = room_receiver.recv().await {
+        let mut subscription: Option<RoomSubscription> = None;
+        loop {
+            let msg = tokio::select! {
+                event = direct_receiver.recv() => match event {
+                    Some(ConnectionEvent::Frame(msg)) => msg,
+                    Some(ConnectionEvent::Close(reason)) => {
+                        let _ = sender.send(system_frame("error", reason.clone())).await;
+                        let _ = sender
+                            .send(Message::Close(Some(CloseFrame {
+                                code: close_code::POLICY,
+                                reason: reason.into(),
+                            })))
+                            .await;
+                        break;
+                    }
+                    Some(ConnectionEvent::SwitchRoom(new_subscription)) => {
+                        // Flush what the old room already delivered so nothing sent before the switch is lost
+                        if let Some(mut old_subscription) = std::mem::replace(&mut subscription, new_subscription) {
+                            if forward_pending(&mut old_subscription, &blocked, &mut sender).await.is_err() {
+                                break;
+                            }
+                        }
+                        continue;
+                    }
+                    None => break,
+                },
+                event = recv_room(&mut subscription) => match event {
+                    Ok(event) if is_hidden(&blocked, &event) => continue,
+                    Ok(event) => event.frame,
+                    Err(broadcast::error::RecvError::Lagged(_)) => {
+                        let last_seq = subscription.as_ref().map_or(0, |subscription| subscription.last_seq);
+                        let _ = sender.send(lagged_close_frame(last_seq)).await;
+                        break;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+
             if sender.send(msg).await.is_err() {
                 break;
             }
@@ -184,26 +1419,15 @@ async fn handle_socket(
     });
 
     let mut recv_task = tokio::spawn(async move {
-        while let Some(Ok(msg)) = receiver.next().await {
-            match msg {
-                Message::Text(text) => {
-                    let chat_msg = ChatMessage {
-                        user_id: user_id.to_string(),
-                        username: username_clone.clone(),
-                        message: text,
-                        timestamp: std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs(),
-                    };
-                    
-                    let chat_msg_json = serde_json::to_string(&chat_msg).unwrap();
-                    let _ = room_sender_clone.send(Message::Text(chat_msg_json));
-                }
-                Message::Close(_) => {
-                    break;
-                }
-                _ => {}
+        loop {
+            tokio::select! {
+                msg = receiver.next() => match msg {
+                    Some(Ok(Message::Text(text))) => session.handle_text(text).await,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                },
+                // Once the session has expired the send task closes the socket
+                _ = session.session_timer(), if !session.is_expired() => session.on_session_timer(),
             }
         }
     });
@@ -217,18 +1441,516 @@ async fn handle_socket(
         }
     }
 
-    if let Some(user) = state.remove_user(user_id) {
-        let leave_msg = SystemMessage {
-            message: format!("{} has left the chat.", user.username),
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
+    if let Some(connection) = state.unregister_connection(connection_id) {
+        if let Some(room_name) = &connection.room_name {
+            state.announce_leave(room_name, user_id, &connection.username).await;
+        }
+    }
+    if !state.has_connections(user_id) {
+        state.remove_user(user_id);
+    }
+    state.release_block_list(user_id);
+
+    tracing::info!("User {} disconnected", username);
+}
+
+/// Forward events already queued on a room subscription without waiting for more.
+/// Fails, after closing the socket, if the subscription lagged and events were lost.
+async fn forward_pending(
+    subscription: &mut RoomSubscription,
+    blocked: &BlockList,
+    sender: &mut futures::stream::SplitSink<WebSocket, Message>,
+) -> Result<(), axum::Error> {
+    loop {
+        match subscription.receiver.try_recv() {
+            Ok(event) if subscription.accept(&event) && !is_hidden(blocked, &event) => sender.send(event.frame).await?,
+            Ok(_) => continue,
+            Err(broadcast::error::TryRecvError::Lagged(_)) => {
+                sender.send(lagged_close_frame(subscription.last_seq)).await?;
+                return Err(axum::Error::new("Room subscription lagged"));
+            }
+            Err(_) => return Ok(()),
+        }
+    }
+}
+
+/// Wait for the next room event not yet delivered, or forever while the connection is in no room
+async fn recv_room(
+    subscription: &mut Option<RoomSubscription>,
+) -> Result<RoomEvent, broadcast::error::RecvError> {
+    let Some(subscription) = subscription else {
+        return std::future::pending().await;
+    };
+    loop {
+        let event = subscription.receiver.recv().await?;
+        if subscription.accept(&event) {
+            return Ok(event);
+        }
+    }
+}
+
+/// Per-connection state owned by the receive task
+struct ChatSession {
+    state: ChatState,
+    connection_id: ConnectionId,
+    user_id: UserId,
+    username: UserName,
+    room_name: Option<RoomName>,
+    room_sender: Option<broadcast::Sender<RoomEvent>>,
+    direct_sender: mpsc::UnboundedSender<ConnectionEvent>,
+    message_repository: Option<MessageRepository>,
+    /// Unix time in seconds at which the access token used to connect expires
+    expires_at: u64,
+    /// Whether the client has been asked to reauthenticate for the current token
+    reauth_requested: bool,
+    /// ID of the banner already sent, so switching rooms does not repeat it
+    banner_sent: Option<String>,
+}
+
+impl ChatSession {
+    /// Send a frame to this connection only
+    fn reply(&self, frame: Message) {
+        let _ = self.direct_sender.send(ConnectionEvent::Frame(frame));
+    }
+
+    fn reply_error(&self, message: String) {
+        self.reply(system_frame("error", message));
+    }
+
+    fn is_expired(&self) -> bool {
+        unix_timestamp() >= self.expires_at
+    }
+
+    /// Sleep until the client should be asked to reauthenticate, or until the session expires
+    async fn session_timer(&self) {
+        let deadline = if self.reauth_requested {
+            self.expires_at
+        } else {
+            self.expires_at.saturating_sub(REAUTH_WARNING_SECS)
         };
-        
-        let leave_msg_json = serde_json::to_string(&leave_msg).unwrap();
-        let _ = room_sender.send(Message::Text(format!("system:{}", leave_msg_json)));
+        let remaining = deadline.saturating_sub(unix_timestamp());
+        tokio::time::sleep(std::time::Duration::from_secs(remaining)).await;
     }
 
-    println!("User {} disconnected from room {}", username, room_name);
-}
\ No newline at end of file
+    fn on_session_timer(&mut self) {
+        if self.is_expired() {
+            let _ = self.direct_sender.send(ConnectionEvent::Close(
+                "Session expired, reconnect with a fresh token".to_string(),
+            ));
+        } else if !self.reauth_requested {
+            self.reauth_requested = true;
+            self.reply(session_frame("reauth", self.expires_at));
+        }
+    }
+
+    /// Extend the session with a fresh access token for the same user
+    async fn reauthenticate(&mut self, token: &str) {
+        let auth_config = crate::config::env::AuthConfig::from_env();
+        let claims = match JwtUtil::validate_access_token(token, &auth_config) {
+            Ok(claims) => claims,
+            Err(_) => {
+                self.reply_error("Invalid token".to_string());
+                return;
+            }
+        };
+        if Uuid::parse_str(&claims.sub).ok() != Some(self.user_id) {
+            self.reply_error("Token belongs to a different user".to_string());
+            return;
+        }
+        if let Err(e) = self.state.check_not_revoked(self.user_id, claims.issued_at_ms()).await {
+            self.reply_error(e);
+            return;
+        }
+
+        self.state.set_connection_token(self.connection_id, claims.issued_at_ms());
+        self.expires_at = claims.exp as u64;
+        self.reauth_requested = false;
+        self.reply(session_frame("session", self.expires_at));
+    }
+
+    async fn join(&mut self, room_name: RoomName, since: Option<u64>) -> Result<(), String> {
+        if let Some(db) = &self.state.db {
+            match RoomRepository::new(db.clone()).add_member(&room_name, self.user_id).await {
+                Ok(true) => {}
+                Ok(false) => return Err(format!("{} is a private room; you need an invite to join", room_name)),
+                Err(e) => {
+                    eprintln!("Failed to record room membership: {}", e);
+                    return Err(format!("Could not join {}, please try again", room_name));
+                }
+            }
+        }
+
+        self.state
+            .add_user_to_room(self.user_id, self.username.clone(), room_name.clone())?;
+        self.leave().await;
+
+        // Subscribe before loading missed messages so nothing sent meanwhile is lost; the
+        // subscription skips whatever the replay already delivered. Subscribing before the
+        // join announcement also lets this connection see its own join message.
+        let room_sender = self.state.get_room_broadcaster(&room_name);
+        let receiver = room_sender.subscribe();
+        let last_seq = match since {
+            Some(since) => self.replay_missed_messages(&room_name, since).await,
+            None => 0,
+        };
+        let _ = self
+            .direct_sender
+            .send(ConnectionEvent::SwitchRoom(Some(RoomSubscription { receiver, last_seq })));
+        self.state
+            .set_connection_room(self.connection_id, Some(room_name.clone()));
+
+        self.state
+            .publish_system_message(&room_name, format!("{} has joined the chat.", self.username))
+            .await;
+        self.state
+            .emit_membership_event(WebhookEventType::Join, &room_name, self.user_id, &self.username);
+        if !self.state.plugins.is_empty() {
+            let event = MembershipEvent {
+                room_name: &room_name,
+                user_id: self.user_id,
+                username: &self.username,
+            };
+            let posts = self.state.plugins.on_join(&self.state, &event);
+            self.state.post_plugin_messages(posts).await;
+        }
+
+        tracing::info!("User {} joined room {}", self.username, room_name);
+        self.send_room_info(&room_name).await;
+        if let Some(banner) = self.state.current_banner() {
+            if self.banner_sent.as_ref() != Some(&banner.id) {
+                self.reply(announcement_frame(&banner));
+                self.banner_sent = Some(banner.id);
+            }
+        }
+        self.room_name = Some(room_name);
+        self.room_sender = Some(room_sender);
+        Ok(())
+    }
+
+    /// Send this connection the chat messages and other room events after `since`,
+    /// returning the sequence number of the last event it has now seen
+    async fn replay_missed_messages(&self, room_name: &str, since: u64) -> u64 {
+        let Some(repository) = &self.message_repository else {
+            return 0;
+        };
+
+        let since_seq = i64::try_from(since).unwrap_or(i64::MAX);
+        let missed = tokio::try_join!(
+            repository.list_messages_after(room_name, since_seq, MAX_REPLAYED_MESSAGES),
+            repository.list_room_events_after(room_name, since_seq, MAX_REPLAYED_MESSAGES),
+        );
+        let (messages, events) = match missed {
+            Ok(missed) => missed,
+            Err(e) => {
+                eprintln!("Failed to load missed messages for {}: {}", room_name, e);
+                self.reply_error(format!("Missed messages in {} could not be loaded", room_name));
+                return since;
+            }
+        };
+
+        // Either list may have been cut off at the limit, so only what follows the later
+        // cut-off is complete
+        let floor = [messages.first().map(|m| m.seq), events.first().map(|e| e.seq)]
+            .into_iter()
+            .zip([messages.len(), events.len()])
+            .filter(|(_, len)| *len as i64 >= MAX_REPLAYED_MESSAGES)
+            .filter_map(|(first, _)| first.map(|seq| seq - 1))
+            .fold(since_seq, i64::max);
+        let seqs = || messages.iter().map(|m| m.seq).chain(events.iter().map(|e| e.seq));
+        let (Some(first_seq), Some(last_seq)) = (seqs().filter(|seq| *seq > floor).min(), seqs().max()) else {
+            // A cursor ahead of the room, e.g. from another deployment, must not hide new events
+            return match repository.last_seq(room_name).await {
+                Ok(last_seq) => since.min(last_seq as u64),
+                Err(_) => since,
+            };
+        };
+
+        let blocked = self.state.block_list(self.user_id).await;
+        let mut missed: Vec<(i64, Message)> = messages
+            .iter()
+            .filter(|message| message.seq > floor && !blocked.read().unwrap().contains(&message.user_id))
+            .map(|message| {
+                let chat_msg_json = serde_json::to_string(&message.to_chat_message()).unwrap();
+                (message.seq, Message::Text(chat_msg_json))
+            })
+            .collect();
+        missed.extend(
+            events
+                .into_iter()
+                .filter(|event| event.seq > floor)
+                .map(|event| (event.seq, Message::Text(event.frame))),
+        );
+        missed.sort_by_key(|(seq, _)| *seq);
+
+        if first_seq > since_seq + 1 {
+            self.reply(system_frame(
+                "system",
+                format!(
+                    "Only the last {} missed messages in {} were replayed; search the history for earlier ones.",
+                    missed.len(),
+                    room_name
+                ),
+            ));
+        }
+        for (_, frame) in missed {
+            self.reply(frame);
+        }
+        last_seq as u64
+    }
+
+    /// Deliver the room topic, pinned messages and open polls to this connection after it joins
+    async fn send_room_info(&self, room_name: &str) {
+        let (topic, pins) = match &self.state.db {
+            Some(db) => {
+                let room_service = RoomService::new(db.clone());
+                let topic = room_service.room_topic(room_name).await.map_err(|e| e.to_string());
+                let pins = room_service.room_pins(room_name).await.map_err(|e| e.to_string());
+                match (topic, pins) {
+                    (Ok(topic), Ok(pins)) => (topic, pins),
+                    (Err(e), _) | (_, Err(e)) => {
+                        eprintln!("Failed to load room info for {}: {}", room_name, e);
+                        return;
+                    }
+                }
+            }
+            None => (
+                self.state.get_topic(room_name).unwrap_or(RoomTopicResponse {
+                    room_name: room_name.to_string(),
+                    topic: None,
+                    set_by: None,
+                    set_at: None,
+                }),
+                PinnedMessagesResponse {
+                    room_name: room_name.to_string(),
+                    pinned: Vec::new(),
+                },
+            ),
+        };
+
+        let topic_json = serde_json::to_string(&topic).unwrap();
+        self.reply(Message::Text(format!("topic:{}", topic_json)));
+        let pins_json = serde_json::to_string(&pins).unwrap();
+        self.reply(Message::Text(format!("pins:{}", pins_json)));
+
+        if let Some(db) = &self.state.db {
+            match PollService::new(db.clone()).open_polls(room_name).await.map_err(|e| e.to_string()) {
+                Ok(polls) => polls.iter().for_each(|poll| {
+                    let poll_json = serde_json::to_string(poll).unwrap();
+                    self.reply(Message::Text(format!("poll:{}", poll_json)));
+                }),
+                Err(e) => eprintln!("Failed to load open polls for {}: {}", room_name, e),
+            }
+        }
+    }
+
+    async fn leave(&mut self) {
+        if let (Some(room_name), Some(_)) = (self.room_name.take(), self.room_sender.take()) {
+            let _ = self.direct_sender.send(ConnectionEvent::SwitchRoom(None));
+            self.state.set_connection_room(self.connection_id, None);
+            self.state.announce_leave(&room_name, self.user_id, &self.username).await;
+            tracing::info!("User {} left room {}", self.username, room_name);
+        }
+    }
+
+    async fn handle_text(&mut self, text: String) {
+        if let Some(request) = text
+            .strip_prefix("reauth:")
+            .and_then(|json| serde_json::from_str::<ReauthRequest>(json).ok())
+        {
+            self.reauthenticate(&request.token).await;
+            return;
+        }
+
+        if let Some(command) = parse_command(&text) {
+            let result = {
+                let ctx = CommandContext {
+                    state: &self.state,
+                    user_id: self.user_id,
+                    username: &self.username,
+                    room_name: self.room_name.as_deref(),
+                };
+                self.state.commands.dispatch(&ctx, &command)
+            };
+            match result {
+                Ok(action) => self.apply_command(action).await,
+                Err(message) => self.reply_error(message),
+            }
+            return;
+        }
+
+        // "//text" sends a literal leading slash
+        let text = match text.strip_prefix("//") {
+            Some(rest) => format!("/{}", rest),
+            None => text,
+        };
+
+        let (text, attachment_id) = match serde_json::from_str::<IncomingChatMessage>(&text) {
+            Ok(incoming) => (incoming.message, incoming.attachment_id),
+            Err(_) => (text, None),
+        };
+        self.send_chat_message(text, attachment_id, false).await;
+    }
+
+    async fn apply_command(&mut self, action: CommandAction) {
+        match action {
+            CommandAction::Reply(message) => self.reply(system_frame("system", message)),
+            CommandAction::Emote(message) => self.send_chat_message(message, None, true).await,
+            CommandAction::ShowTopic => {
+                let Some(room_name) = &self.room_name else { return };
+                let topic = match &self.state.db {
+                    Some(db) => RoomService::new(db.clone())
+                        .room_topic(room_name)
+                        .await
+                        .map(|topic| topic.topic)
+                        .unwrap_or_else(|e| {
+                            eprintln!("Failed to load topic: {}", e);
+                            None
+                        }),
+                    None => self.state.get_topic(room_name).and_then(|topic| topic.topic),
+                };
+                let message = match topic {
+                    Some(topic) => format!("Topic for {}: {}", room_name, topic),
+                    None => format!("No topic is set for {}", room_name),
+                };
+                self.reply(system_frame("system", message));
+            }
+            CommandAction::SetTopic(topic) => {
+                let Some(room_name) = &self.room_name else { return };
+                let result = match &self.state.db {
+                    Some(db) => RoomService::new(db.clone())
+                        .set_topic(self.user_id, self.state.is_admin(self.user_id), room_name, &topic)
+                        .await
+                        .map_err(|e| e.to_string()),
+                    None => Ok(RoomTopicResponse {
+                        room_name: room_name.clone(),
+                        topic: Some(topic),
+                        set_by: Some(self.user_id.to_string()),
+                        set_at: Some(unix_timestamp_ms()),
+                    }),
+                };
+                match result {
+                    Ok(topic) => self.state.publish_topic(topic, &self.username).await,
+                    Err(e) => self.reply_error(e),
+                }
+            }
+            CommandAction::Join(room_name) => {
+                if let Err(e) = self.join(room_name, None).await {
+                    self.reply_error(e);
+                }
+            }
+            CommandAction::Leave => self.leave().await,
+            CommandAction::DirectMessage { to, message } => {
+                let Some((to_user_id, to_username)) = self.state.find_user_by_username(&to) else {
+                    self.reply_error(format!("{} is not online", to));
+                    return;
+                };
+                if to_user_id == self.user_id {
+                    self.reply_error("You cannot send a direct message to yourself".to_string());
+                    return;
+                }
+                if self.state.is_blocked_between(self.user_id, to_user_id).await {
+                    self.reply_error(format!("You cannot send direct messages to {}", to));
+                    return;
+                }
+
+                let direct_msg = DirectMessage {
+                    from_user_id: self.user_id.to_string(),
+                    from_username: self.username.clone(),
+                    to_user_id: to_user_id.to_string(),
+                    to_username,
+                    message,
+                    timestamp: unix_timestamp_ms(),
+                };
+                let direct_msg_json = serde_json::to_string(&direct_msg).unwrap();
+                let frame = Message::Text(format!("dm:{}", direct_msg_json));
+
+                // Echo to all of the sender's connections so every device shows the conversation
+                self.state.send_to_user(to_user_id, frame.clone());
+                self.state.send_to_user(self.user_id, frame);
+            }
+            CommandAction::CreatePoll(poll) => {
+                let Some(room_name) = &self.room_name else { return };
+                if let Err(e) = self.state.create_poll(self.user_id, &self.username, room_name, poll).await {
+                    self.reply_error(e);
+                }
+            }
+            CommandAction::Vote { poll, options } => {
+                let Some(room_name) = &self.room_name else { return };
+                let Some(db) = &self.state.db else {
+                    self.reply_error("Polls are not available without persistence".to_string());
+                    return;
+                };
+                let poll_id = match PollService::new(db.clone()).find_room_poll(room_name, poll.as_deref()).await {
+                    Ok(poll_id) => poll_id,
+                    Err(e) => {
+                        self.reply_error(e.to_string());
+                        return;
+                    }
+                };
+                match self.state.vote_poll(self.user_id, poll_id, &options).await {
+                    Ok(_) => self.reply(system_frame("system", "Your vote was recorded.".to_string())),
+                    Err(e) => self.reply_error(e),
+                }
+            }
+            CommandAction::Schedule { delay, message } => {
+                let Some(room_name) = &self.room_name else { return };
+                let Some(db) = &self.state.db else {
+                    self.reply_error("Scheduled messages are not available without persistence".to_string());
+                    return;
+                };
+                let Some(send_at) = time::OffsetDateTime::now_utc().checked_add(delay) else {
+                    self.reply_error("Invalid send time: must be in the future and at most 365 days away".to_string());
+                    return;
+                };
+                let message = ScheduleMessageDto {
+                    message,
+                    send_at,
+                    attachment_id: None,
+                    action: false,
+                };
+                match ScheduledMessageService::new(db.clone())
+                    .schedule(self.user_id, room_name, &message)
+                    .await
+                    .map_err(|e| e.to_string())
+                {
+                    Ok(scheduled) => {
+                        self.state.schedule_notify.notify_one();
+                        let send_at = time::OffsetDateTime::from_unix_timestamp(scheduled.send_at as i64)
+                            .ok()
+                            .and_then(|send_at| send_at.format(&time::format_description::well_known::Rfc3339).ok())
+                            .unwrap_or_default();
+                        self.reply(system_frame(
+                            "system",
+                            format!("Message scheduled for {} (ID {}).", send_at, scheduled.id),
+                        ));
+                    }
+                    Err(e) => self.reply_error(e),
+                }
+            }
+        }
+    }
+
+    async fn send_chat_message(&self, text: String, attachment_id: Option<Uuid>, action: bool) {
+        let Some(room_name) = &self.room_name else {
+            self.reply_error("You are not in a room. Use /join <room> first.".to_string());
+            return;
+        };
+
+        let message = UserMessage {
+            room_name,
+            user_id: self.user_id,
+            username: &self.username,
+            message: text,
+            attachment_id,
+            action,
+        };
+        match self.state.send_user_message(message).await {
+            Ok(SendOutcome::Sent(_)) => {}
+            Ok(SendOutcome::HeldForReview) => self.reply(system_frame(
+                "system",
+                "Your message is held for review by a moderator.".to_string(),
+            )),
+            Err(e) => self.reply_error(e),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use crate::config::environment::Environment;
+use crate::modules::chat::entities::attachment::Attachment;
+use crate::modules::chat::repositories::{AttachmentRepository, RoomRepository};
+use crate::modules::file_indexer::scanner::calculate_file_hash;
+use sqlx::PgPool;
+use std::path::{Path, PathBuf};
+use tokio::{fs, task};
+use uuid::Uuid;
+
+pub struct AttachmentService {
+    attachment_repository: AttachmentRepository,
+    room_repository: RoomRepository,
+    storage_dir: PathBuf,
+}
+
+impl AttachmentService {
+    pub fn new(db_pool: PgPool, env: Environment) -> Self {
+        let attachment_repository = AttachmentRepository::new(db_pool.clone());
+        let room_repository = RoomRepository::new(db_pool);
+        Self {
+            attachment_repository,
+            room_repository,
+            storage_dir: env.chat.attachments_dir,
+        }
+    }
+
+    /// Reserve a temporary path for an upload that is still being received
+    pub async fn staging_path(&self) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let staging_dir = self.storage_dir.join("tmp");
+        fs::create_dir_all(&staging_dir).await?;
+        Ok(staging_dir.join(Uuid::new_v4().to_string()))
+    }
+
+    /// Content-addressed location of a blob, sharded by the first two hex digits
+    pub fn blob_path(&self, sha256: &str) -> PathBuf {
+        self.storage_dir.join(&sha256[..2]).join(sha256)
+    }
+
+    /// Move a staged upload into the blob store and record it as shared in a room.
+    ///
+    /// Identical content is stored only once; the staged copy is discarded if the
+    /// blob already exists.
+    pub async fn save_upload(
+        &self,
+        uploader_id: Uuid,
+        room_name: &str,
+        file_name: &str,
+        content_type: &str,
+        staged_path: &Path,
+    ) -> Result<Attachment, Box<dyn std::error::Error>> {
+        if !self.room_repository.is_member(room_name, uploader_id).await? {
+            let _ = fs::remove_file(staged_path).await;
+            return Err("Not a member of this room".into());
+        }
+
+        let size_bytes = fs::metadata(staged_path).await?.len() as i64;
+
+        // Hashing reads the whole file, so keep it off the async workers
+        let hash_path = staged_path.to_path_buf();
+        let sha256 = task::spawn_blocking(move || calculate_file_hash(&hash_path)).await??;
+
+        let blob_path = self.blob_path(&sha256);
+        if fs::try_exists(&blob_path).await? {
+            fs::remove_file(staged_path).await?;
+        } else {
+            if let Some(parent) = blob_path.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::rename(staged_path, &blob_path).await?;
+        }
+
+        let attachment = self
+            .attachment_repository
+            .create_attachment(&sha256, room_name, uploader_id, file_name, content_type, size_bytes)
+            .await?;
+        Ok(attachment)
+    }
+
+    /// Resolve an attachment for download, checking that the user belongs to its room
+    pub async fn open_for_download(
+        &self,
+        user_id: Uuid,
+        attachment_id: Uuid,
+    ) -> Result<(Attachment, PathBuf), Box<dyn std::error::Error>> {
+        let attachment = self
+            .attachment_repository
+            .find_attachment_by_id(attachment_id)
+            .await?
+            .ok_or("Attachment not found")?;
+
+        if !self.room_repository.is_member(&attachment.room_name, user_id).await? {
+            return Err("Not a member of this room".into());
+        }
+
+        let path = self.blob_path(&attachment.sha256);
+        Ok((attachment, path))
+    }
+
+    /// Find an attachment only if it was shared in the given room
+    pub async fn find_in_room(
+        &self,
+        attachment_id: Uuid,
+        room_name: &str,
+    ) -> Result<Option<Attachment>, Box<dyn std::error::Error>> {
+        let attachment = self.attachment_repository.find_attachment_by_id(attachment_id).await?;
+        Ok(attachment.filter(|attachment| attachment.room_name == room_name))
+    }
+}
@@ -0,0 +1,45 @@
+use crate::modules::chat::dto::block_dto::BlockedUserResponse;
+use crate::modules::chat::repositories::BlockRepository;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub struct BlockService {
+    block_repository: BlockRepository,
+}
+
+impl BlockService {
+    pub fn new(db_pool: PgPool) -> Self {
+        let block_repository = BlockRepository::new(db_pool);
+        Self { block_repository }
+    }
+
+    pub async fn block(&self, user_id: Uuid, blocked_id: Uuid) -> Result<BlockedUserResponse, Box<dyn std::error::Error>> {
+        if user_id == blocked_id {
+            return Err("Invalid request: you cannot block yourself".into());
+        }
+        let block = self
+            .block_repository
+            .block_user(user_id, blocked_id)
+            .await?
+            .ok_or("User not found")?;
+        Ok(block.to_response())
+    }
+
+    pub async fn unblock(&self, user_id: Uuid, blocked_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.block_repository.unblock_user(user_id, blocked_id).await? {
+            return Err("Blocked user not found".into());
+        }
+        Ok(())
+    }
+
+    pub async fn list_blocked(&self, user_id: Uuid) -> Result<Vec<BlockedUserResponse>, Box<dyn std::error::Error>> {
+        let blocks = self.block_repository.list_blocked(user_id).await?;
+        Ok(blocks.iter().map(|block| block.to_response()).collect())
+    }
+
+    /// IDs of the users a user has blocked
+    pub async fn blocked_ids(&self, user_id: Uuid) -> Result<Vec<Uuid>, Box<dyn std::error::Error>> {
+        let blocks = self.block_repository.list_blocked(user_id).await?;
+        Ok(blocks.into_iter().map(|block| block.blocked_id).collect())
+    }
+}
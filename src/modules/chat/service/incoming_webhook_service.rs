@@ -0,0 +1,106 @@
+use crate::modules::chat::dto::webhook_dto::IncomingWebhookResponse;
+use crate::modules::chat::entities::room::RoomRole;
+use crate::modules::chat::entities::webhook::IncomingWebhook;
+use crate::modules::chat::repositories::IncomingWebhookRepository;
+use crate::modules::chat::service::RoomService;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Maximum length of a bot display name in characters
+pub const MAX_DISPLAY_NAME_LENGTH: usize = 64;
+/// Maximum length of a message posted through an incoming webhook in characters
+pub const MAX_INCOMING_MESSAGE_LENGTH: usize = 4000;
+
+pub struct IncomingWebhookService {
+    incoming_webhook_repository: IncomingWebhookRepository,
+    room_service: RoomService,
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+impl IncomingWebhookService {
+    pub fn new(db_pool: PgPool) -> Self {
+        let incoming_webhook_repository = IncomingWebhookRepository::new(db_pool.clone());
+        let room_service = RoomService::new(db_pool);
+        Self {
+            incoming_webhook_repository,
+            room_service,
+        }
+    }
+
+    async fn require_owner(&self, room_name: &str, user_id: Uuid, is_admin: bool) -> Result<(), Box<dyn std::error::Error>> {
+        if self.room_service.acting_role(room_name, user_id, is_admin).await? != RoomRole::Owner {
+            return Err("Only the room owner can manage webhooks".into());
+        }
+        Ok(())
+    }
+
+    /// Create a webhook token; only its hash is stored, so the response is the only place it is shown
+    pub async fn create_incoming_webhook(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        room_name: &str,
+        display_name: &str,
+    ) -> Result<IncomingWebhookResponse, Box<dyn std::error::Error>> {
+        self.require_owner(room_name, user_id, is_admin).await?;
+
+        let display_name = display_name.trim();
+        if display_name.is_empty() || display_name.chars().count() > MAX_DISPLAY_NAME_LENGTH {
+            return Err(format!(
+                "Invalid display name: must be 1 to {} characters",
+                MAX_DISPLAY_NAME_LENGTH
+            )
+            .into());
+        }
+
+        let token = format!("ihk_{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let webhook = self
+            .incoming_webhook_repository
+            .create_incoming_webhook(room_name, &hash_token(&token), display_name, user_id)
+            .await?;
+        Ok(webhook.to_response(Some(token)))
+    }
+
+    pub async fn list_incoming_webhooks(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        room_name: &str,
+    ) -> Result<Vec<IncomingWebhookResponse>, Box<dyn std::error::Error>> {
+        self.require_owner(room_name, user_id, is_admin).await?;
+        let webhooks = self.incoming_webhook_repository.list_incoming_webhooks(room_name).await?;
+        Ok(webhooks.iter().map(|webhook| webhook.to_response(None)).collect())
+    }
+
+    pub async fn revoke_incoming_webhook(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        room_name: &str,
+        webhook_id: Uuid,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.require_owner(room_name, user_id, is_admin).await?;
+        if !self
+            .incoming_webhook_repository
+            .revoke_incoming_webhook(room_name, webhook_id)
+            .await?
+        {
+            return Err("Webhook not found".into());
+        }
+        Ok(())
+    }
+
+    /// Look up the active webhook a token belongs to
+    pub async fn authenticate(&self, token: &str) -> Result<IncomingWebhook, Box<dyn std::error::Error>> {
+        let webhook = self
+            .incoming_webhook_repository
+            .find_active_by_token_hash(&hash_token(token))
+            .await?
+            .ok_or("Webhook not found")?;
+        Ok(webhook)
+    }
+}
@@ -0,0 +1,101 @@
+use crate::modules::chat::dto::invite_dto::{AcceptInviteResponse, CreateInviteDto, InviteResponse};
+use crate::modules::chat::entities::room::RoomRole;
+use crate::modules::chat::repositories::invite_repository::AcceptOutcome;
+use crate::modules::chat::repositories::InviteRepository;
+use crate::modules::chat::service::RoomService;
+use sqlx::PgPool;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Largest number of uses an invite can be given
+pub const MAX_INVITE_USES: i32 = 10000;
+
+/// Check a new invite's settings, returning the role it grants
+pub fn validate_invite(invite: &CreateInviteDto) -> Result<RoomRole, String> {
+    if let Some(max_uses) = invite.max_uses {
+        if !(1..=MAX_INVITE_USES).contains(&max_uses) {
+            return Err(format!("Invalid maximum uses: must be between 1 and {}", MAX_INVITE_USES));
+        }
+    }
+    if invite.expires_at.is_some_and(|expires_at| expires_at <= OffsetDateTime::now_utc()) {
+        return Err("Invalid expiry: must be in the future".to_string());
+    }
+    match invite.role.as_deref().unwrap_or("member").parse()? {
+        RoomRole::Owner => Err("Invalid room role: invites grant `member` or `moderator`".to_string()),
+        role => Ok(role),
+    }
+}
+
+pub struct InviteService {
+    invite_repository: InviteRepository,
+    room_service: RoomService,
+}
+
+impl InviteService {
+    pub fn new(db_pool: PgPool) -> Self {
+        let invite_repository = InviteRepository::new(db_pool.clone());
+        let room_service = RoomService::new(db_pool);
+        Self {
+            invite_repository,
+            room_service,
+        }
+    }
+
+    async fn require_owner(&self, room_name: &str, user_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+        if self.room_service.member_role(room_name, user_id).await? != RoomRole::Owner {
+            return Err("Only the room owner can manage invites".into());
+        }
+        Ok(())
+    }
+
+    pub async fn create_invite(
+        &self,
+        user_id: Uuid,
+        room_name: &str,
+        invite: &CreateInviteDto,
+    ) -> Result<InviteResponse, Box<dyn std::error::Error>> {
+        self.require_owner(room_name, user_id).await?;
+        let role = validate_invite(invite)?;
+
+        let code = Uuid::new_v4().simple().to_string();
+        let invite = self
+            .invite_repository
+            .create_invite(&code, room_name, user_id, role.as_str(), invite.max_uses, invite.expires_at)
+            .await?;
+        Ok(invite.to_response())
+    }
+
+    /// Invites of a room that have not been revoked, including expired and used up ones
+    pub async fn list_invites(&self, user_id: Uuid, room_name: &str) -> Result<Vec<InviteResponse>, Box<dyn std::error::Error>> {
+        self.require_owner(room_name, user_id).await?;
+        let invites = self.invite_repository.list_invites(room_name).await?;
+        Ok(invites.iter().map(|invite| invite.to_response()).collect())
+    }
+
+    pub async fn revoke_invite(&self, user_id: Uuid, room_name: &str, code: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.require_owner(room_name, user_id).await?;
+        if !self.invite_repository.revoke_invite(room_name, code).await? {
+            return Err("Invite not found".into());
+        }
+        Ok(())
+    }
+
+    /// Join the room an invite is for
+    pub async fn accept_invite(&self, user_id: Uuid, code: &str) -> Result<AcceptInviteResponse, Box<dyn std::error::Error>> {
+        match self.invite_repository.accept_invite(code, user_id).await? {
+            AcceptOutcome::Joined { room_name, role } => Ok(AcceptInviteResponse {
+                room_name,
+                role,
+                already_member: false,
+            }),
+            AcceptOutcome::AlreadyMember { room_name, role } => Ok(AcceptInviteResponse {
+                room_name,
+                role,
+                already_member: true,
+            }),
+            AcceptOutcome::NotFound => Err("Invite not found".into()),
+            AcceptOutcome::Expired => Err("Invite has expired".into()),
+            AcceptOutcome::UsedUp => Err("Invite has no uses left".into()),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::modules::chat::dto::message_dto::{ExportTranscriptQuery, MessageSearchResponse, SearchMessagesQuery};
+use crate::modules::chat::entities::room::RoomRole;
+use crate::modules::chat::export::{ExportFormat, TranscriptEncoder};
+use crate::modules::chat::repositories::message_repository::{MessageSearchFilter, TranscriptFilter};
+use crate::modules::chat::repositories::{MessageRepository, RoomRepository};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const DEFAULT_SEARCH_LIMIT: i64 = 20;
+const MAX_SEARCH_LIMIT: i64 = 100;
+/// Messages read from the database at a time while exporting a transcript
+const EXPORT_BATCH_SIZE: i64 = 500;
+
+pub struct MessageService {
+    message_repository: MessageRepository,
+    room_repository: RoomRepository,
+}
+
+impl MessageService {
+    pub fn new(db_pool: PgPool) -> Self {
+        let message_repository = MessageRepository::new(db_pool.clone());
+        let room_repository = RoomRepository::new(db_pool);
+        Self { message_repository, room_repository }
+    }
+
+    /// Full-text search over the history of rooms the user has joined
+    pub async fn search(
+        &self,
+        user_id: Uuid,
+        query: SearchMessagesQuery,
+    ) -> Result<MessageSearchResponse, Box<dyn std::error::Error>> {
+        let terms = query.q.trim();
+        if terms.is_empty() {
+            return Err("Search query must not be empty".into());
+        }
+
+        let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT);
+        let offset = query.offset.unwrap_or(0).max(0);
+
+        // Fetch one extra row to tell whether another page exists
+        let filter = MessageSearchFilter {
+            query: terms,
+            room_name: query.room.as_deref(),
+            author_id: query.author,
+            from: query.from,
+            to: query.to,
+            limit: limit + 1,
+            offset,
+        };
+        let mut hits = self.message_repository.search_messages(user_id, &filter).await?;
+
+        let has_more = hits.len() as i64 > limit;
+        hits.truncate(limit as usize);
+
+        Ok(MessageSearchResponse {
+            results: hits.iter().map(|hit| hit.to_response()).collect(),
+            limit,
+            offset,
+            has_more,
+        })
+    }
+
+    /// Stream the transcript of a room, for its owners and server admins.
+    ///
+    /// Messages are read in batches as the stream is polled, so large rooms are never
+    /// held in memory at once.
+    pub async fn export_transcript(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        room_name: &str,
+        query: ExportTranscriptQuery,
+    ) -> Result<(ExportFormat, impl Stream<Item = Result<String, sqlx::Error>>), Box<dyn std::error::Error>> {
+        let format = query.format.as_deref().unwrap_or("json").parse::<ExportFormat>()?;
+        if let (Some(from), Some(to)) = (query.from, query.to) {
+            if from >= to {
+                return Err("Invalid time range: from must be before to".into());
+            }
+        }
+
+        if !is_admin {
+            let role = self.room_repository.find_member_role(room_name, user_id).await?;
+            if role.as_deref() != Some(RoomRole::Owner.as_str()) {
+                return Err("Only room owners and admins can export transcripts".into());
+            }
+        } else if self.room_repository.find_room(room_name).await?.is_none() {
+            return Err("Room not found".into());
+        }
+
+        let repository = self.message_repository.clone();
+        let room_name = room_name.to_string();
+        let (from, to) = (query.from, query.to);
+        // Pages are keyed by sequence number, so each query picks up where the last ended
+        let pages = stream::try_unfold(Some(0), move |after_seq| {
+            let repository = repository.clone();
+            let room_name = room_name.clone();
+            async move {
+                let Some(after_seq) = after_seq else {
+                    return Ok(None);
+                };
+                let filter = TranscriptFilter { room_name: &room_name, from, to };
+                let page = repository.list_transcript_page(&filter, after_seq, EXPORT_BATCH_SIZE).await?;
+                if page.is_empty() {
+                    return Ok(None);
+                }
+                let next = (page.len() as i64 == EXPORT_BATCH_SIZE).then(|| page[page.len() - 1].seq);
+                Ok(Some((page, next)))
+            }
+        });
+
+        let mut encoder = TranscriptEncoder::new(format);
+        let header = encoder.header();
+        let footer = encoder.footer();
+        let body = stream::once(async move { Ok(header) })
+            .chain(pages.map_ok(move |page| encoder.encode(&page)))
+            .chain(stream::once(async move { Ok(footer) }));
+
+        Ok((format, body))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,25 @@
+pub mod attachment_service;
+pub mod block_service;
+pub mod incoming_webhook_service;
+pub mod invite_service;
+pub mod message_service;
+pub mod moderation_service;
+pub mod notification_service;
+pub mod poll_service;
+pub mod retention_service;
+pub mod room_service;
+pub mod scheduled_message_service;
+pub mod webhook_service;
+
+pub use attachment_service::AttachmentService;
+pub use block_service::BlockService;
+pub use incoming_webhook_service::IncomingWebhookService;
+pub use invite_service::InviteService;
+pub use message_service::MessageService;
+pub use moderation_service::ModerationService;
+pub use notification_service::NotificationService;
+pub use poll_service::PollService;
+pub use retention_service::RetentionService;
+pub use room_service::RoomService;
+pub use scheduled_message_service::ScheduledMessageService;
+pub use webhook_service::WebhookService;
\ No newline at end of file
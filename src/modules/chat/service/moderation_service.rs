@@ -0,0 +1,146 @@
+use crate::modules::chat::dto::moderation_dto::{FlaggedMessageResponse, ModerationRuleResponse};
+use crate::modules::chat::entities::moderation::{FlaggedMessage, ModerationRule};
+use crate::modules::chat::moderation::{compile_rule_regex, RuleAction, RuleKind};
+use crate::modules::chat::repositories::moderation_repository::NewFlaggedMessage;
+use crate::modules::chat::repositories::ModerationRepository;
+use crate::modules::chat::service::RoomService;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Maximum number of moderation rules per room
+pub const MAX_RULES_PER_ROOM: usize = 100;
+/// Maximum length of a rule's pattern
+const MAX_PATTERN_LENGTH: usize = 500;
+const QUEUE_STATUSES: [&str; 3] = ["pending", "approved", "rejected"];
+
+pub struct ModerationService {
+    moderation_repository: ModerationRepository,
+    room_service: RoomService,
+}
+
+impl ModerationService {
+    pub fn new(db_pool: PgPool) -> Self {
+        let moderation_repository = ModerationRepository::new(db_pool.clone());
+        let room_service = RoomService::new(db_pool);
+        Self {
+            moderation_repository,
+            room_service,
+        }
+    }
+
+    async fn require_moderator(&self, room_name: &str, user_id: Uuid, is_admin: bool) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.room_service.acting_role(room_name, user_id, is_admin).await?.can_moderate() {
+            return Err("Only room owners and moderators can manage moderation".into());
+        }
+        Ok(())
+    }
+
+    /// Rules applied to a room's messages, without checking membership
+    pub async fn room_rules(&self, room_name: &str) -> Result<Vec<ModerationRule>, Box<dyn std::error::Error>> {
+        Ok(self.moderation_repository.list_rules(room_name).await?)
+    }
+
+    pub async fn create_rule(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        room_name: &str,
+        kind: &str,
+        pattern: &str,
+        action: &str,
+    ) -> Result<ModerationRuleResponse, Box<dyn std::error::Error>> {
+        self.require_moderator(room_name, user_id, is_admin).await?;
+
+        let kind: RuleKind = kind.parse()?;
+        let action: RuleAction = action.parse()?;
+        let pattern = pattern.trim();
+        if pattern.is_empty() || pattern.chars().count() > MAX_PATTERN_LENGTH {
+            return Err(format!("Invalid pattern: must be 1 to {} characters", MAX_PATTERN_LENGTH).into());
+        }
+        let pattern = match kind {
+            RuleKind::Word => pattern.to_string(),
+            RuleKind::Regex => {
+                compile_rule_regex(pattern).map_err(|e| format!("Invalid regular expression: {}", e))?;
+                pattern.to_string()
+            }
+            RuleKind::LinkDomain => {
+                let domain = pattern.to_lowercase();
+                if domain.contains(['/', ':', ' ']) || !domain.contains('.') {
+                    return Err("Invalid domain: use a bare domain such as example.com".into());
+                }
+                domain
+            }
+        };
+        if self.moderation_repository.list_rules(room_name).await?.len() >= MAX_RULES_PER_ROOM {
+            return Err(format!("A room can have at most {} moderation rules", MAX_RULES_PER_ROOM).into());
+        }
+
+        let rule = self
+            .moderation_repository
+            .create_rule(room_name, kind.as_str(), &pattern, action.as_str(), user_id)
+            .await?;
+        Ok(rule.to_response())
+    }
+
+    pub async fn list_rules(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        room_name: &str,
+    ) -> Result<Vec<ModerationRuleResponse>, Box<dyn std::error::Error>> {
+        self.require_moderator(room_name, user_id, is_admin).await?;
+        let rules = self.moderation_repository.list_rules(room_name).await?;
+        Ok(rules.iter().map(ModerationRule::to_response).collect())
+    }
+
+    pub async fn delete_rule(&self, user_id: Uuid, is_admin: bool, room_name: &str, rule_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+        self.require_moderator(room_name, user_id, is_admin).await?;
+        if !self.moderation_repository.delete_rule(room_name, rule_id).await? {
+            return Err("Moderation rule not found".into());
+        }
+        Ok(())
+    }
+
+    /// Hold a message for review
+    pub async fn flag_message(&self, flagged: &NewFlaggedMessage<'_>) -> Result<FlaggedMessage, Box<dyn std::error::Error>> {
+        Ok(self.moderation_repository.create_flagged_message(flagged).await?)
+    }
+
+    pub async fn list_queue(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        room_name: &str,
+        status: Option<&str>,
+    ) -> Result<Vec<FlaggedMessageResponse>, Box<dyn std::error::Error>> {
+        self.require_moderator(room_name, user_id, is_admin).await?;
+        let status = status.unwrap_or("pending");
+        if !QUEUE_STATUSES.contains(&status) {
+            return Err(format!("Invalid status: {}", status).into());
+        }
+        let flagged = self.moderation_repository.list_flagged_messages(room_name, status).await?;
+        Ok(flagged.iter().map(FlaggedMessage::to_response).collect())
+    }
+
+    /// Approve or reject a pending message; each message can only be reviewed once
+    pub async fn review(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        room_name: &str,
+        flagged_id: Uuid,
+        approve: bool,
+    ) -> Result<FlaggedMessage, Box<dyn std::error::Error>> {
+        self.require_moderator(room_name, user_id, is_admin).await?;
+        let status = if approve { "approved" } else { "rejected" };
+        self.moderation_repository
+            .review_flagged_message(room_name, flagged_id, status, user_id)
+            .await?
+            .ok_or_else(|| "Pending flagged message not found".into())
+    }
+
+    /// Record the chat message an approved message was posted as
+    pub async fn set_posted_message(&self, flagged_id: Uuid, message_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(self.moderation_repository.set_posted_message(flagged_id, message_id).await?)
+    }
+}
@@ -0,0 +1,105 @@
+use crate::modules::auth::repositories::AuthRepository;
+use crate::modules::chat::dto::notification_dto::{ListNotificationsQuery, NotificationListResponse};
+use crate::modules::chat::entities::notification::Notification;
+use crate::modules::chat::mentions::extract_mentions;
+use crate::modules::chat::repositories::notification_repository::NewNotification;
+use crate::modules::chat::repositories::{BlockRepository, NotificationRepository};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const DEFAULT_NOTIFICATION_LIMIT: i64 = 50;
+const MAX_NOTIFICATION_LIMIT: i64 = 100;
+
+pub struct NotificationService {
+    notification_repository: NotificationRepository,
+    auth_repository: AuthRepository,
+    block_repository: BlockRepository,
+}
+
+impl NotificationService {
+    pub fn new(db_pool: PgPool) -> Self {
+        let notification_repository = NotificationRepository::new(db_pool.clone());
+        let auth_repository = AuthRepository::new(db_pool.clone());
+        let block_repository = BlockRepository::new(db_pool);
+        Self {
+            notification_repository,
+            auth_repository,
+            block_repository,
+        }
+    }
+
+    /// Create a `mention` notification for every user mentioned in a message.
+    ///
+    /// Users cannot mention themselves, and users who blocked the author are not
+    /// notified; bot messages have no actor. Returns the
+    /// notifications created so they can be pushed to connected users.
+    pub async fn notify_mentions(
+        &self,
+        room_name: &str,
+        message_id: Option<Uuid>,
+        actor_id: Option<Uuid>,
+        actor_username: &str,
+        body: &str,
+    ) -> Result<Vec<Notification>, Box<dyn std::error::Error>> {
+        let names = extract_mentions(body);
+        if names.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let users = self.auth_repository.find_users_by_mention_names(&names).await?;
+        let mut notifications = Vec::new();
+        for user in users.iter().filter(|user| Some(user.id) != actor_id) {
+            if let Some(actor_id) = actor_id {
+                if self.block_repository.is_blocked(user.id, actor_id).await? {
+                    continue;
+                }
+            }
+            let notification = self
+                .notification_repository
+                .create_notification(&NewNotification {
+                    user_id: user.id,
+                    kind: "mention",
+                    room_name,
+                    message_id,
+                    actor_id,
+                    actor_username,
+                    body,
+                })
+                .await?;
+            notifications.push(notification);
+        }
+
+        Ok(notifications)
+    }
+
+    pub async fn list(
+        &self,
+        user_id: Uuid,
+        query: ListNotificationsQuery,
+    ) -> Result<NotificationListResponse, Box<dyn std::error::Error>> {
+        let limit = query.limit.unwrap_or(DEFAULT_NOTIFICATION_LIMIT).clamp(1, MAX_NOTIFICATION_LIMIT);
+        let offset = query.offset.unwrap_or(0).max(0);
+
+        let notifications = self
+            .notification_repository
+            .list_notifications(user_id, query.unread_only.unwrap_or(false), limit, offset)
+            .await?;
+        let unread_count = self.notification_repository.count_unread(user_id).await?;
+
+        Ok(NotificationListResponse {
+            notifications: notifications.iter().map(|n| n.to_response()).collect(),
+            unread_count,
+        })
+    }
+
+    pub async fn mark_read(&self, user_id: Uuid, notification_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.notification_repository.mark_read(user_id, notification_id).await? {
+            return Err("Notification not found".into());
+        }
+        Ok(())
+    }
+
+    pub async fn mark_all_read(&self, user_id: Uuid) -> Result<u64, Box<dyn std::error::Error>> {
+        Ok(self.notification_repository.mark_all_read(user_id).await?)
+    }
+}
@@ -0,0 +1,290 @@
+use crate::modules::chat::dto::poll_dto::{CreatePollDto, PollResponse};
+use crate::modules::chat::entities::poll::Poll;
+use crate::modules::chat::repositories::poll_repository::{NewPoll, VoteOutcome};
+use crate::modules::chat::repositories::PollRepository;
+use crate::modules::chat::server::ChatState;
+use crate::modules::chat::service::RoomService;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+/// Options a poll needs at least
+pub const MIN_POLL_OPTIONS: usize = 2;
+/// Options a poll can have at most
+pub const MAX_POLL_OPTIONS: usize = 10;
+/// Maximum length of a poll question in characters
+pub const MAX_QUESTION_LENGTH: usize = 300;
+/// Maximum length of a poll option in characters
+pub const MAX_OPTION_LENGTH: usize = 100;
+/// Polls can run for at most this long
+pub const MAX_POLL_DURATION: time::Duration = time::Duration::days(30);
+/// Polls listed per room
+const POLL_LIST_LIMIT: i64 = 50;
+/// How long the close job sleeps when no poll has a deadline
+const CLOSE_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Trim a new poll's question and options and check them against the limits
+pub fn validate_poll(poll: &CreatePollDto) -> Result<(String, Vec<String>), String> {
+    let question = poll.question.trim();
+    if question.is_empty() || question.chars().count() > MAX_QUESTION_LENGTH {
+        return Err(format!("Invalid question: must be 1 to {} characters", MAX_QUESTION_LENGTH));
+    }
+
+    let options: Vec<String> = poll.options.iter().map(|option| option.trim().to_string()).collect();
+    if !(MIN_POLL_OPTIONS..=MAX_POLL_OPTIONS).contains(&options.len()) {
+        return Err(format!(
+            "Invalid options: a poll has {} to {} options",
+            MIN_POLL_OPTIONS, MAX_POLL_OPTIONS
+        ));
+    }
+    if options
+        .iter()
+        .any(|option| option.is_empty() || option.chars().count() > MAX_OPTION_LENGTH)
+    {
+        return Err(format!("Invalid options: each option must be 1 to {} characters", MAX_OPTION_LENGTH));
+    }
+
+    if let Some(closes_at) = poll.closes_at {
+        let now = OffsetDateTime::now_utc();
+        if closes_at <= now || closes_at > now + MAX_POLL_DURATION {
+            return Err("Invalid deadline: must be in the future and at most 30 days away".to_string());
+        }
+    }
+    Ok((question.to_string(), options))
+}
+
+/// Check a ballot against a poll, returning the chosen option indexes in order
+pub fn validate_choices(option_count: usize, multiple_choice: bool, choices: &[usize]) -> Result<Vec<i32>, String> {
+    let mut choices = choices.to_vec();
+    choices.sort_unstable();
+    choices.dedup();
+    if choices.is_empty() {
+        return Err("Invalid vote: choose at least one option".to_string());
+    }
+    if !multiple_choice && choices.len() > 1 {
+        return Err("Invalid vote: this poll allows only one choice".to_string());
+    }
+    if let Some(choice) = choices.iter().find(|choice| **choice >= option_count) {
+        return Err(format!("Invalid vote: the poll has no option {}", choice + 1));
+    }
+    Ok(choices.into_iter().map(|choice| choice as i32).collect())
+}
+
+/// Chat message announcing a new poll
+pub fn announcement_text(poll: &PollResponse, created_by: &str) -> String {
+    let options: Vec<String> = poll
+        .options
+        .iter()
+        .map(|option| format!("{}) {}", option.index + 1, option.text))
+        .collect();
+    let mut details = Vec::new();
+    if poll.multiple_choice {
+        details.push("multiple choice".to_string());
+    }
+    if poll.anonymous {
+        details.push("anonymous".to_string());
+    }
+    if poll.closes_at.is_some() {
+        details.push("closes automatically".to_string());
+    }
+    let details = if details.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", details.join(", "))
+    };
+    format!(
+        "{} started a poll{}: {} {} - vote with /vote <number>",
+        created_by,
+        details,
+        poll.question,
+        options.join(" ")
+    )
+}
+
+/// Chat message with the final results of a poll
+pub fn results_text(poll: &PollResponse) -> String {
+    let results: Vec<String> = poll
+        .options
+        .iter()
+        .map(|option| {
+            let votes = if option.votes == 1 { "vote" } else { "votes" };
+            format!("{}: {} {}", option.text, option.votes, votes)
+        })
+        .collect();
+    format!(
+        "Poll closed: {} {} ({} voted)",
+        poll.question,
+        results.join(", "),
+        poll.total_voters
+    )
+}
+
+pub struct PollService {
+    poll_repository: PollRepository,
+    room_service: RoomService,
+}
+
+impl PollService {
+    pub fn new(db_pool: PgPool) -> Self {
+        let poll_repository = PollRepository::new(db_pool.clone());
+        let room_service = RoomService::new(db_pool);
+        Self {
+            poll_repository,
+            room_service,
+        }
+    }
+
+    async fn response(&self, poll: &Poll) -> Result<PollResponse, Box<dyn std::error::Error>> {
+        let (tallies, total_voters) = self.poll_repository.tally(poll.id).await?;
+        Ok(poll.to_response(&tallies, total_voters))
+    }
+
+    /// Find a poll, checking that the user belongs to its room
+    async fn find_for_member(&self, user_id: Uuid, poll_id: Uuid) -> Result<Poll, Box<dyn std::error::Error>> {
+        let poll = self.poll_repository.find_poll(poll_id).await?.ok_or("Poll not found")?;
+        self.room_service.member_role(&poll.room_name, user_id).await?;
+        Ok(poll)
+    }
+
+    /// Create a poll with an already validated question and options
+    pub async fn create_poll(
+        &self,
+        user_id: Uuid,
+        room_name: &str,
+        question: &str,
+        options: &[String],
+        poll: &CreatePollDto,
+    ) -> Result<PollResponse, Box<dyn std::error::Error>> {
+        self.room_service.member_role(room_name, user_id).await?;
+
+        let new_poll = NewPoll {
+            room_name,
+            created_by: user_id,
+            question,
+            options,
+            multiple_choice: poll.multiple_choice,
+            anonymous: poll.anonymous,
+            closes_at: poll.closes_at,
+        };
+        let poll = self.poll_repository.create_poll(&new_poll).await?;
+        self.response(&poll).await
+    }
+
+    pub async fn get_poll(&self, user_id: Uuid, poll_id: Uuid) -> Result<PollResponse, Box<dyn std::error::Error>> {
+        let poll = self.find_for_member(user_id, poll_id).await?;
+        self.response(&poll).await
+    }
+
+    /// Recent polls of a room, newest first
+    pub async fn list_polls(&self, user_id: Uuid, room_name: &str) -> Result<Vec<PollResponse>, Box<dyn std::error::Error>> {
+        self.room_service.member_role(room_name, user_id).await?;
+        let polls = self.poll_repository.list_polls(room_name, false, POLL_LIST_LIMIT).await?;
+        let mut responses = Vec::with_capacity(polls.len());
+        for poll in &polls {
+            responses.push(self.response(poll).await?);
+        }
+        Ok(responses)
+    }
+
+    /// Open polls of a room, without checking membership
+    pub async fn open_polls(&self, room_name: &str) -> Result<Vec<PollResponse>, Box<dyn std::error::Error>> {
+        let polls = self.poll_repository.list_polls(room_name, true, POLL_LIST_LIMIT).await?;
+        let mut responses = Vec::with_capacity(polls.len());
+        for poll in polls.iter().rev() {
+            responses.push(self.response(poll).await?);
+        }
+        Ok(responses)
+    }
+
+    /// Resolve the poll a `/vote` refers to: the newest open poll of the room, or the
+    /// poll whose ID starts with `id_prefix`
+    pub async fn find_room_poll(&self, room_name: &str, id_prefix: Option<&str>) -> Result<Uuid, Box<dyn std::error::Error>> {
+        let polls = self
+            .poll_repository
+            .list_polls(room_name, id_prefix.is_none(), POLL_LIST_LIMIT)
+            .await?;
+        let poll = match id_prefix {
+            Some(prefix) => {
+                let prefix = prefix.to_lowercase();
+                polls.iter().find(|poll| poll.id.to_string().starts_with(&prefix))
+            }
+            None => polls.first(),
+        };
+        Ok(poll.ok_or("Poll not found")?.id)
+    }
+
+    /// Record a member's vote, replacing their earlier one
+    pub async fn vote(&self, user_id: Uuid, poll_id: Uuid, choices: &[usize]) -> Result<PollResponse, Box<dyn std::error::Error>> {
+        let poll = self.find_for_member(user_id, poll_id).await?;
+        let choices = validate_choices(poll.options.len(), poll.multiple_choice, choices)?;
+
+        match self.poll_repository.replace_vote(poll_id, user_id, &choices).await? {
+            VoteOutcome::Recorded => self.response(&poll).await,
+            VoteOutcome::PollNotFound => Err("Poll not found".into()),
+            VoteOutcome::PollClosed => Err("Invalid vote: the poll is closed".into()),
+        }
+    }
+
+    /// Close a poll before its deadline; allowed for its creator and room moderators
+    pub async fn close_poll(&self, user_id: Uuid, poll_id: Uuid) -> Result<PollResponse, Box<dyn std::error::Error>> {
+        let poll = self.find_for_member(user_id, poll_id).await?;
+        if poll.created_by != Some(user_id)
+            && !self.room_service.member_role(&poll.room_name, user_id).await?.can_moderate()
+        {
+            return Err("Only the poll creator and room moderators can close a poll".into());
+        }
+
+        let poll = self
+            .poll_repository
+            .close_poll(poll_id)
+            .await?
+            .ok_or("Invalid request: the poll is already closed")?;
+        self.response(&poll).await
+    }
+
+    /// Close polls whose deadline has passed
+    pub async fn close_due_polls(&self) -> Result<Vec<PollResponse>, Box<dyn std::error::Error>> {
+        let polls = self.poll_repository.close_due_polls().await?;
+        let mut responses = Vec::with_capacity(polls.len());
+        for poll in &polls {
+            responses.push(self.response(poll).await?);
+        }
+        Ok(responses)
+    }
+
+    pub async fn set_results_message(&self, poll_id: Uuid, message_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(self.poll_repository.set_results_message(poll_id, message_id).await?)
+    }
+
+    /// Close polls at their deadline and announce the results, until the process exits.
+    ///
+    /// `notify` wakes the job when a poll with a deadline is created.
+    pub async fn run_close_job(self, state: ChatState, notify: Arc<Notify>) {
+        loop {
+            match self.close_due_polls().await.map_err(|e| e.to_string()) {
+                Ok(polls) => {
+                    for poll in polls {
+                        state.finish_poll(&poll).await;
+                    }
+                }
+                Err(e) => eprintln!("Failed to close due polls: {}", e),
+            }
+
+            let sleep = match self.poll_repository.next_deadline().await {
+                Ok(Some(deadline)) => {
+                    let remaining = deadline - OffsetDateTime::now_utc();
+                    Duration::try_from(remaining).unwrap_or(Duration::ZERO).min(CLOSE_POLL_INTERVAL)
+                }
+                Ok(None) => CLOSE_POLL_INTERVAL,
+                Err(e) => {
+                    eprintln!("Failed to load poll deadlines: {}", e);
+                    CLOSE_POLL_INTERVAL
+                }
+            };
+            let _ = tokio::time::timeout(sleep, notify.notified()).await;
+        }
+    }
+}
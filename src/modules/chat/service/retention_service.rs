@@ -0,0 +1,194 @@
+use crate::config::environment::Environment;
+use crate::modules::chat::dto::retention_dto::{RetentionPolicyResponse, SetRetentionPolicyDto};
+use crate::modules::chat::entities::room::RoomRole;
+use crate::modules::chat::repositories::{RetentionRepository, RoomRepository};
+use crate::modules::chat::service::AttachmentService;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tracing::info;
+use uuid::Uuid;
+
+/// Longest retention period a room can be given
+pub const MAX_RETENTION_DAYS: i32 = 36500;
+/// Uploads are shared in a message after they finish, so unreferenced attachments are
+/// only considered orphaned after this long
+const ORPHAN_ATTACHMENT_GRACE: time::Duration = time::Duration::days(1);
+/// Pause between purge batches, leaving the database to the chat hot path
+const PURGE_BATCH_PAUSE: Duration = Duration::from_millis(100);
+
+/// What one purge run removed
+#[derive(Debug, Default, PartialEq)]
+pub struct PurgeReport {
+    pub messages: i64,
+    /// Joins, leaves, topic changes and other room events kept for replay
+    pub events: i64,
+    pub attachments: usize,
+    pub blobs: usize,
+}
+
+pub struct RetentionService {
+    retention_repository: RetentionRepository,
+    room_repository: RoomRepository,
+    attachment_service: AttachmentService,
+    default_days: u32,
+    batch_size: i64,
+    interval: Duration,
+}
+
+impl RetentionService {
+    pub fn new(db_pool: PgPool, env: Environment) -> Self {
+        let default_days = env.chat.retention_days;
+        let batch_size = env.chat.retention_batch_size.max(1);
+        let interval = Duration::from_secs(env.chat.retention_interval_secs.max(1));
+        Self {
+            retention_repository: RetentionRepository::new(db_pool.clone()),
+            room_repository: RoomRepository::new(db_pool.clone()),
+            attachment_service: AttachmentService::new(db_pool, env),
+            default_days,
+            batch_size,
+            interval,
+        }
+    }
+
+    /// Get the retention policy of a room, for its members and server admins
+    pub async fn get_policy(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        room_name: &str,
+    ) -> Result<RetentionPolicyResponse, Box<dyn std::error::Error>> {
+        if !is_admin && !self.room_repository.is_member(room_name, user_id).await? {
+            return Err("Not a member of this room".into());
+        }
+        let retention = self
+            .retention_repository
+            .find_retention(room_name)
+            .await?
+            .ok_or("Room not found")?;
+        Ok(retention.to_response(self.default_days))
+    }
+
+    /// Change the retention policy of a room.
+    ///
+    /// Room owners and admins may change the retention period; only admins may place or
+    /// lift a legal hold.
+    pub async fn set_policy(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        room_name: &str,
+        policy: SetRetentionPolicyDto,
+    ) -> Result<RetentionPolicyResponse, Box<dyn std::error::Error>> {
+        if let Some(days) = policy.retention_days {
+            if !(0..=MAX_RETENTION_DAYS).contains(&days) {
+                return Err(format!("Invalid retention period: must be between 0 and {} days", MAX_RETENTION_DAYS).into());
+            }
+        }
+        if !is_admin {
+            let role = self.room_repository.find_member_role(room_name, user_id).await?;
+            if role.as_deref() != Some(RoomRole::Owner.as_str()) {
+                return Err("Only room owners and admins can change retention".into());
+            }
+            if policy.legal_hold.is_some() {
+                return Err("Only admins can place or lift a legal hold".into());
+            }
+        }
+
+        let retention = self
+            .retention_repository
+            .set_retention(room_name, policy.retention_days, policy.legal_hold)
+            .await?
+            .ok_or("Room not found")?;
+        Ok(retention.to_response(self.default_days))
+    }
+
+    /// Purge expired messages and room events, then attachments no message refers to any more
+    pub async fn purge(&self) -> Result<PurgeReport, Box<dyn std::error::Error>> {
+        let mut report = PurgeReport::default();
+        let default_days = (self.default_days > 0).then_some(self.default_days.min(MAX_RETENTION_DAYS as u32) as i32);
+
+        loop {
+            let purged = self
+                .retention_repository
+                .purge_expired_messages(default_days, self.batch_size)
+                .await?;
+            let deleted: i64 = purged.iter().map(|room| room.deleted).sum();
+            for room in &purged {
+                info!("Retention purged {} expired messages from room {}", room.deleted, room.room_name);
+            }
+            report.messages += deleted;
+            if deleted < self.batch_size {
+                break;
+            }
+            tokio::time::sleep(PURGE_BATCH_PAUSE).await;
+        }
+
+        loop {
+            let deleted = self
+                .retention_repository
+                .purge_expired_room_events(default_days, self.batch_size)
+                .await?;
+            report.events += deleted;
+            if deleted < self.batch_size {
+                break;
+            }
+            tokio::time::sleep(PURGE_BATCH_PAUSE).await;
+        }
+
+        let uploaded_before = OffsetDateTime::now_utc() - ORPHAN_ATTACHMENT_GRACE;
+        loop {
+            let purged = self
+                .retention_repository
+                .purge_orphaned_attachments(uploaded_before, self.batch_size)
+                .await?;
+
+            // Blobs are shared by identical uploads, so a file is only removed once no
+            // attachment uses it
+            let mut hashes = HashSet::new();
+            for attachment in &purged {
+                info!(
+                    "Retention purged orphaned attachment {} ({} bytes) from room {}",
+                    attachment.id, attachment.size_bytes, attachment.room_name
+                );
+                hashes.insert(attachment.sha256.as_str());
+            }
+            for sha256 in hashes {
+                if self.retention_repository.is_blob_referenced(sha256).await? {
+                    continue;
+                }
+                match tokio::fs::remove_file(self.attachment_service.blob_path(sha256)).await {
+                    Ok(()) => report.blobs += 1,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => eprintln!("Failed to remove attachment blob {}: {}", sha256, e),
+                }
+            }
+
+            report.attachments += purged.len();
+            if (purged.len() as i64) < self.batch_size {
+                break;
+            }
+            tokio::time::sleep(PURGE_BATCH_PAUSE).await;
+        }
+
+        Ok(report)
+    }
+
+    /// Purge on startup and then periodically until the process exits
+    pub async fn run_purge_job(self) {
+        let mut ticker = tokio::time::interval(self.interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            match self.purge().await {
+                Ok(report) if report != PurgeReport::default() => info!(
+                    "Retention purge removed {} messages, {} room events, {} attachments and {} stored files",
+                    report.messages, report.events, report.attachments, report.blobs
+                ),
+                Ok(_) => {}
+                Err(e) => eprintln!("Retention purge failed: {}", e),
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use crate::modules::chat::dto::room_dto::{PinnedMessagesResponse, RoomPrivacyResponse, RoomTopicResponse};
+use crate::modules::chat::entities::room::{RoomRole, DEFAULT_ROOM};
+use crate::modules::chat::repositories::room_repository::PinOutcome;
+use crate::modules::chat::repositories::RoomRepository;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Maximum number of messages that can be pinned in one room
+pub const MAX_PINNED_MESSAGES: usize = 10;
+/// Maximum length of a room topic in characters
+pub const MAX_TOPIC_LENGTH: usize = 500;
+
+pub struct RoomService {
+    room_repository: RoomRepository,
+}
+
+impl RoomService {
+    pub fn new(db_pool: PgPool) -> Self {
+        let room_repository = RoomRepository::new(db_pool);
+        Self { room_repository }
+    }
+
+    /// Get a user's role in a room, failing if they are not a member
+    pub async fn member_role(&self, room_name: &str, user_id: Uuid) -> Result<RoomRole, Box<dyn std::error::Error>> {
+        let role = self
+            .room_repository
+            .find_member_role(room_name, user_id)
+            .await?
+            .ok_or("Not a member of this room")?;
+        Ok(role.parse()?)
+    }
+
+    /// Get the role a user acts with in a room; server admins act as the owner of every room
+    pub async fn acting_role(&self, room_name: &str, user_id: Uuid, is_admin: bool) -> Result<RoomRole, Box<dyn std::error::Error>> {
+        if is_admin {
+            self.room_repository.find_room(room_name).await?.ok_or("Room not found")?;
+            return Ok(RoomRole::Owner);
+        }
+        self.member_role(room_name, user_id).await
+    }
+
+    async fn require_moderator(&self, room_name: &str, user_id: Uuid, is_admin: bool) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.acting_role(room_name, user_id, is_admin).await?.can_moderate() {
+            return Err("Only room owners and moderators can do this".into());
+        }
+        Ok(())
+    }
+
+    /// Read the topic of a room without checking membership
+    pub async fn room_topic(&self, room_name: &str) -> Result<RoomTopicResponse, Box<dyn std::error::Error>> {
+        let topic = match self.room_repository.find_room(room_name).await? {
+            Some(room) => room.to_topic_response(),
+            None => RoomTopicResponse {
+                room_name: room_name.to_string(),
+                topic: None,
+                set_by: None,
+                set_at: None,
+            },
+        };
+        Ok(topic)
+    }
+
+    /// Read the pinned messages of a room without checking membership
+    pub async fn room_pins(&self, room_name: &str) -> Result<PinnedMessagesResponse, Box<dyn std::error::Error>> {
+        let pinned = self.room_repository.list_pinned_messages(room_name).await?;
+        Ok(PinnedMessagesResponse {
+            room_name: room_name.to_string(),
+            pinned: pinned.iter().map(|pin| pin.to_response()).collect(),
+        })
+    }
+
+    pub async fn get_topic(&self, user_id: Uuid, room_name: &str) -> Result<RoomTopicResponse, Box<dyn std::error::Error>> {
+        self.member_role(room_name, user_id).await?;
+        self.room_topic(room_name).await
+    }
+
+    /// Change the topic; an empty topic clears it
+    pub async fn set_topic(&self, user_id: Uuid, is_admin: bool, room_name: &str, topic: &str) -> Result<RoomTopicResponse, Box<dyn std::error::Error>> {
+        self.require_moderator(room_name, user_id, is_admin).await?;
+
+        let topic = topic.trim();
+        if topic.chars().count() > MAX_TOPIC_LENGTH {
+            return Err(format!("Topic must be at most {} characters", MAX_TOPIC_LENGTH).into());
+        }
+
+        let room = self
+            .room_repository
+            .set_topic(room_name, (!topic.is_empty()).then_some(topic), user_id)
+            .await?
+            .ok_or("Room not found")?;
+        Ok(room.to_topic_response())
+    }
+
+    pub async fn get_pins(&self, user_id: Uuid, room_name: &str) -> Result<PinnedMessagesResponse, Box<dyn std::error::Error>> {
+        self.member_role(room_name, user_id).await?;
+        self.room_pins(room_name).await
+    }
+
+    pub async fn pin_message(&self, user_id: Uuid, is_admin: bool, room_name: &str, message_id: Uuid) -> Result<PinnedMessagesResponse, Box<dyn std::error::Error>> {
+        self.require_moderator(room_name, user_id, is_admin).await?;
+
+        match self
+            .room_repository
+            .pin_message(room_name, message_id, user_id, MAX_PINNED_MESSAGES as i64)
+            .await?
+        {
+            PinOutcome::Pinned | PinOutcome::AlreadyPinned => self.room_pins(room_name).await,
+            PinOutcome::LimitReached => {
+                Err(format!("A room can have at most {} pinned messages", MAX_PINNED_MESSAGES).into())
+            }
+            PinOutcome::MessageNotFound => Err("Message not found in this room".into()),
+        }
+    }
+
+    pub async fn unpin_message(&self, user_id: Uuid, is_admin: bool, room_name: &str, message_id: Uuid) -> Result<PinnedMessagesResponse, Box<dyn std::error::Error>> {
+        self.require_moderator(room_name, user_id, is_admin).await?;
+
+        if !self.room_repository.unpin_message(room_name, message_id).await? {
+            return Err("Pinned message not found".into());
+        }
+        self.room_pins(room_name).await
+    }
+
+    /// Let the room owner or a server admin promote or demote members
+    pub async fn set_member_role(&self, user_id: Uuid, is_admin: bool, room_name: &str, member_id: Uuid, role: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if self.acting_role(room_name, user_id, is_admin).await? != RoomRole::Owner {
+            return Err("Only the room owner can change member roles".into());
+        }
+
+        let role: RoomRole = role.parse()?;
+        if role == RoomRole::Owner || member_id == user_id {
+            return Err("Invalid room role: ownership cannot be transferred".into());
+        }
+
+        if !self.room_repository.set_member_role(room_name, member_id, role.as_str()).await? {
+            return Err("Member not found in this room".into());
+        }
+        Ok(())
+    }
+
+    /// Let the room owner make the room private, so only members and invited users can join.
+    /// The default room is always public.
+    pub async fn set_private(&self, user_id: Uuid, is_admin: bool, room_name: &str, private: bool) -> Result<RoomPrivacyResponse, Box<dyn std::error::Error>> {
+        if room_name == DEFAULT_ROOM {
+            return Err(format!("Invalid room: {} is always public", DEFAULT_ROOM).into());
+        }
+        if self.acting_role(room_name, user_id, is_admin).await? != RoomRole::Owner {
+            return Err("Only the room owner can change room privacy".into());
+        }
+
+        let private = self
+            .room_repository
+            .set_private(room_name, private)
+            .await?
+            .ok_or("Room not found")?;
+        Ok(RoomPrivacyResponse {
+            room_name: room_name.to_string(),
+            private,
+        })
+    }
+}
@@ -0,0 +1,231 @@
+use crate::modules::chat::dto::scheduled_message_dto::{
+    ScheduleMessageDto, ScheduledMessageResponse, UpdateScheduledMessageDto,
+};
+use crate::modules::chat::entities::scheduled_message::ScheduledMessage;
+use crate::modules::chat::repositories::scheduled_message_repository::NewScheduledMessage;
+use crate::modules::chat::repositories::{AttachmentRepository, ScheduledMessageRepository};
+use crate::modules::chat::server::{display_name, ChatState, SendOutcome, UserMessage};
+use crate::modules::chat::service::RoomService;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::sync::Notify;
+use tracing::info;
+use uuid::Uuid;
+
+/// Maximum length of a scheduled message in characters
+pub const MAX_SCHEDULED_MESSAGE_LENGTH: usize = 4000;
+/// Messages can be scheduled at most this far ahead
+pub const MAX_SCHEDULE_AHEAD: time::Duration = time::Duration::days(365);
+/// How long the scheduler sleeps when no message is pending
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Check the text and send time of a scheduled message
+pub fn validate_schedule(message: Option<&str>, send_at: Option<OffsetDateTime>) -> Result<(), String> {
+    if let Some(message) = message {
+        if message.trim().is_empty() || message.chars().count() > MAX_SCHEDULED_MESSAGE_LENGTH {
+            return Err(format!(
+                "Invalid message: must be 1 to {} characters",
+                MAX_SCHEDULED_MESSAGE_LENGTH
+            ));
+        }
+    }
+    if let Some(send_at) = send_at {
+        let now = OffsetDateTime::now_utc();
+        if send_at <= now || send_at > now + MAX_SCHEDULE_AHEAD {
+            return Err("Invalid send time: must be in the future and at most 365 days away".to_string());
+        }
+    }
+    Ok(())
+}
+
+pub struct ScheduledMessageService {
+    scheduled_message_repository: ScheduledMessageRepository,
+    attachment_repository: AttachmentRepository,
+    room_service: RoomService,
+}
+
+impl ScheduledMessageService {
+    pub fn new(db_pool: PgPool) -> Self {
+        let scheduled_message_repository = ScheduledMessageRepository::new(db_pool.clone());
+        let attachment_repository = AttachmentRepository::new(db_pool.clone());
+        let room_service = RoomService::new(db_pool);
+        Self {
+            scheduled_message_repository,
+            attachment_repository,
+            room_service,
+        }
+    }
+
+    /// Schedule a message to a room the user belongs to
+    pub async fn schedule(
+        &self,
+        user_id: Uuid,
+        room_name: &str,
+        message: &ScheduleMessageDto,
+    ) -> Result<ScheduledMessageResponse, Box<dyn std::error::Error>> {
+        validate_schedule(Some(&message.message), Some(message.send_at))?;
+        self.room_service.member_role(room_name, user_id).await?;
+
+        // Only attachments shared in this room may be referenced from it
+        if let Some(attachment_id) = message.attachment_id {
+            let attachment = self.attachment_repository.find_attachment_by_id(attachment_id).await?;
+            if attachment.is_none_or(|attachment| attachment.room_name != room_name) {
+                return Err(format!("Invalid attachment: {} was not shared in {}", attachment_id, room_name).into());
+            }
+        }
+
+        let new_message = NewScheduledMessage {
+            room_name,
+            user_id,
+            body: &message.message,
+            attachment_id: message.attachment_id,
+            is_action: message.action,
+            send_at: message.send_at,
+        };
+        let scheduled = self
+            .scheduled_message_repository
+            .create_scheduled_message(&new_message)
+            .await?;
+        Ok(scheduled.to_response())
+    }
+
+    /// A user's pending scheduled messages, soonest first
+    pub async fn list_pending(
+        &self,
+        user_id: Uuid,
+        room_name: Option<&str>,
+    ) -> Result<Vec<ScheduledMessageResponse>, Box<dyn std::error::Error>> {
+        let scheduled = self.scheduled_message_repository.list_pending(user_id, room_name).await?;
+        Ok(scheduled.iter().map(ScheduledMessage::to_response).collect())
+    }
+
+    pub async fn get(&self, user_id: Uuid, id: Uuid) -> Result<ScheduledMessageResponse, Box<dyn std::error::Error>> {
+        let scheduled = self
+            .scheduled_message_repository
+            .find_scheduled_message(id, user_id)
+            .await?
+            .ok_or("Scheduled message not found")?;
+        Ok(scheduled.to_response())
+    }
+
+    /// Change the text or send time of a pending message
+    pub async fn update(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        changes: &UpdateScheduledMessageDto,
+    ) -> Result<ScheduledMessageResponse, Box<dyn std::error::Error>> {
+        validate_schedule(changes.message.as_deref(), changes.send_at)?;
+        match self
+            .scheduled_message_repository
+            .update_pending(id, user_id, changes.message.as_deref(), changes.send_at)
+            .await?
+        {
+            Some(scheduled) => Ok(scheduled.to_response()),
+            None => Err(self.not_pending(user_id, id).await),
+        }
+    }
+
+    /// Cancel a pending message
+    pub async fn cancel(&self, user_id: Uuid, id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+        if self.scheduled_message_repository.delete_pending(id, user_id).await? {
+            Ok(())
+        } else {
+            Err(self.not_pending(user_id, id).await)
+        }
+    }
+
+    /// Explain why a message could not be changed
+    async fn not_pending(&self, user_id: Uuid, id: Uuid) -> Box<dyn std::error::Error> {
+        match self.scheduled_message_repository.find_scheduled_message(id, user_id).await {
+            Ok(Some(_)) => "Invalid request: the message has already been sent".into(),
+            Ok(None) => "Scheduled message not found".into(),
+            Err(e) => e.into(),
+        }
+    }
+
+    /// Post a claimed message as if its author had sent it live, and record the outcome
+    async fn deliver(&self, state: &ChatState, scheduled: ScheduledMessage) -> Result<(), Box<dyn std::error::Error>> {
+        let username = display_name(scheduled.user_id);
+        let membership = self
+            .room_service
+            .member_role(&scheduled.room_name, scheduled.user_id)
+            .await
+            .map_err(|e| e.to_string());
+        let result = match membership {
+            Ok(_) => {
+                let message = UserMessage {
+                    room_name: &scheduled.room_name,
+                    user_id: scheduled.user_id,
+                    username: &username,
+                    message: scheduled.body.clone(),
+                    attachment_id: scheduled.attachment_id,
+                    action: scheduled.is_action,
+                };
+                state.send_user_message(message).await
+            }
+            Err(e) => Err(e),
+        };
+
+        let (status, message_id, error) = match result {
+            Ok(SendOutcome::Sent(message)) => ("sent", message.id.and_then(|id| Uuid::parse_str(&id).ok()), None),
+            Ok(SendOutcome::HeldForReview) => ("held", None, None),
+            Err(e) => ("failed", None, Some(e)),
+        };
+        match &error {
+            Some(e) => {
+                state.send_system_to_user(
+                    scheduled.user_id,
+                    format!("Your scheduled message to {} could not be sent: {}", scheduled.room_name, e),
+                );
+            }
+            None => info!("Sent scheduled message {} to room {}", scheduled.id, scheduled.room_name),
+        }
+        self.scheduled_message_repository
+            .finish(scheduled.id, status, message_id, error.as_deref())
+            .await?;
+        Ok(())
+    }
+
+    /// Post due messages until none is left
+    async fn deliver_due(&self, state: &ChatState) -> Result<(), Box<dyn std::error::Error>> {
+        while let Some(scheduled) = self.scheduled_message_repository.claim_due().await? {
+            self.deliver(state, scheduled).await?;
+        }
+        Ok(())
+    }
+
+    /// Post scheduled messages when they are due, until the process exits.
+    ///
+    /// Messages that came due while the server was down are posted on startup. A message
+    /// being posted when the server stopped is posted again, so it may appear twice.
+    /// `notify` wakes the scheduler when a message is scheduled or rescheduled.
+    pub async fn run_scheduler(self, state: ChatState, notify: Arc<Notify>) {
+        match self.scheduled_message_repository.release_claimed().await {
+            Ok(0) => {}
+            Ok(released) => info!("Requeued {} scheduled messages interrupted by a restart", released),
+            Err(e) => eprintln!("Failed to requeue scheduled messages: {}", e),
+        }
+
+        loop {
+            if let Err(e) = self.deliver_due(&state).await.map_err(|e| e.to_string()) {
+                eprintln!("Failed to send scheduled messages: {}", e);
+            }
+
+            let sleep = match self.scheduled_message_repository.next_send_at().await {
+                Ok(Some(send_at)) => {
+                    let remaining = send_at - OffsetDateTime::now_utc();
+                    Duration::try_from(remaining).unwrap_or(Duration::ZERO).min(SCHEDULER_INTERVAL)
+                }
+                Ok(None) => SCHEDULER_INTERVAL,
+                Err(e) => {
+                    eprintln!("Failed to load scheduled message times: {}", e);
+                    SCHEDULER_INTERVAL
+                }
+            };
+            let _ = tokio::time::timeout(sleep, notify.notified()).await;
+        }
+    }
+}
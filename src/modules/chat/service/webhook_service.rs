@@ -0,0 +1,204 @@
+use crate::config::env::ChatConfig;
+use crate::config::environment::Environment;
+use crate::modules::chat::dto::webhook_dto::{WebhookDeliveryResponse, WebhookResponse};
+use crate::modules::chat::entities::room::RoomRole;
+use crate::modules::chat::entities::webhook::ClaimedDelivery;
+use crate::modules::chat::repositories::WebhookRepository;
+use crate::modules::chat::service::RoomService;
+use crate::modules::chat::webhooks::{check_destination, delivery_client, post_event, retry_delay, WebhookEvent};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+/// Maximum number of webhooks per room
+pub const MAX_WEBHOOKS_PER_ROOM: usize = 10;
+/// Number of deliveries listed in a webhook's delivery log
+const DELIVERY_LOG_LIMIT: i64 = 100;
+/// Deliveries attempted concurrently by the worker
+const DELIVERY_BATCH_SIZE: i64 = 20;
+/// How often the worker looks for due retries when it is not woken up
+const DELIVERY_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+pub struct WebhookService {
+    webhook_repository: WebhookRepository,
+    room_service: RoomService,
+}
+
+impl WebhookService {
+    pub fn new(db_pool: PgPool) -> Self {
+        let webhook_repository = WebhookRepository::new(db_pool.clone());
+        let room_service = RoomService::new(db_pool);
+        Self {
+            webhook_repository,
+            room_service,
+        }
+    }
+
+    async fn require_owner(&self, room_name: &str, user_id: Uuid, is_admin: bool) -> Result<(), Box<dyn std::error::Error>> {
+        if self.room_service.acting_role(room_name, user_id, is_admin).await? != RoomRole::Owner {
+            return Err("Only the room owner can manage webhooks".into());
+        }
+        Ok(())
+    }
+
+    /// Register a webhook; the response is the only place its signing secret is shown
+    pub async fn create_webhook(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        room_name: &str,
+        url: &str,
+    ) -> Result<WebhookResponse, Box<dyn std::error::Error>> {
+        self.require_owner(room_name, user_id, is_admin).await?;
+
+        let parsed = url::Url::parse(url.trim()).map_err(|_| "Invalid webhook URL")?;
+        if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+            return Err("Invalid webhook URL: only http and https URLs are supported".into());
+        }
+        let allow_private = Environment::from_env().chat.webhook_allow_private_addresses;
+        check_destination(parsed.as_str(), allow_private).map_err(|e| format!("Invalid webhook URL: {}", e))?;
+        if self.webhook_repository.list_webhooks(room_name).await?.len() >= MAX_WEBHOOKS_PER_ROOM {
+            return Err(format!("A room can have at most {} webhooks", MAX_WEBHOOKS_PER_ROOM).into());
+        }
+
+        let secret = format!("whsec_{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let webhook = self
+            .webhook_repository
+            .create_webhook(room_name, parsed.as_str(), &secret, user_id)
+            .await?;
+        Ok(webhook.to_response(true))
+    }
+
+    pub async fn list_webhooks(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        room_name: &str,
+    ) -> Result<Vec<WebhookResponse>, Box<dyn std::error::Error>> {
+        self.require_owner(room_name, user_id, is_admin).await?;
+        let webhooks = self.webhook_repository.list_webhooks(room_name).await?;
+        Ok(webhooks.iter().map(|webhook| webhook.to_response(false)).collect())
+    }
+
+    pub async fn delete_webhook(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        room_name: &str,
+        webhook_id: Uuid,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.require_owner(room_name, user_id, is_admin).await?;
+        if !self.webhook_repository.delete_webhook(room_name, webhook_id).await? {
+            return Err("Webhook not found".into());
+        }
+        Ok(())
+    }
+
+    pub async fn list_deliveries(
+        &self,
+        user_id: Uuid,
+        is_admin: bool,
+        room_name: &str,
+        webhook_id: Uuid,
+    ) -> Result<Vec<WebhookDeliveryResponse>, Box<dyn std::error::Error>> {
+        self.require_owner(room_name, user_id, is_admin).await?;
+        self.webhook_repository
+            .find_webhook(room_name, webhook_id)
+            .await?
+            .ok_or("Webhook not found")?;
+
+        let deliveries = self
+            .webhook_repository
+            .list_deliveries(webhook_id, DELIVERY_LOG_LIMIT)
+            .await?;
+        Ok(deliveries.iter().map(|delivery| delivery.to_response()).collect())
+    }
+
+    /// Queue an event for the room's webhooks, returning how many deliveries were queued
+    pub async fn enqueue_event(&self, event: &WebhookEvent) -> Result<u64, Box<dyn std::error::Error>> {
+        let payload = serde_json::to_string(event)?;
+        let queued = self
+            .webhook_repository
+            .enqueue_deliveries(&event.room_name, event.id, event.event_type.as_str(), &payload)
+            .await?;
+        Ok(queued)
+    }
+
+    /// Deliver queued events until the process exits.
+    ///
+    /// The worker wakes up when `notify` is signalled after events are queued, and
+    /// polls periodically for retries that have become due.
+    pub async fn run_delivery_worker(self, notify: Arc<Notify>) {
+        let config = Environment::from_env().chat;
+        // A claimed delivery is retried after this long if its attempt never completes
+        let lease = Duration::from_secs(config.webhook_timeout_secs * 2 + 30);
+
+        loop {
+            let lease_until = OffsetDateTime::now_utc() + lease;
+            let deliveries = match self
+                .webhook_repository
+                .claim_due_deliveries(DELIVERY_BATCH_SIZE, lease_until)
+                .await
+            {
+                Ok(deliveries) => deliveries,
+                Err(e) => {
+                    eprintln!("Failed to load webhook deliveries: {}", e);
+                    Vec::new()
+                }
+            };
+
+            if deliveries.is_empty() {
+                let _ = tokio::time::timeout(DELIVERY_POLL_INTERVAL, notify.notified()).await;
+                continue;
+            }
+
+            let attempts = deliveries.iter().map(|delivery| self.attempt_delivery(delivery, &config));
+            futures::future::join_all(attempts).await;
+        }
+    }
+
+    async fn attempt_delivery(
+        &self,
+        delivery: &ClaimedDelivery,
+        config: &ChatConfig,
+    ) {
+        let timeout = Duration::from_secs(config.webhook_timeout_secs);
+        let result = match delivery_client(&delivery.url, timeout, config.webhook_allow_private_addresses).await {
+            Ok(client) => {
+                post_event(
+                    &client,
+                    &delivery.url,
+                    &delivery.secret,
+                    delivery.id,
+                    &delivery.event_type,
+                    &delivery.payload,
+                )
+                .await
+            }
+            Err(e) => Err((None, e)),
+        };
+
+        let recorded = match result {
+            Ok(status_code) => {
+                self.webhook_repository
+                    .mark_delivered(delivery.id, i32::from(status_code))
+                    .await
+            }
+            Err((status_code, error)) => {
+                let attempts = delivery.attempts.max(0) as u32;
+                let base_delay = Duration::from_millis(config.webhook_retry_base_ms);
+                let retry_at = (attempts < config.webhook_max_attempts)
+                    .then(|| OffsetDateTime::now_utc() + retry_delay(attempts, base_delay));
+                self.webhook_repository
+                    .mark_attempt_failed(delivery.id, status_code.map(i32::from), &error, retry_at)
+                    .await
+            }
+        };
+        if let Err(e) = recorded {
+            eprintln!("Failed to record webhook delivery {}: {}", delivery.id, e);
+        }
+    }
+}
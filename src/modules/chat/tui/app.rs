@@ -0,0 +1,627 @@
+//! State of the terminal chat client, independent of how it is drawn
+
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use time::{macros::format_description, OffsetDateTime};
+
+use crate::modules::chat::client::ChatEvent;
+use crate::modules::chat::server::DirectMessage;
+
+/// Lines kept per buffer
+const MAX_SCROLLBACK: usize = 2000;
+/// Entries kept in the input history
+const MAX_HISTORY: usize = 500;
+/// DMs and mentions reach every connection of a user, so recent ones are remembered
+/// to show each only once
+const RECENT_DELIVERIES: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Message,
+    Action,
+    Bot,
+    Direct,
+    System,
+    Announcement,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChatLine {
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    pub kind: LineKind,
+    pub author: Option<String>,
+    pub text: String,
+}
+
+impl ChatLine {
+    fn new(kind: LineKind, author: Option<String>, text: impl Into<String>) -> Self {
+        ChatLine {
+            timestamp: now_ms(),
+            kind,
+            author,
+            text: text.into(),
+        }
+    }
+
+    /// The line without its timestamp, as shown in the scrollback
+    pub fn body(&self) -> String {
+        match (self.kind, &self.author) {
+            (LineKind::Action, Some(author)) => format!("* {} {}", author, self.text),
+            (LineKind::System, _) => format!("-- {}", self.text),
+            (LineKind::Announcement, _) => format!("** {}", self.text),
+            (LineKind::Error, _) => format!("!! {}", self.text),
+            (_, Some(author)) => format!("<{}> {}", author, self.text),
+            (_, None) => self.text.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferKind {
+    Room,
+    /// Conversation with one user through direct messages
+    Direct,
+}
+
+#[derive(Debug)]
+pub struct Buffer {
+    pub name: String,
+    pub kind: BufferKind,
+    pub lines: VecDeque<ChatLine>,
+    pub members: BTreeSet<String>,
+    pub topic: Option<String>,
+    pub pinned: usize,
+    pub connected: bool,
+    /// Lines received while another buffer was shown
+    pub unread: usize,
+    /// Set when the user was mentioned while another buffer was shown
+    pub highlighted: bool,
+    /// Lines scrolled up from the bottom
+    pub scroll: usize,
+}
+
+impl Buffer {
+    fn new(name: String, kind: BufferKind) -> Self {
+        Buffer {
+            name,
+            kind,
+            lines: VecDeque::new(),
+            members: BTreeSet::new(),
+            topic: None,
+            pinned: 0,
+            connected: false,
+            unread: 0,
+            highlighted: false,
+            scroll: 0,
+        }
+    }
+
+    /// Name shown in the buffer list and the plain output, e.g. "#general" or "@User_1a2b3c4d"
+    pub fn label(&self) -> String {
+        match self.kind {
+            BufferKind::Room => format!("#{}", self.name),
+            BufferKind::Direct => format!("@{}", self.name),
+        }
+    }
+
+    fn push(&mut self, line: ChatLine) {
+        if self.lines.len() == MAX_SCROLLBACK {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+        if self.scroll > 0 {
+            self.scroll += 1;
+        }
+    }
+}
+
+/// Something the runner has to do on the app's behalf
+#[derive(Debug, PartialEq, Eq)]
+pub enum Request {
+    Message { room: String, text: String },
+    /// A slash command for the server, run in `room`
+    Command { room: String, command: String },
+    DirectMessage { room: String, to: String, text: String },
+    /// Connect to another room
+    Open(String),
+    /// Disconnect from a room whose buffer was closed
+    Close(String),
+    Quit,
+}
+
+pub struct App {
+    /// Display name of the logged in user, if known
+    pub username: Option<String>,
+    pub buffers: Vec<Buffer>,
+    pub active: usize,
+    pub input: String,
+    /// Cursor position in `input`, in characters
+    pub cursor: usize,
+    history: Vec<String>,
+    history_index: Option<usize>,
+    draft: String,
+    /// Rooms whose `/who` reply is used for the member list rather than shown
+    pending_who: HashSet<String>,
+    /// Direct messages sent but not yet echoed back, as recipient and text
+    pending_direct: VecDeque<(String, String)>,
+    recent: VecDeque<String>,
+    /// Lines added by the client itself, with the label of their buffer, for the plain output
+    pub notices: Vec<(String, ChatLine)>,
+}
+
+impl App {
+    pub fn new(username: Option<String>) -> Self {
+        App {
+            username,
+            buffers: Vec::new(),
+            active: 0,
+            input: String::new(),
+            cursor: 0,
+            history: Vec::new(),
+            history_index: None,
+            draft: String::new(),
+            pending_who: HashSet::new(),
+            pending_direct: VecDeque::new(),
+            recent: VecDeque::new(),
+            notices: Vec::new(),
+        }
+    }
+
+    pub fn active_buffer(&self) -> Option<&Buffer> {
+        self.buffers.get(self.active)
+    }
+
+    fn buffer_index(&self, name: &str, kind: BufferKind) -> Option<usize> {
+        self.buffers.iter().position(|buffer| buffer.kind == kind && buffer.name == name)
+    }
+
+    /// Index of the buffer, which is created if needed
+    fn buffer(&mut self, name: &str, kind: BufferKind) -> usize {
+        match self.buffer_index(name, kind) {
+            Some(index) => index,
+            None => {
+                self.buffers.push(Buffer::new(name.to_string(), kind));
+                self.buffers.len() - 1
+            }
+        }
+    }
+
+    /// Add a room buffer and show it
+    pub fn open_room(&mut self, room: &str) {
+        self.active = self.buffer(room, BufferKind::Room);
+    }
+
+    /// Add a room's `/who` reply to its member list instead of the scrollback
+    pub fn expect_who(&mut self, room: &str) {
+        self.pending_who.insert(room.to_string());
+    }
+
+    /// Show the echo of a direct message being sent in the conversation with `to`
+    pub fn expect_direct_message(&mut self, to: &str, text: &str) {
+        if self.pending_direct.len() == RECENT_DELIVERIES {
+            self.pending_direct.pop_front();
+        }
+        self.pending_direct.push_back((to.to_string(), text.to_string()));
+    }
+
+    /// Whether a direct message is the echo of one sent from here or by this user elsewhere
+    fn is_outgoing(&mut self, message: &DirectMessage) -> bool {
+        let sent = self
+            .pending_direct
+            .iter()
+            .position(|(to, text)| *to == message.to_username && *text == message.message);
+        match sent {
+            Some(index) => {
+                self.pending_direct.remove(index);
+                true
+            }
+            None => self.username.as_deref() == Some(message.from_username.as_str()),
+        }
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if let Some(buffer) = self.buffers.get_mut(index) {
+            buffer.unread = 0;
+            buffer.highlighted = false;
+            self.active = index;
+        }
+    }
+
+    fn select_next(&mut self, forward: bool) {
+        if self.buffers.is_empty() {
+            return;
+        }
+        let count = self.buffers.len();
+        let index = if forward { (self.active + 1) % count } else { (self.active + count - 1) % count };
+        self.select(index);
+    }
+
+    fn close_buffer(&mut self, index: usize) {
+        self.buffers.remove(index);
+        if self.active >= self.buffers.len() {
+            self.active = self.buffers.len().saturating_sub(1);
+        }
+        self.select(self.active);
+    }
+
+    /// Room that commands typed in the active buffer run in
+    fn command_room(&self) -> Option<String> {
+        match self.active_buffer() {
+            Some(buffer) if buffer.kind == BufferKind::Room => Some(buffer.name.clone()),
+            _ => self
+                .buffers
+                .iter()
+                .find(|buffer| buffer.kind == BufferKind::Room)
+                .map(|buffer| buffer.name.clone()),
+        }
+    }
+
+    /// Show a message from the client itself in the active buffer
+    pub fn notice(&mut self, kind: LineKind, text: impl Into<String>) {
+        let line = ChatLine::new(kind, None, text);
+        let label = match self.buffers.get_mut(self.active) {
+            Some(buffer) => {
+                buffer.push(line.clone());
+                buffer.label()
+            }
+            None => "*".to_string(),
+        };
+        self.notices.push((label, line));
+    }
+
+    /// Apply an event from `room`'s connection, returning the line it added, if any,
+    /// with the label of the buffer it went to
+    pub fn handle_event(&mut self, room: &str, event: ChatEvent) -> Option<(String, ChatLine)> {
+        let room_index = self.buffer_index(room, BufferKind::Room)?;
+        let (index, line, mentioned) = match event {
+            ChatEvent::Connected { .. } => {
+                self.buffers[room_index].connected = true;
+                (room_index, ChatLine::new(LineKind::System, None, format!("Connected to #{}", room)), false)
+            }
+            ChatEvent::Disconnected { reason, retry_in } => {
+                self.buffers[room_index].connected = false;
+                let text = match retry_in {
+                    Some(delay) => format!("Disconnected: {} (reconnecting in {:.1}s)", reason, delay.as_secs_f32()),
+                    None => format!("Disconnected: {}", reason),
+                };
+                (room_index, ChatLine::new(LineKind::Error, None, text), false)
+            }
+            ChatEvent::Message(message) => {
+                let kind = match (message.action, message.bot) {
+                    (true, _) => LineKind::Action,
+                    (false, true) => LineKind::Bot,
+                    (false, false) => LineKind::Message,
+                };
+                let mut text = message.message;
+                if let Some(attachment_id) = message.attachment_id {
+                    text = format!("{} [attachment {}]", text, attachment_id);
+                }
+                let line = ChatLine {
+                    timestamp: message.timestamp,
+                    kind,
+                    author: Some(message.username),
+                    text,
+                };
+                (room_index, line, false)
+            }
+            ChatEvent::DirectMessage(message) => {
+                if !self.first_delivery(direct_message_key(&message)) {
+                    return None;
+                }
+                let outgoing = self.is_outgoing(&message);
+                let peer = if outgoing { &message.to_username } else { &message.from_username };
+                let index = self.buffer(peer, BufferKind::Direct);
+                let line = ChatLine {
+                    timestamp: message.timestamp,
+                    kind: LineKind::Direct,
+                    author: Some(message.from_username),
+                    text: message.message,
+                };
+                (index, line, !outgoing)
+            }
+            ChatEvent::Mention(notification) => {
+                if !self.first_delivery(notification.id) {
+                    return None;
+                }
+                // The message itself shows up in the room; only flag the buffer
+                if let Some(index) = self.buffer_index(&notification.room_name, BufferKind::Room) {
+                    if index != self.active {
+                        self.buffers[index].highlighted = true;
+                    }
+                    return None;
+                }
+                let text = format!("{} mentioned you in #{}: {}", notification.actor_username, notification.room_name, notification.message);
+                (self.active, ChatLine::new(LineKind::System, None, text), true)
+            }
+            ChatEvent::Topic(topic) => {
+                let buffer = &mut self.buffers[room_index];
+                if buffer.topic == topic.topic {
+                    return None;
+                }
+                buffer.topic = topic.topic;
+                let text = match (&buffer.topic, topic.set_by) {
+                    (Some(text), Some(by)) => format!("Topic: {} (set by {})", text, by),
+                    (Some(text), None) => format!("Topic: {}", text),
+                    (None, _) => "The topic was cleared".to_string(),
+                };
+                (room_index, ChatLine::new(LineKind::System, None, text), false)
+            }
+            ChatEvent::Pins(pins) => {
+                self.buffers[room_index].pinned = pins.pinned.len();
+                return None;
+            }
+            ChatEvent::Poll(poll) => {
+                let tallies: Vec<String> = poll
+                    .options
+                    .iter()
+                    .map(|option| format!("{}. {} ({})", option.index + 1, option.text, option.votes))
+                    .collect();
+                let state = if poll.closed { "Poll closed" } else { "Poll" };
+                let text = format!("{}: {} {}", state, poll.question, tallies.join(" | "));
+                (room_index, ChatLine::new(LineKind::System, None, text), false)
+            }
+            ChatEvent::System(system) => {
+                if self.pending_who.contains(room) {
+                    if let Some(names) = system.message.strip_prefix(&format!("Users in {}: ", room)) {
+                        self.pending_who.remove(room);
+                        self.buffers[room_index].members = names.split(", ").map(str::to_string).collect();
+                        return None;
+                    }
+                }
+                let members = &mut self.buffers[room_index].members;
+                if let Some(name) = system.message.strip_suffix(" has joined the chat.") {
+                    members.insert(name.to_string());
+                } else if let Some(name) = system.message.strip_suffix(" has left the chat.") {
+                    members.remove(name);
+                }
+                let line = ChatLine {
+                    timestamp: system.timestamp,
+                    kind: LineKind::System,
+                    author: None,
+                    text: system.message,
+                };
+                (room_index, line, false)
+            }
+            ChatEvent::Announcement(announcement) => {
+                // Every open room's connection receives it; show it once, where the user looks
+                if !self.first_delivery(announcement.id) {
+                    return None;
+                }
+                let line = ChatLine {
+                    timestamp: announcement.timestamp,
+                    kind: LineKind::Announcement,
+                    author: None,
+                    text: format!("Announcement from {}: {}", announcement.author, announcement.message),
+                };
+                (self.active, line, true)
+            }
+            ChatEvent::Error(error) => {
+                let line = ChatLine {
+                    timestamp: error.timestamp,
+                    kind: LineKind::Error,
+                    author: None,
+                    text: error.message,
+                };
+                (room_index, line, false)
+            }
+            ChatEvent::Session(_) | ChatEvent::ReauthRequired(_) => return None,
+            ChatEvent::Unknown(frame) => (room_index, ChatLine::new(LineKind::System, None, frame), false),
+        };
+
+        let active = self.active;
+        let buffer = &mut self.buffers[index];
+        buffer.push(line.clone());
+        if index != active {
+            buffer.unread += 1;
+            buffer.highlighted |= mentioned;
+        }
+        Some((buffer.label(), line))
+    }
+
+    /// Whether a DM or notification is seen for the first time
+    fn first_delivery(&mut self, key: String) -> bool {
+        if self.recent.contains(&key) {
+            return false;
+        }
+        if self.recent.len() == RECENT_DELIVERIES {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(key);
+        true
+    }
+
+    /// Handle a submitted input line
+    pub fn submit(&mut self, input: &str) -> Option<Request> {
+        let input = input.trim_end();
+        if input.trim().is_empty() {
+            return None;
+        }
+        if self.history.last().map(String::as_str) != Some(input) {
+            if self.history.len() == MAX_HISTORY {
+                self.history.remove(0);
+            }
+            self.history.push(input.to_string());
+        }
+
+        let Some(command) = input.strip_prefix('/').filter(|rest| !rest.starts_with('/')) else {
+            // "//text" sends a literal leading slash
+            let text = input.strip_prefix('/').unwrap_or(input).to_string();
+            return self.send_text(text);
+        };
+        let (name, args) = command.split_once(' ').map_or((command, ""), |(name, args)| (name, args.trim()));
+        match name {
+            "quit" | "exit" => Some(Request::Quit),
+            "join" if !args.is_empty() => {
+                let room = args.strip_prefix('#').unwrap_or(args);
+                match self.buffer_index(room, BufferKind::Room) {
+                    Some(index) => {
+                        self.select(index);
+                        None
+                    }
+                    None => Some(Request::Open(room.to_string())),
+                }
+            }
+            "leave" | "close" => {
+                let buffer = self.active_buffer()?;
+                let request = match buffer.kind {
+                    BufferKind::Room => Request::Close(buffer.name.clone()),
+                    BufferKind::Direct => {
+                        self.close_buffer(self.active);
+                        return None;
+                    }
+                };
+                self.close_buffer(self.active);
+                Some(request)
+            }
+            "query" if !args.is_empty() => {
+                let index = self.buffer(args, BufferKind::Direct);
+                self.select(index);
+                None
+            }
+            "dm" => {
+                let Some((to, text)) = args.split_once(' ') else {
+                    self.notice(LineKind::Error, "Usage: /dm <user> <text>");
+                    return None;
+                };
+                self.direct_message(to.to_string(), text.to_string())
+            }
+            _ => {
+                if name == "help" {
+                    self.notice(
+                        LineKind::System,
+                        "Client commands: /join <room> opens a room, /leave closes it, /query <user> opens a conversation, /close closes it, /quit exits",
+                    );
+                }
+                let Some(room) = self.command_room() else {
+                    self.notice(LineKind::Error, "Join a room first with /join <room>");
+                    return None;
+                };
+                Some(Request::Command {
+                    room,
+                    command: input.to_string(),
+                })
+            }
+        }
+    }
+
+    fn send_text(&mut self, text: String) -> Option<Request> {
+        let buffer = self.active_buffer()?;
+        match buffer.kind {
+            BufferKind::Room => Some(Request::Message {
+                room: buffer.name.clone(),
+                text,
+            }),
+            BufferKind::Direct => {
+                let to = buffer.name.clone();
+                self.direct_message(to, text)
+            }
+        }
+    }
+
+    fn direct_message(&mut self, to: String, text: String) -> Option<Request> {
+        let Some(room) = self.command_room() else {
+            self.notice(LineKind::Error, "Join a room first with /join <room>");
+            return None;
+        };
+        Some(Request::DirectMessage { room, to, text })
+    }
+
+    /// Handle a key press in the interactive client
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Request> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') | KeyCode::Char('d') if ctrl => return Some(Request::Quit),
+            KeyCode::Char('n') if ctrl => self.select_next(true),
+            KeyCode::Char('p') if ctrl => self.select_next(false),
+            KeyCode::Char(digit @ '1'..='9') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.select(digit as usize - '1' as usize);
+            }
+            KeyCode::Tab => self.select_next(true),
+            KeyCode::BackTab => self.select_next(false),
+            KeyCode::Enter => {
+                let input = std::mem::take(&mut self.input);
+                self.cursor = 0;
+                self.history_index = None;
+                return self.submit(&input);
+            }
+            KeyCode::Char(c) => {
+                let at = self.byte_offset(self.cursor);
+                self.input.insert(at, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                let at = self.byte_offset(self.cursor);
+                self.input.remove(at);
+            }
+            KeyCode::Delete if self.cursor < self.input.chars().count() => {
+                let at = self.byte_offset(self.cursor);
+                self.input.remove(at);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.input.chars().count()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.input.chars().count(),
+            KeyCode::Up => self.recall(true),
+            KeyCode::Down => self.recall(false),
+            KeyCode::PageUp => self.scroll(10, true),
+            KeyCode::PageDown => self.scroll(10, false),
+            _ => {}
+        }
+        None
+    }
+
+    fn byte_offset(&self, chars: usize) -> usize {
+        self.input.char_indices().nth(chars).map_or(self.input.len(), |(at, _)| at)
+    }
+
+    /// Step through the input history, keeping the unsent line as a draft
+    fn recall(&mut self, older: bool) {
+        let index = match (self.history_index, older) {
+            (None, true) if !self.history.is_empty() => {
+                self.draft = std::mem::take(&mut self.input);
+                self.history.len() - 1
+            }
+            (Some(index), true) => index.saturating_sub(1),
+            (Some(index), false) if index + 1 < self.history.len() => index + 1,
+            (Some(_), false) => {
+                self.history_index = None;
+                self.input = std::mem::take(&mut self.draft);
+                self.cursor = self.input.chars().count();
+                return;
+            }
+            _ => return,
+        };
+        self.history_index = Some(index);
+        self.input = self.history[index].clone();
+        self.cursor = self.input.chars().count();
+    }
+
+    fn scroll(&mut self, lines: usize, up: bool) {
+        let Some(buffer) = self.buffers.get_mut(self.active) else { return };
+        buffer.scroll = if up {
+            (buffer.scroll + lines).min(buffer.lines.len().saturating_sub(1))
+        } else {
+            buffer.scroll.saturating_sub(lines)
+        };
+    }
+}
+
+fn direct_message_key(message: &DirectMessage) -> String {
+    format!("dm:{}:{}:{}:{}", message.timestamp, message.from_user_id, message.to_user_id, message.message)
+}
+
+fn now_ms() -> u64 {
+    (OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000) as u64
+}
+
+/// `HH:MM:SS` in UTC
+pub fn format_time(timestamp_ms: u64) -> String {
+    OffsetDateTime::from_unix_timestamp_nanos(i128::from(timestamp_ms) * 1_000_000)
+        .ok()
+        .and_then(|time| time.format(format_description!("[hour]:[minute]:[second]")).ok())
+        .unwrap_or_default()
+}
@@ -0,0 +1,216 @@
+//! Terminal chat client: an interactive UI with one buffer per room and direct
+//! conversation, and a line-based `--plain` mode for scripts
+//!
+//! Every open room has its own [`ChatClient`] connection, so messages keep arriving
+//! in rooms that are not shown.
+
+pub mod app;
+mod ui;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crossterm::event::{Event, EventStream, KeyEventKind};
+use futures::stream::{BoxStream, SelectAll};
+use futures::StreamExt;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use uuid::Uuid;
+
+use crate::modules::auth::utils::jwt::Claims;
+
+use crate::modules::chat::client::{ChatClient, ChatEvent, ChatSender, ClientError, Credentials};
+use crate::modules::chat::server::display_name;
+use app::{format_time, App, ChatLine, LineKind, Request};
+
+/// How long `--plain` waits for queued messages to go out once its input ends
+const PLAIN_FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub struct ClientOptions {
+    pub server_url: String,
+    pub credentials: Credentials,
+    /// Rooms to open on start; the last one is shown first
+    pub rooms: Vec<String>,
+}
+
+/// The connections of all open rooms
+struct Connections {
+    server_url: String,
+    credentials: Credentials,
+    senders: HashMap<String, ChatSender>,
+    events: SelectAll<BoxStream<'static, (String, ChatEvent)>>,
+}
+
+impl Connections {
+    fn new(options: &ClientOptions) -> Self {
+        Connections {
+            server_url: options.server_url.clone(),
+            credentials: options.credentials.clone(),
+            senders: HashMap::new(),
+            events: SelectAll::new(),
+        }
+    }
+
+    async fn open(&mut self, room: &str) -> Result<(), ClientError> {
+        let client = ChatClient::builder(self.server_url.clone())
+            .credentials(self.credentials.clone())
+            .room(room)
+            .connect()
+            .await?;
+        self.senders.insert(room.to_string(), client.sender());
+        let room = room.to_string();
+        self.events.push(client.map(move |event| (room.clone(), event)).boxed());
+        Ok(())
+    }
+
+    async fn next_event(&mut self) -> Option<(String, ChatEvent)> {
+        if self.events.is_empty() {
+            return futures::future::pending().await;
+        }
+        self.events.next().await
+    }
+
+    /// Carry out a request from the app, returning false when the client should exit
+    async fn handle(&mut self, app: &mut App, request: Request) -> bool {
+        let result = match request {
+            Request::Quit => return false,
+            Request::Open(room) => match self.open(&room).await {
+                Ok(()) => {
+                    app.open_room(&room);
+                    Ok(())
+                }
+                Err(e) => Err(format!("Could not join #{}: {}", room, e)),
+            },
+            Request::Close(room) => {
+                if let Some(sender) = self.senders.remove(&room) {
+                    let _ = sender.close();
+                }
+                Ok(())
+            }
+            Request::Message { room, text } => self.send(&room, |sender| sender.send_message(text)),
+            Request::Command { room, command } => self.send(&room, |sender| sender.send_command(&command)),
+            Request::DirectMessage { room, to, text } => {
+                app.expect_direct_message(&to, &text);
+                self.send(&room, |sender| sender.direct_message(&to, &text))
+            }
+        };
+        if let Err(e) = result {
+            app.notice(LineKind::Error, e);
+        }
+        true
+    }
+
+    fn send(&self, room: &str, send: impl FnOnce(&ChatSender) -> Result<(), ClientError>) -> Result<(), String> {
+        let sender = self.senders.get(room).ok_or_else(|| format!("Not connected to #{}", room))?;
+        send(sender).map_err(|e| e.to_string())
+    }
+
+    fn on_event(&mut self, app: &mut App, room: &str, event: ChatEvent) -> Option<(String, ChatLine)> {
+        if matches!(event, ChatEvent::Connected { .. }) {
+            // Fill the member list, which is kept up to date from join and leave notices
+            if let Some(sender) = self.senders.get(room) {
+                if sender.send_command("/who").is_ok() {
+                    app.expect_who(room);
+                }
+            }
+        }
+        app.handle_event(room, event)
+    }
+
+    async fn open_initial_rooms(&mut self, app: &mut App, rooms: &[String]) -> Result<(), ClientError> {
+        for room in rooms {
+            self.open(room).await?;
+            app.open_room(room);
+        }
+        Ok(())
+    }
+
+    /// Close every connection and wait until queued messages are sent
+    async fn close_all(mut self) {
+        for sender in self.senders.values() {
+            let _ = sender.close();
+        }
+        let _ = tokio::time::timeout(PLAIN_FLUSH_TIMEOUT, async { while self.events.next().await.is_some() {} }).await;
+    }
+}
+
+/// Run the interactive client until the user quits
+pub async fn run(options: ClientOptions, username: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = App::new(username);
+    let mut connections = Connections::new(&options);
+    connections.open_initial_rooms(&mut app, &options.rooms).await?;
+
+    let mut terminal = ratatui::init();
+    let mut keys = EventStream::new();
+    let result = loop {
+        if let Err(e) = terminal.draw(|frame| ui::draw(frame, &app)) {
+            break Err(e.into());
+        }
+        tokio::select! {
+            input = keys.next() => match input {
+                Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                    if let Some(request) = app.handle_key(key) {
+                        if !connections.handle(&mut app, request).await {
+                            break Ok(());
+                        }
+                    }
+                    app.notices.clear();
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => break Err(e.into()),
+                None => break Ok(()),
+            },
+            Some((room, event)) = connections.next_event() => {
+                connections.on_event(&mut app, &room, event);
+            }
+        }
+    };
+    ratatui::restore();
+    connections.close_all().await;
+    result
+}
+
+/// Run the line-based client: each input line is handled like the interactive input,
+/// and every event is printed as `HH:MM:SS #room text` until `/quit` or the input ends
+pub async fn run_plain(options: ClientOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = App::new(None);
+    let mut connections = Connections::new(&options);
+    connections.open_initial_rooms(&mut app, &options.rooms).await?;
+
+    let print = |(label, line): (String, ChatLine)| {
+        println!("{} {} {}", format_time(line.timestamp), label, line.body());
+    };
+
+    let mut input = BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        tokio::select! {
+            line = input.next_line() => {
+                let Some(line) = line? else { break };
+                let keep_running = match app.submit(&line) {
+                    Some(request) => connections.handle(&mut app, request).await,
+                    None => true,
+                };
+                app.notices.drain(..).for_each(print);
+                if !keep_running {
+                    break;
+                }
+            }
+            Some((room, event)) = connections.next_event() => {
+                if let Some(line) = connections.on_event(&mut app, &room, event) {
+                    print(line);
+                }
+            }
+        }
+    }
+    connections.close_all().await;
+    Ok(())
+}
+
+/// Display name of the user an access token belongs to, read without verifying the
+/// token since only the server can
+pub fn token_username(token: &str) -> Option<String> {
+    let mut validation = jsonwebtoken::Validation::default();
+    validation.insecure_disable_signature_validation();
+    validation.validate_exp = false;
+    let claims = jsonwebtoken::decode::<Claims>(token, &jsonwebtoken::DecodingKey::from_secret(&[]), &validation).ok()?;
+    Uuid::parse_str(&claims.claims.sub).ok().map(display_name)
+}
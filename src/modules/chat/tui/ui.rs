@@ -0,0 +1,156 @@
+//! Drawing of the interactive terminal client
+
+use ratatui::layout::{Constraint, Layout, Position, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Wrap};
+use ratatui::Frame;
+
+use super::app::{format_time, App, Buffer, BufferKind, ChatLine, LineKind};
+
+const SIDEBAR_WIDTH: u16 = 22;
+const AUTHOR_COLORS: [Color; 6] = [Color::Cyan, Color::Green, Color::Yellow, Color::Blue, Color::Magenta, Color::LightRed];
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [main, input] = Layout::vertical([Constraint::Min(3), Constraint::Length(3)]).areas(frame.area());
+    let [buffers, scrollback, members] = Layout::horizontal([
+        Constraint::Length(SIDEBAR_WIDTH),
+        Constraint::Min(20),
+        Constraint::Length(SIDEBAR_WIDTH),
+    ])
+    .areas(main);
+
+    draw_buffer_list(frame, app, buffers);
+    match app.active_buffer() {
+        Some(buffer) => {
+            draw_scrollback(frame, buffer, scrollback);
+            draw_members(frame, buffer, members);
+        }
+        None => {
+            let hint = Paragraph::new("Not in any room. Type /join <room> or /quit.")
+                .block(Block::default().borders(Borders::ALL));
+            frame.render_widget(hint, scrollback.union(members));
+        }
+    }
+    draw_input(frame, app, input);
+}
+
+fn draw_buffer_list(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .buffers
+        .iter()
+        .enumerate()
+        .map(|(index, buffer)| {
+            let mut label = format!("{} {}", index + 1, buffer.label());
+            if buffer.unread > 0 {
+                label.push_str(&format!(" ({})", buffer.unread));
+            }
+            let mut style = Style::default();
+            if buffer.kind == BufferKind::Room && !buffer.connected {
+                style = style.fg(Color::DarkGray);
+            }
+            if buffer.unread > 0 {
+                style = style.add_modifier(Modifier::BOLD);
+            }
+            if buffer.highlighted {
+                style = style.fg(Color::Yellow);
+            }
+            if index == app.active {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            ListItem::new(label).style(style)
+        })
+        .collect();
+    frame.render_widget(List::new(items).block(Block::default().borders(Borders::ALL).title("Rooms")), area);
+}
+
+fn draw_scrollback(frame: &mut Frame, buffer: &Buffer, area: Rect) {
+    let mut title = buffer.label();
+    if let Some(topic) = &buffer.topic {
+        title.push_str(&format!(" - {}", topic));
+    }
+    if buffer.pinned > 0 {
+        title.push_str(&format!(" [{} pinned]", buffer.pinned));
+    }
+    if buffer.kind == BufferKind::Room && !buffer.connected {
+        title.push_str(" [disconnected]");
+    }
+    if buffer.scroll > 0 {
+        title.push_str(&format!(" [scrolled up {}]", buffer.scroll));
+    }
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let inner = block.inner(area);
+
+    // Take lines from the bottom, skipping the scrolled-up ones, until the pane is full
+    let end = buffer.lines.len().saturating_sub(buffer.scroll);
+    let width = usize::from(inner.width.max(1));
+    let mut height = 0;
+    let mut lines = Vec::new();
+    for line in buffer.lines.range(..end).rev() {
+        let line = render_line(line);
+        height += line.width().max(1).div_ceil(width);
+        lines.push(line);
+        if height >= usize::from(inner.height) {
+            break;
+        }
+    }
+    lines.reverse();
+    let overflow = height.saturating_sub(usize::from(inner.height)) as u16;
+
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false }).scroll((overflow, 0));
+    frame.render_widget(paragraph, area);
+}
+
+fn render_line(line: &ChatLine) -> Line<'static> {
+    let mut spans = vec![Span::styled(format!("{} ", format_time(line.timestamp)), Style::default().fg(Color::DarkGray))];
+    match (line.kind, &line.author) {
+        (LineKind::Action, Some(author)) => {
+            spans.push(Span::styled(format!("* {} {}", author, line.text), Style::default().italic().fg(author_color(author))));
+        }
+        (LineKind::Message | LineKind::Bot | LineKind::Direct, Some(author)) => {
+            let mut author_style = Style::default().fg(author_color(author)).bold();
+            if line.kind == LineKind::Bot {
+                author_style = author_style.italic();
+            }
+            spans.push(Span::styled(format!("<{}> ", author), author_style));
+            let text_style = match line.kind {
+                LineKind::Direct => Style::default().fg(Color::Magenta),
+                _ => Style::default(),
+            };
+            spans.push(Span::styled(line.text.clone(), text_style));
+        }
+        (LineKind::Announcement, _) => spans.push(Span::styled(line.body(), Style::default().fg(Color::Yellow).bold())),
+        (LineKind::Error, _) => spans.push(Span::styled(line.body(), Style::default().fg(Color::Red))),
+        _ => spans.push(Span::styled(line.body(), Style::default().fg(Color::Gray).italic())),
+    }
+    Line::from(spans)
+}
+
+fn author_color(author: &str) -> Color {
+    let hash = author.bytes().fold(0usize, |hash, byte| hash.wrapping_mul(31).wrapping_add(usize::from(byte)));
+    AUTHOR_COLORS[hash % AUTHOR_COLORS.len()]
+}
+
+fn draw_members(frame: &mut Frame, buffer: &Buffer, area: Rect) {
+    let (title, items): (String, Vec<ListItem>) = match buffer.kind {
+        BufferKind::Room => (
+            format!("Members ({})", buffer.members.len()),
+            buffer.members.iter().map(|name| ListItem::new(name.clone()).fg(author_color(name))).collect(),
+        ),
+        BufferKind::Direct => ("Direct".to_string(), vec![ListItem::new(buffer.name.clone())]),
+    };
+    frame.render_widget(List::new(items).block(Block::default().borders(Borders::ALL).title(title)), area);
+}
+
+fn draw_input(frame: &mut Frame, app: &App, area: Rect) {
+    let title = app.username.clone().unwrap_or_else(|| "Message".to_string());
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let inner = block.inner(area);
+
+    // Scroll long input horizontally so the cursor stays visible
+    let width = usize::from(inner.width.max(1));
+    let offset = (app.cursor + 1).saturating_sub(width);
+    let visible: String = app.input.chars().skip(offset).take(width).collect();
+    frame.render_widget(Paragraph::new(visible).block(block), area);
+    frame.set_cursor_position(Position::new(inner.x + (app.cursor - offset) as u16, inner.y));
+}
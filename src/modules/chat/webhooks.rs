@@ -0,0 +1,173 @@
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::modules::chat::server::ChatMessage;
+
+/// Event type, e.g. `message`
+pub const EVENT_HEADER: &str = "X-Chat-Event";
+/// ID of the delivery, stable across retries
+pub const DELIVERY_HEADER: &str = "X-Chat-Delivery";
+/// Unix time in seconds at which the request was signed
+pub const TIMESTAMP_HEADER: &str = "X-Chat-Timestamp";
+/// `sha256=<hex>` HMAC of `"{timestamp}.{body}"` keyed with the webhook secret
+pub const SIGNATURE_HEADER: &str = "X-Chat-Signature";
+
+/// Longest wait between two delivery attempts
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookEventType {
+    Message,
+    Join,
+    Leave,
+}
+
+impl WebhookEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventType::Message => "message",
+            WebhookEventType::Join => "join",
+            WebhookEventType::Leave => "leave",
+        }
+    }
+}
+
+/// JSON body POSTed to a room's webhooks
+#[derive(Debug, Serialize)]
+pub struct WebhookEvent {
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub event_type: WebhookEventType,
+    pub room_name: String,
+    pub user_id: String,
+    pub username: String,
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<ChatMessage>,
+}
+
+/// Signature sent in the `X-Chat-Signature` header
+pub fn sign_payload(secret: &str, timestamp: u64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Delay before retrying after the given number of failed attempts, doubling each time
+pub fn retry_delay(attempts: u32, base: Duration) -> Duration {
+    let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+    base.saturating_mul(factor).min(MAX_RETRY_DELAY)
+}
+
+/// Whether an address is reachable on the public internet, as opposed to loopback,
+/// private, link-local and other special-purpose ranges
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || first == 0
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (first == 100 && second & 0xc0 == 64))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_address(IpAddr::V4(ip)),
+            None => {
+                let first = ip.segments()[0];
+                // Unique local fc00::/7 and link-local fe80::/10
+                !(ip.is_loopback() || ip.is_unspecified() || ip.is_multicast() || first & 0xfe00 == 0xfc00 || first & 0xffc0 == 0xfe80)
+            }
+        },
+    }
+}
+
+/// Reject webhook URLs whose host is a literal non-public address; host names are
+/// checked by `delivery_client` when they are resolved
+pub fn check_destination(url: &str, allow_private: bool) -> Result<(), String> {
+    let url = url::Url::parse(url).map_err(|_| "Invalid webhook URL".to_string())?;
+    let ip = match url.host() {
+        Some(url::Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(url::Host::Ipv6(ip)) => IpAddr::V6(ip),
+        _ => return Ok(()),
+    };
+    if allow_private || is_public_address(ip) {
+        Ok(())
+    } else {
+        Err(format!("Webhook address {} is not public", ip))
+    }
+}
+
+/// HTTP client for one delivery to `url`. Redirects are never followed. Unless
+/// `allow_private` is set, the host must resolve to public addresses only, and the
+/// client connects to the addresses checked here, so a second lookup cannot swap them.
+pub async fn delivery_client(url: &str, timeout: Duration, allow_private: bool) -> Result<reqwest::Client, String> {
+    let builder = reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(reqwest::redirect::Policy::none());
+    check_destination(url, allow_private)?;
+
+    let parsed = url::Url::parse(url).map_err(|_| "Invalid webhook URL".to_string())?;
+    let builder = match parsed.host() {
+        Some(url::Host::Domain(host)) if !allow_private => {
+            let port = parsed.port_or_known_default().unwrap_or(80);
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+                .await
+                .map_err(|e| format!("Failed to resolve {}: {}", host, e))?
+                .collect();
+            if let Some(addr) = addrs.iter().find(|addr| !is_public_address(addr.ip())) {
+                return Err(format!("Webhook host {} resolves to {}, which is not public", host, addr.ip()));
+            }
+            builder.resolve_to_addrs(host, &addrs)
+        }
+        _ => builder,
+    };
+    builder.build().map_err(|e| format!("Failed to build webhook HTTP client: {}", e))
+}
+
+/// POST a signed event, returning the response status on success
+pub async fn post_event(
+    client: &reqwest::Client,
+    url: &str,
+    secret: &str,
+    delivery_id: Uuid,
+    event_type: &str,
+    body: &str,
+) -> Result<u16, (Option<u16>, String)> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let response = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, event_type)
+        .header(DELIVERY_HEADER, delivery_id.to_string())
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, sign_payload(secret, timestamp, body))
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|e| (None, e.to_string()))?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(status.as_u16())
+    } else {
+        Err((Some(status.as_u16()), format!("Webhook responded with {}", status)))
+    }
+}
@@ -54,7 +54,7 @@ pub fn scan_directory(dir_path: &Path, config: ScannerConfig) -> Result<ScanResu
     
     // Split files into chunks for threading
     let chunks: Vec<Vec<FilePath>> = files
-        .chunks((files.len() + config.num_threads - 1) / config.num_threads)
+        .chunks(files.len().div_ceil(config.num_threads))
         .map(|chunk| chunk.to_vec())
         .collect();
     
@@ -119,13 +119,13 @@ fn process_file_chunk(chunk: Vec<FilePath>, index: Arc<Mutex<FileIndex>>) {
     for file_path in chunk {
         if let Ok(hash) = calculate_file_hash(&file_path) {
             let mut index = index.lock().unwrap();
-            index.entry(hash).or_insert_with(Vec::new).push(file_path);
+            index.entry(hash).or_default().push(file_path);
         }
     }
 }
 
 /// Calculate SHA256 hash of a file
-fn calculate_file_hash(file_path: &Path) -> Result<FileHash, std::io::Error> {
+pub fn calculate_file_hash(file_path: &Path) -> Result<FileHash, std::io::Error> {
     let mut file = fs::File::open(file_path)?;
     let mut hasher = Sha256::new();
     
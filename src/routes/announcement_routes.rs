@@ -0,0 +1,154 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use sqlx::Pool;
+use sqlx::Postgres;
+use uuid::Uuid;
+
+use crate::config::environment::Environment;
+use crate::modules::auth::utils::jwt::JwtUtil;
+use crate::modules::chat::dto::announcement_dto::{AnnouncementDeliveryResponse, AnnouncementResponse, CreateAnnouncementDto};
+use crate::modules::chat::server::{display_name, ChatState};
+
+/// Longest announcement, in characters
+const MAX_ANNOUNCEMENT_LENGTH: usize = 1000;
+
+/// Configure server-wide announcement routes
+pub fn announcement_routes(chat_state: ChatState) -> Router<Pool<Postgres>> {
+    Router::new()
+        .route("/api/announcements", post(create_announcement))
+        .route("/api/announcements/banner", get(get_banner).delete(clear_banner))
+        .with_state(chat_state)
+}
+
+/// Authenticate the request and require a server admin (`CHAT_ADMIN_USERS`)
+fn require_admin(state: &ChatState, headers: &HeaderMap, env: &Environment) -> Result<Uuid, (StatusCode, String)> {
+    let user_id = JwtUtil::user_id_from_headers(headers, &env.auth)?;
+    if !state.is_admin(user_id) {
+        return Err((StatusCode::FORBIDDEN, "Only admins may manage announcements".to_string()));
+    }
+    Ok(user_id)
+}
+
+/// Announce something to every connected user
+///
+/// The announcement is sent as an `announcement:` frame to every open chat connection,
+/// in any room or none. With `banner` it also replaces the banner, which connections
+/// receive whenever they join a room until it expires or is cleared. Banners are kept
+/// in memory and do not survive a restart. Only server admins (`CHAT_ADMIN_USERS`) may
+/// make announcements.
+#[utoipa::path(
+    post,
+    path = "/api/announcements",
+    request_body = CreateAnnouncementDto,
+    responses(
+        (status = 200, description = "Announcement sent", body = AnnouncementDeliveryResponse),
+        (status = 400, description = "Invalid message or expiry", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not an admin", body = ErrorResponse),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Announcements"
+)]
+pub async fn create_announcement(
+    State(state): State<ChatState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateAnnouncementDto>,
+) -> Result<Json<AnnouncementDeliveryResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = require_admin(&state, &headers, &env)?;
+
+    let message = payload.message.trim();
+    if message.is_empty() || message.chars().count() > MAX_ANNOUNCEMENT_LENGTH {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Invalid message: must be 1 to {} characters", MAX_ANNOUNCEMENT_LENGTH),
+        ));
+    }
+    if payload.expires_in.is_some() && !payload.banner {
+        return Err((StatusCode::BAD_REQUEST, "Invalid expiry: only banners expire".to_string()));
+    }
+    if payload.expires_in == Some(0) {
+        return Err((StatusCode::BAD_REQUEST, "Invalid expiry: must be at least 1 second".to_string()));
+    }
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    let announcement = AnnouncementResponse {
+        id: Uuid::new_v4().to_string(),
+        message: message.to_string(),
+        author: display_name(user_id),
+        timestamp,
+        banner: payload.banner,
+        expires_at: payload
+            .expires_in
+            .map(|secs| timestamp.saturating_add(secs.saturating_mul(1000))),
+    };
+    let delivered = state.announce(announcement.clone());
+    tracing::info!("{} announced to {} connections: {}", announcement.author, delivered, announcement.message);
+
+    Ok(Json(AnnouncementDeliveryResponse { announcement, delivered }))
+}
+
+/// Get the current banner
+#[utoipa::path(
+    get,
+    path = "/api/announcements/banner",
+    responses(
+        (status = 200, description = "Current banner", body = AnnouncementResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 404, description = "No banner is shown", body = ErrorResponse),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Announcements"
+)]
+pub async fn get_banner(
+    State(state): State<ChatState>,
+    headers: HeaderMap,
+) -> Result<Json<AnnouncementResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+
+    state
+        .current_banner()
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, "No banner is shown".to_string()))
+}
+
+/// Clear the banner before it expires
+#[utoipa::path(
+    delete,
+    path = "/api/announcements/banner",
+    responses(
+        (status = 200, description = "Banner cleared"),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not an admin", body = ErrorResponse),
+        (status = 404, description = "No banner is shown", body = ErrorResponse),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Announcements"
+)]
+pub async fn clear_banner(
+    State(state): State<ChatState>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    require_admin(&state, &headers, &env)?;
+
+    if !state.clear_banner() {
+        return Err((StatusCode::NOT_FOUND, "No banner is shown".to_string()));
+    }
+    Ok(Json(json!({ "success": true })))
+}
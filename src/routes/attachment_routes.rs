@@ -0,0 +1,219 @@
+use axum::{
+    body::StreamBody,
+    extract::{DefaultBodyLimit, Multipart, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use sqlx::Pool;
+use sqlx::Postgres;
+use tokio::io::AsyncWriteExt;
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+
+use crate::config::environment::Environment;
+use crate::modules::auth::utils::jwt::JwtUtil;
+use crate::modules::chat::service::AttachmentService;
+
+/// Configure attachment routes
+pub fn attachment_routes() -> Router<Pool<Postgres>> {
+    let env = Environment::from_env();
+
+    Router::new()
+        .route(
+            "/api/rooms/:room/attachments",
+            post(upload_attachment).layer(DefaultBodyLimit::max(env.chat.max_attachment_bytes + 64 * 1024)),
+        )
+        .route("/api/attachments/:id", get(download_attachment))
+}
+
+/// Upload a file into a room
+///
+/// Accepts a `multipart/form-data` body with a single `file` field. Blobs are stored
+/// once per SHA-256 hash, so sharing the same file twice does not duplicate it on disk.
+///
+/// The returned attachment `id` can be referenced from a chat message by sending a
+/// JSON frame over the WebSocket connection:
+///
+/// ```json
+/// {"message": "Here is the report", "attachment_id": "attachment-uuid"}
+/// ```
+///
+/// Only members of the room (users who have joined it) may upload into it.
+#[utoipa::path(
+    post,
+    path = "/api/rooms/{room}/attachments",
+    request_body(content = UploadAttachmentForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Attachment uploaded successfully", body = AttachmentResponse),
+        (status = 400, description = "Missing or invalid file field", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a member of the room", body = ErrorResponse),
+        (status = 413, description = "File exceeds the configured size limit", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room the file is shared in"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Chat"
+)]
+pub async fn upload_attachment(
+    State(pool): State<Pool<Postgres>>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+    mut multipart: Multipart,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let max_bytes = env.chat.max_attachment_bytes;
+    let attachment_service = AttachmentService::new(pool, env);
+
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let file_name = field.file_name().unwrap_or("attachment").to_string();
+        let content_type = field
+            .content_type()
+            .unwrap_or("application/octet-stream")
+            .to_string();
+
+        let staged_path = attachment_service
+            .staging_path()
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        let mut staged_file = tokio::fs::File::create(&staged_path)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+        // Stream the upload to disk instead of buffering it in memory
+        let mut written = 0;
+        loop {
+            let chunk = match field.chunk().await {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(e) => {
+                    let _ = tokio::fs::remove_file(&staged_path).await;
+                    return Err((StatusCode::BAD_REQUEST, e.to_string()));
+                }
+            };
+            written += chunk.len();
+            if written > max_bytes {
+                let _ = tokio::fs::remove_file(&staged_path).await;
+                return Err((StatusCode::PAYLOAD_TOO_LARGE, "File exceeds the maximum attachment size".to_string()));
+            }
+            staged_file
+                .write_all(&chunk)
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        }
+        staged_file
+            .flush()
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+        return match attachment_service
+            .save_upload(user_id, &room, &file_name, &content_type, &staged_path)
+            .await
+        {
+            Ok(attachment) => {
+                let response = serde_json::json!({
+                    "success": true,
+                    "attachment": attachment.to_response()
+                });
+                Ok(Json(response))
+            }
+            Err(e) => {
+                if e.to_string().contains("Not a member") {
+                    Err((StatusCode::FORBIDDEN, e.to_string()))
+                } else {
+                    Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+                }
+            }
+        };
+    }
+
+    Err((StatusCode::BAD_REQUEST, "Missing file field".to_string()))
+}
+
+/// Download an attachment
+///
+/// Streams the stored file back with its original name and content type, always as a
+/// download and with scripts disabled, so uploaded HTML cannot run in the browser.
+/// Only members of the room where the file was shared may download it.
+#[utoipa::path(
+    get,
+    path = "/api/attachments/{id}",
+    responses(
+        (status = 200, description = "Attachment content"),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a member of the room the file was shared in", body = ErrorResponse),
+        (status = 404, description = "Attachment not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("id" = String, Path, description = "Attachment ID"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Chat"
+)]
+pub async fn download_attachment(
+    State(pool): State<Pool<Postgres>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let attachment_service = AttachmentService::new(pool, env);
+
+    let (attachment, path) = attachment_service
+        .open_for_download(user_id, id)
+        .await
+        .map_err(|e| {
+            let message = e.to_string();
+            if message.contains("not found") {
+                (StatusCode::NOT_FOUND, message)
+            } else if message.contains("Not a member") {
+                (StatusCode::FORBIDDEN, message)
+            } else {
+                (StatusCode::INTERNAL_SERVER_ERROR, message)
+            }
+        })?;
+
+    let file = tokio::fs::File::open(&path)
+        .await
+        .map_err(|_| (StatusCode::NOT_FOUND, "Attachment content is missing".to_string()))?;
+
+    let response_headers = download_headers(&attachment.file_name, &attachment.content_type);
+    Ok((response_headers, StreamBody::new(ReaderStream::new(file))))
+}
+
+/// Response headers of an attachment download.
+///
+/// The content type is whatever the uploader claimed, so browsers are told to save the
+/// file instead of rendering it, not to guess another type, and to run no script in it
+/// should it be opened anyway. Otherwise an uploaded HTML or SVG file would run in the
+/// API's origin.
+pub fn download_headers(file_name: &str, content_type: &str) -> [(header::HeaderName, String); 4] {
+    let disposition = format!(
+        "attachment; filename=\"{}\"",
+        file_name.replace(['"', '\\', '\r', '\n'], "_")
+    );
+    [
+        (header::CONTENT_TYPE, content_type.to_string()),
+        (header::CONTENT_DISPOSITION, disposition),
+        (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        (header::CONTENT_SECURITY_POLICY, "default-src 'none'; sandbox".to_string()),
+    ]
+}
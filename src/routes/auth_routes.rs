@@ -24,14 +24,74 @@ use utoipa::OpenApi;
         refresh_token,
         change_password,
         chat_websocket,
+        crate::routes::chat_routes::issue_chat_ticket,
+        crate::routes::chat_routes::revoke_tokens,
         crate::routes::file_routes::scan_files,
+        crate::routes::attachment_routes::upload_attachment,
+        crate::routes::attachment_routes::download_attachment,
+        crate::routes::search_routes::search_messages,
+        crate::routes::room_routes::get_room_topic,
+        crate::routes::room_routes::set_room_topic,
+        crate::routes::room_routes::get_pinned_messages,
+        crate::routes::room_routes::pin_message,
+        crate::routes::room_routes::unpin_message,
+        crate::routes::room_routes::set_member_role,
+        crate::routes::room_routes::export_room_transcript,
+        crate::routes::room_routes::get_retention_policy,
+        crate::routes::room_routes::set_retention_policy,
+        crate::routes::notification_routes::list_notifications,
+        crate::routes::notification_routes::mark_notification_read,
+        crate::routes::notification_routes::mark_all_notifications_read,
+        crate::routes::webhook_routes::create_webhook,
+        crate::routes::webhook_routes::list_webhooks,
+        crate::routes::webhook_routes::delete_webhook,
+        crate::routes::webhook_routes::list_webhook_deliveries,
+        crate::routes::incoming_webhook_routes::create_incoming_webhook,
+        crate::routes::incoming_webhook_routes::list_incoming_webhooks,
+        crate::routes::incoming_webhook_routes::revoke_incoming_webhook,
+        crate::routes::incoming_webhook_routes::post_incoming_message,
+        crate::routes::moderation_routes::create_moderation_rule,
+        crate::routes::moderation_routes::list_moderation_rules,
+        crate::routes::moderation_routes::delete_moderation_rule,
+        crate::routes::moderation_routes::list_moderation_queue,
+        crate::routes::moderation_routes::approve_flagged_message,
+        crate::routes::moderation_routes::reject_flagged_message,
+        crate::routes::poll_routes::create_poll,
+        crate::routes::poll_routes::list_polls,
+        crate::routes::poll_routes::get_poll,
+        crate::routes::poll_routes::vote_poll,
+        crate::routes::poll_routes::close_poll,
+        crate::routes::scheduled_message_routes::schedule_message,
+        crate::routes::scheduled_message_routes::list_scheduled_messages,
+        crate::routes::scheduled_message_routes::get_scheduled_message,
+        crate::routes::scheduled_message_routes::update_scheduled_message,
+        crate::routes::scheduled_message_routes::cancel_scheduled_message,
+        crate::routes::room_routes::set_room_privacy,
+        crate::routes::invite_routes::create_invite,
+        crate::routes::invite_routes::list_invites,
+        crate::routes::invite_routes::revoke_invite,
+        crate::routes::invite_routes::accept_invite,
+        crate::routes::block_routes::list_blocked_users,
+        crate::routes::block_routes::block_user,
+        crate::routes::block_routes::unblock_user,
+        crate::routes::announcement_routes::create_announcement,
+        crate::routes::announcement_routes::get_banner,
+        crate::routes::announcement_routes::clear_banner,
     ),
     components(
-        schemas(RegisterDto, LoginDto, TokenResponse, RefreshTokenDto, ChangePasswordDto, UserResponse, ErrorResponse, crate::routes::file_routes::ScanRequest, crate::routes::file_routes::ScanResponse)
+        schemas(RegisterDto, LoginDto, TokenResponse, RefreshTokenDto, ChangePasswordDto, UserResponse, ErrorResponse, crate::routes::file_routes::ScanRequest, crate::routes::file_routes::ScanResponse, crate::modules::chat::dto::attachment_dto::AttachmentResponse, crate::modules::chat::dto::attachment_dto::UploadAttachmentForm, crate::modules::chat::dto::message_dto::MessageSearchResult, crate::modules::chat::dto::message_dto::MessageSearchResponse, crate::modules::chat::dto::room_dto::RoomTopicResponse, crate::modules::chat::dto::room_dto::SetTopicDto, crate::modules::chat::dto::room_dto::PinnedMessageResponse, crate::modules::chat::dto::room_dto::PinnedMessagesResponse, crate::modules::chat::dto::room_dto::PinMessageDto, crate::modules::chat::dto::room_dto::SetMemberRoleDto, crate::modules::chat::dto::notification_dto::NotificationResponse, crate::modules::chat::dto::notification_dto::NotificationListResponse, crate::modules::chat::dto::webhook_dto::CreateWebhookDto, crate::modules::chat::dto::webhook_dto::WebhookResponse, crate::modules::chat::dto::webhook_dto::WebhookDeliveryResponse, crate::modules::chat::dto::webhook_dto::CreateIncomingWebhookDto, crate::modules::chat::dto::webhook_dto::IncomingWebhookResponse, crate::modules::chat::dto::webhook_dto::IncomingMessageDto, crate::modules::chat::dto::moderation_dto::CreateModerationRuleDto, crate::modules::chat::dto::moderation_dto::ModerationRuleResponse, crate::modules::chat::dto::moderation_dto::FlaggedMessageResponse, crate::modules::chat::dto::ticket_dto::ChatTicketResponse, crate::modules::chat::dto::retention_dto::RetentionPolicyResponse, crate::modules::chat::dto::retention_dto::SetRetentionPolicyDto, crate::modules::chat::dto::poll_dto::CreatePollDto, crate::modules::chat::dto::poll_dto::VotePollDto, crate::modules::chat::dto::poll_dto::PollOptionResponse, crate::modules::chat::dto::poll_dto::PollResponse, crate::modules::chat::dto::poll_dto::PollListResponse, crate::modules::chat::dto::scheduled_message_dto::ScheduleMessageDto, crate::modules::chat::dto::scheduled_message_dto::UpdateScheduledMessageDto, crate::modules::chat::dto::scheduled_message_dto::ScheduledMessageResponse, crate::modules::chat::dto::scheduled_message_dto::ScheduledMessageListResponse, crate::modules::chat::dto::room_dto::SetRoomPrivacyDto, crate::modules::chat::dto::room_dto::RoomPrivacyResponse, crate::modules::chat::dto::invite_dto::CreateInviteDto, crate::modules::chat::dto::invite_dto::InviteResponse, crate::modules::chat::dto::invite_dto::InviteListResponse, crate::modules::chat::dto::invite_dto::AcceptInviteResponse, crate::modules::chat::dto::block_dto::BlockedUserResponse, crate::modules::chat::dto::block_dto::BlockListResponse, crate::modules::chat::dto::announcement_dto::AnnouncementResponse, crate::modules::chat::dto::announcement_dto::CreateAnnouncementDto, crate::modules::chat::dto::announcement_dto::AnnouncementDeliveryResponse)
     ),
     tags(
         (name = "Authentication", description = "User authentication and management endpoints"),
         (name = "Chat", description = "Real-time chat endpoints"),
+        (name = "Notifications", description = "Mention notification inbox endpoints"),
+        (name = "Webhooks", description = "Outgoing and incoming room webhook endpoints"),
+        (name = "Moderation", description = "Room moderation rule and review queue endpoints"),
+        (name = "Polls", description = "Room poll and voting endpoints"),
+        (name = "Scheduled Messages", description = "Endpoints for messages posted to a room later"),
+        (name = "Invites", description = "Room invite endpoints"),
+        (name = "Blocking", description = "Endpoints for blocking other users"),
+        (name = "Announcements", description = "Server-wide announcement endpoints for admins"),
         (name = "File Management", description = "File indexing and duplicate detection endpoints")
     )
 )]
@@ -263,12 +323,13 @@ pub async fn change_password(
 /// 
 /// Authentication is required using a valid JWT token.
 /// 
-/// You can provide the token in either:
-/// 1. Query parameter: `?token=YOUR_JWT_TOKEN`
+/// You can authenticate with either:
+/// 1. A single-use ticket from `POST /api/chat/ticket`: `?ticket=YOUR_TICKET` (recommended)
 /// 2. Authorization header: `Authorization: Bearer YOUR_JWT_TOKEN`
+/// 3. Query parameter: `?token=YOUR_JWT_TOKEN` (deprecated, the token ends up in access logs)
 /// 
 /// You can also specify a room to join:
-/// `?token=YOUR_JWT_TOKEN&room=room_name`
+/// `?ticket=YOUR_TICKET&room=room_name`
 /// 
 /// If no room is specified, you will join the default "general" room.
 /// 
@@ -278,7 +339,7 @@ pub async fn change_password(
 /// 
 /// ## Sending Messages
 /// Simply send text messages through the WebSocket connection:
-/// ```
+/// ```text
 /// Hello everyone!
 /// ```
 /// 
@@ -310,7 +371,12 @@ pub async fn change_password(
 /// JavaScript example:
 /// ```javascript
 /// const token = "YOUR_JWT_TOKEN";
-/// const ws = new WebSocket(`ws://localhost:3005/ws?token=${token}`);
+/// const response = await fetch("http://localhost:3005/api/chat/ticket", {
+///   method: "POST",
+///   headers: { Authorization: `Bearer ${token}` },
+/// });
+/// const { ticket } = await response.json();
+/// const ws = new WebSocket(`ws://localhost:3005/ws?ticket=${ticket}`);
 /// 
 /// ws.onopen = () => {
 ///   console.log("Connected to chat server");
@@ -330,7 +396,8 @@ pub async fn change_password(
         (status = 404, description = "Not Found - WebSocket endpoint not found"),
     ),
     params(
-        ("token" = String, Query, description = "JWT token for authentication (optional if provided in Authorization header)"),
+        ("ticket" = String, Query, description = "Single-use ticket from POST /api/chat/ticket (optional if a token is provided)"),
+        ("token" = String, Query, description = "Deprecated: JWT token for authentication (optional if a ticket or Authorization header is provided)"),
         ("room" = String, Query, description = "Room name to join (optional, defaults to 'general')"),
     ),
     security(
@@ -0,0 +1,132 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, put},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use sqlx::Pool;
+use sqlx::Postgres;
+use uuid::Uuid;
+
+use crate::config::environment::Environment;
+use crate::modules::auth::utils::jwt::JwtUtil;
+use crate::modules::chat::dto::block_dto::{BlockListResponse, BlockedUserResponse};
+use crate::modules::chat::server::ChatState;
+use crate::modules::chat::service::BlockService;
+
+/// Configure user blocking routes
+pub fn block_routes(chat_state: ChatState) -> Router<Pool<Postgres>> {
+    Router::new()
+        .route("/api/blocks", get(list_blocked_users))
+        .route("/api/blocks/:user_id", put(block_user).delete(unblock_user))
+        .with_state(chat_state)
+}
+
+/// Map blocking errors to HTTP status codes
+fn block_error(e: Box<dyn std::error::Error>) -> (StatusCode, String) {
+    let message = e.to_string();
+    if message.contains("not found") {
+        (StatusCode::NOT_FOUND, message)
+    } else if message.contains("Invalid") {
+        (StatusCode::BAD_REQUEST, message)
+    } else {
+        (StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+/// List the users you have blocked, most recently blocked first
+#[utoipa::path(
+    get,
+    path = "/api/blocks",
+    responses(
+        (status = 200, description = "Blocked users", body = BlockListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Blocking"
+)]
+pub async fn list_blocked_users(
+    State(state): State<ChatState>,
+    headers: HeaderMap,
+) -> Result<Json<BlockListResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let block_service = BlockService::new(state.db_pool()?);
+
+    let blocked = block_service.list_blocked(user_id).await.map_err(block_error)?;
+    Ok(Json(BlockListResponse { blocked }))
+}
+
+/// Block a user
+///
+/// The user's chat messages are no longer delivered to any of your connections,
+/// including messages replayed when resuming, and their mentions of you no longer
+/// notify you. Direct messages between you are rejected in both directions. The
+/// blocked user is not told. Blocking a user twice has no further effect.
+#[utoipa::path(
+    put,
+    path = "/api/blocks/{user_id}",
+    responses(
+        (status = 200, description = "User blocked", body = BlockedUserResponse),
+        (status = 400, description = "Cannot block yourself", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("user_id" = String, Path, description = "ID of the user to block"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Blocking"
+)]
+pub async fn block_user(
+    State(state): State<ChatState>,
+    Path(blocked_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<BlockedUserResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let block_service = BlockService::new(state.db_pool()?);
+
+    let blocked = block_service.block(user_id, blocked_id).await.map_err(block_error)?;
+    state.set_blocked(user_id, blocked_id, true);
+    Ok(Json(blocked))
+}
+
+/// Unblock a user
+#[utoipa::path(
+    delete,
+    path = "/api/blocks/{user_id}",
+    responses(
+        (status = 200, description = "User unblocked"),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 404, description = "User is not blocked", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("user_id" = String, Path, description = "ID of the blocked user"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Blocking"
+)]
+pub async fn unblock_user(
+    State(state): State<ChatState>,
+    Path(blocked_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let block_service = BlockService::new(state.db_pool()?);
+
+    block_service.unblock(user_id, blocked_id).await.map_err(block_error)?;
+    state.set_blocked(user_id, blocked_id, false);
+    Ok(Json(json!({ "success": true })))
+}
@@ -1,15 +1,100 @@
 use axum::{
-    routing::get,
-    Router,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
 };
+use serde_json::{json, Value};
 use sqlx::Pool;
 use sqlx::Postgres;
 
-use crate::modules::chat::server::{websocket_handler, ChatState};
+use crate::config::environment::Environment;
+use crate::modules::auth::service::AuthService;
+use crate::modules::auth::utils::jwt::JwtUtil;
+use crate::modules::chat::dto::ticket_dto::ChatTicketResponse;
+use crate::modules::chat::server::{websocket_handler, ChatState, TICKET_TTL};
 
 /// Configure chat routes
 pub fn chat_routes(chat_state: ChatState) -> Router<Pool<Postgres>> {
     Router::new()
         .route("/ws", get(websocket_handler))
+        .route("/api/chat/ticket", post(issue_chat_ticket))
+        .route("/auth/revoke", post(revoke_tokens))
         .with_state(chat_state)
 }
+
+/// Exchange an access token for a WebSocket ticket
+///
+/// Browsers cannot set headers on WebSocket requests, and access tokens in the query
+/// string end up in proxy and access logs. Instead, request a ticket with the bearer
+/// token and connect with `/ws?ticket=...`. A ticket is valid for 30 seconds and for a
+/// single connection, which lives no longer than the access token it was issued for.
+#[utoipa::path(
+    post,
+    path = "/api/chat/ticket",
+    responses(
+        (status = 200, description = "Ticket issued", body = ChatTicketResponse),
+        (status = 401, description = "Unauthorized - Invalid, revoked or missing JWT token", body = ErrorResponse),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Chat"
+)]
+pub async fn issue_chat_ticket(
+    State(state): State<ChatState>,
+    headers: HeaderMap,
+) -> Result<Json<ChatTicketResponse>, (StatusCode, String)> {
+    let auth_config = crate::config::env::AuthConfig::from_env();
+    let claims = JwtUtil::claims_from_headers(&headers, &auth_config)?;
+    let user_id = uuid::Uuid::parse_str(&claims.sub)
+        .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid user ID in token".to_string()))?;
+    state
+        .check_not_revoked(user_id, claims.issued_at_ms())
+        .await
+        .map_err(|e| (StatusCode::UNAUTHORIZED, e))?;
+
+    let ticket = state.issue_ticket(user_id, claims.issued_at_ms(), claims.exp as u64);
+    Ok(Json(ChatTicketResponse {
+        ticket,
+        expires_in: TICKET_TTL.as_secs(),
+    }))
+}
+
+/// Revoke all of the caller's tokens
+///
+/// Every access and refresh token issued to the caller so far stops working for chat
+/// connections and token refreshes, and the caller's open chat connections are closed
+/// immediately. Log in again to get new tokens.
+#[utoipa::path(
+    post,
+    path = "/auth/revoke",
+    responses(
+        (status = 200, description = "Tokens revoked"),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Authentication"
+)]
+pub async fn revoke_tokens(
+    State(state): State<ChatState>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let auth_service = AuthService::new(state.db_pool()?, env);
+
+    auth_service
+        .revoke_tokens(user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let disconnected = state.disconnect_user(user_id, "Your tokens have been revoked");
+
+    Ok(Json(json!({
+        "success": true,
+        "disconnected": disconnected,
+    })))
+}
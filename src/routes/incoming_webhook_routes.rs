@@ -0,0 +1,248 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use sqlx::Pool;
+use sqlx::Postgres;
+use uuid::Uuid;
+
+use crate::config::environment::Environment;
+use crate::modules::auth::utils::jwt::JwtUtil;
+use crate::modules::chat::dto::webhook_dto::{CreateIncomingWebhookDto, IncomingMessageDto, IncomingWebhookResponse};
+use crate::modules::chat::moderation::ModerationOutcome;
+use crate::modules::chat::server::{ChatState, MessageSender, OutgoingMessage};
+use crate::modules::chat::service::incoming_webhook_service::MAX_INCOMING_MESSAGE_LENGTH;
+use crate::modules::chat::service::IncomingWebhookService;
+
+/// Configure incoming webhook routes
+pub fn incoming_webhook_routes(chat_state: ChatState) -> Router<Pool<Postgres>> {
+    Router::new()
+        .route(
+            "/api/rooms/:room/incoming-webhooks",
+            get(list_incoming_webhooks).post(create_incoming_webhook),
+        )
+        .route("/api/rooms/:room/incoming-webhooks/:id", delete(revoke_incoming_webhook))
+        .route("/hooks/:token", post(post_incoming_message))
+        .with_state(chat_state)
+}
+
+/// Map incoming webhook service errors to HTTP status codes
+fn incoming_webhook_error(e: Box<dyn std::error::Error>) -> (StatusCode, String) {
+    let message = e.to_string();
+    if message.contains("Not a member") || message.contains("Only the room") {
+        (StatusCode::FORBIDDEN, message)
+    } else if message.contains("not found") {
+        (StatusCode::NOT_FOUND, message)
+    } else if message.contains("Invalid") {
+        (StatusCode::BAD_REQUEST, message)
+    } else {
+        (StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+/// Create an incoming webhook for a room
+///
+/// Only the room owner and server admins may manage incoming webhooks. The response contains the secret
+/// `token` and the `url` to POST messages to; neither is shown again.
+#[utoipa::path(
+    post,
+    path = "/api/rooms/{room}/incoming-webhooks",
+    request_body = CreateIncomingWebhookDto,
+    responses(
+        (status = 200, description = "Incoming webhook created", body = IncomingWebhookResponse),
+        (status = 400, description = "Invalid display name", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not the room owner or an admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Webhooks"
+)]
+pub async fn create_incoming_webhook(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateIncomingWebhookDto>,
+) -> Result<Json<IncomingWebhookResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let incoming_webhook_service = IncomingWebhookService::new(state.db_pool()?);
+
+    incoming_webhook_service
+        .create_incoming_webhook(user_id, state.is_admin(user_id), &room, &payload.display_name)
+        .await
+        .map(Json)
+        .map_err(incoming_webhook_error)
+}
+
+/// List the incoming webhooks of a room, including revoked ones
+#[utoipa::path(
+    get,
+    path = "/api/rooms/{room}/incoming-webhooks",
+    responses(
+        (status = 200, description = "Incoming webhooks of the room", body = [IncomingWebhookResponse]),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not the room owner or an admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Webhooks"
+)]
+pub async fn list_incoming_webhooks(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<IncomingWebhookResponse>>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let incoming_webhook_service = IncomingWebhookService::new(state.db_pool()?);
+
+    incoming_webhook_service
+        .list_incoming_webhooks(user_id, state.is_admin(user_id), &room)
+        .await
+        .map(Json)
+        .map_err(incoming_webhook_error)
+}
+
+/// Revoke an incoming webhook
+///
+/// Requests with the webhook's token are rejected from then on.
+#[utoipa::path(
+    delete,
+    path = "/api/rooms/{room}/incoming-webhooks/{id}",
+    responses(
+        (status = 200, description = "Incoming webhook revoked"),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not the room owner or an admin", body = ErrorResponse),
+        (status = 404, description = "Webhook not found or already revoked", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+        ("id" = String, Path, description = "Incoming webhook ID"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Webhooks"
+)]
+pub async fn revoke_incoming_webhook(
+    State(state): State<ChatState>,
+    Path((room, id)): Path<(String, Uuid)>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let incoming_webhook_service = IncomingWebhookService::new(state.db_pool()?);
+
+    incoming_webhook_service
+        .revoke_incoming_webhook(user_id, state.is_admin(user_id), &room, id)
+        .await
+        .map_err(incoming_webhook_error)?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// Post a message into a room with an incoming webhook
+///
+/// The token in the path authenticates the request; no JWT is needed. The message is
+/// broadcast and stored like any other, authored by the webhook's display name and
+/// flagged with `"bot": true`. It goes through the room's moderation first: secrets are
+/// masked, and a message that a rule rejects or would hold for review is refused with
+/// `400 Bad Request`. Each webhook may post a limited number of messages per minute;
+/// further requests get `429 Too Many Requests` with a `Retry-After` header.
+///
+/// # Example
+///
+/// ```text
+/// curl -X POST http://localhost:8080/hooks/ihk_... \
+///   -H "Content-Type: application/json" \
+///   -d '{"message": "Build #1234 failed on main"}'
+/// ```
+#[utoipa::path(
+    post,
+    path = "/hooks/{token}",
+    request_body = IncomingMessageDto,
+    responses(
+        (status = 200, description = "Message posted"),
+        (status = 400, description = "Empty, too long or refused by moderation", body = ErrorResponse),
+        (status = 404, description = "Unknown or revoked token", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("token" = String, Path, description = "Incoming webhook token"),
+    ),
+    tag = "Webhooks"
+)]
+pub async fn post_incoming_message(
+    State(state): State<ChatState>,
+    Path(token): Path<String>,
+    Json(payload): Json<IncomingMessageDto>,
+) -> Result<Json<Value>, Response> {
+    let incoming_webhook_service = IncomingWebhookService::new(state.db_pool().map_err(IntoResponse::into_response)?);
+    let webhook = incoming_webhook_service
+        .authenticate(&token)
+        .await
+        .map_err(|e| incoming_webhook_error(e).into_response())?;
+
+    if let Err(retry_after) = state.incoming_webhook_limiter.check(webhook.id) {
+        let retry_after = retry_after.as_secs().max(1).to_string();
+        return Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after)],
+            "Rate limit exceeded".to_string(),
+        )
+            .into_response());
+    }
+
+    let message = payload.message.trim();
+    if message.is_empty() || message.chars().count() > MAX_INCOMING_MESSAGE_LENGTH {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Message must be 1 to {} characters", MAX_INCOMING_MESSAGE_LENGTH),
+        )
+            .into_response());
+    }
+
+    // Webhooks have no user to hold a message for, so flagged messages are refused
+    let message = match state.moderation_pipeline(&webhook.room_name).await.run(message) {
+        ModerationOutcome::Allow(message) => message,
+        ModerationOutcome::Reject(reason) => return Err((StatusCode::BAD_REQUEST, reason).into_response()),
+        ModerationOutcome::Flag { reasons, .. } => {
+            let reason = format!("Message was not posted: {}", reasons.join(", "));
+            return Err((StatusCode::BAD_REQUEST, reason).into_response());
+        }
+    };
+
+    let chat_msg = state
+        .post_message(OutgoingMessage {
+            room_name: webhook.room_name,
+            sender: MessageSender::IncomingWebhook(webhook.id),
+            username: webhook.display_name,
+            message,
+            attachment_id: None,
+            action: false,
+        })
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())?;
+
+    Ok(Json(json!({
+        "success": true,
+        "message_id": chat_msg.id,
+        "seq": chat_msg.seq,
+    })))
+}
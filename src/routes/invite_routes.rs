@@ -0,0 +1,203 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use sqlx::Pool;
+use sqlx::Postgres;
+
+use crate::config::environment::Environment;
+use crate::modules::auth::utils::jwt::JwtUtil;
+use crate::modules::chat::dto::invite_dto::{AcceptInviteResponse, CreateInviteDto, InviteListResponse, InviteResponse};
+use crate::modules::chat::server::ChatState;
+use crate::modules::chat::service::InviteService;
+
+/// Configure room invite routes
+pub fn invite_routes(chat_state: ChatState) -> Router<Pool<Postgres>> {
+    Router::new()
+        .route("/api/rooms/:room/invites", get(list_invites).post(create_invite))
+        .route("/api/rooms/:room/invites/:code", delete(revoke_invite))
+        .route("/api/invites/:code/accept", post(accept_invite))
+        .with_state(chat_state)
+}
+
+/// Map invite errors to HTTP status codes
+fn invite_error(e: Box<dyn std::error::Error>) -> (StatusCode, String) {
+    let message = e.to_string();
+    if message.contains("Not a member") || message.contains("Only the room") {
+        (StatusCode::FORBIDDEN, message)
+    } else if message.contains("not found") {
+        (StatusCode::NOT_FOUND, message)
+    } else if message.contains("has expired") || message.contains("no uses left") {
+        (StatusCode::GONE, message)
+    } else if message.contains("Invalid") {
+        (StatusCode::BAD_REQUEST, message)
+    } else {
+        (StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+/// Create an invite to a room
+///
+/// Only the room owner may create invites. Share the returned `code`; anyone
+/// accepting it joins the room with the invite's role, even when the room is
+/// private. Invites can expire at `expires_at` and be limited to `max_uses` users.
+///
+/// # Example
+///
+/// ```json
+/// { "expires_at": "2025-01-08T00:00:00Z", "max_uses": 10, "role": "member" }
+/// ```
+#[utoipa::path(
+    post,
+    path = "/api/rooms/{room}/invites",
+    request_body = CreateInviteDto,
+    responses(
+        (status = 201, description = "Invite created", body = InviteResponse),
+        (status = 400, description = "Invalid expiry, maximum uses or role", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not the room owner", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Invites"
+)]
+pub async fn create_invite(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateInviteDto>,
+) -> Result<(StatusCode, Json<InviteResponse>), (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let invite_service = InviteService::new(state.db_pool()?);
+
+    let invite = invite_service
+        .create_invite(user_id, &room, &payload)
+        .await
+        .map_err(invite_error)?;
+    Ok((StatusCode::CREATED, Json(invite)))
+}
+
+/// List the invites of a room
+///
+/// Revoked invites are left out; expired and used up invites are listed until
+/// they are revoked.
+#[utoipa::path(
+    get,
+    path = "/api/rooms/{room}/invites",
+    responses(
+        (status = 200, description = "Invites of the room, newest first", body = InviteListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not the room owner", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Invites"
+)]
+pub async fn list_invites(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<InviteListResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let invite_service = InviteService::new(state.db_pool()?);
+
+    let invites = invite_service
+        .list_invites(user_id, &room)
+        .await
+        .map_err(invite_error)?;
+    Ok(Json(InviteListResponse { invites }))
+}
+
+/// Revoke an invite
+///
+/// The code can no longer be accepted. Users who already joined with it stay
+/// members of the room.
+#[utoipa::path(
+    delete,
+    path = "/api/rooms/{room}/invites/{code}",
+    responses(
+        (status = 200, description = "Invite revoked"),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not the room owner", body = ErrorResponse),
+        (status = 404, description = "Invite not found or already revoked", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+        ("code" = String, Path, description = "Invite code"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Invites"
+)]
+pub async fn revoke_invite(
+    State(state): State<ChatState>,
+    Path((room, code)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let invite_service = InviteService::new(state.db_pool()?);
+
+    invite_service
+        .revoke_invite(user_id, &room, &code)
+        .await
+        .map_err(invite_error)?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// Accept an invite
+///
+/// Adds the caller to the invite's room with the invite's role, after which they
+/// can connect to the room over the WebSocket. Accepting an invite to a room the
+/// caller already belongs to leaves their role unchanged and does not use up the
+/// invite.
+#[utoipa::path(
+    post,
+    path = "/api/invites/{code}/accept",
+    responses(
+        (status = 200, description = "Caller is a member of the room", body = AcceptInviteResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 404, description = "Invite not found or revoked", body = ErrorResponse),
+        (status = 410, description = "Invite expired or used up", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("code" = String, Path, description = "Invite code"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Invites"
+)]
+pub async fn accept_invite(
+    State(state): State<ChatState>,
+    Path(code): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<AcceptInviteResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let invite_service = InviteService::new(state.db_pool()?);
+
+    invite_service
+        .accept_invite(user_id, &code)
+        .await
+        .map(Json)
+        .map_err(invite_error)
+}
@@ -1,7 +1,31 @@
+pub mod announcement_routes;
+pub mod attachment_routes;
 pub mod auth_routes;
+pub mod block_routes;
 pub mod chat_routes;
 pub mod file_routes;
+pub mod incoming_webhook_routes;
+pub mod invite_routes;
+pub mod moderation_routes;
+pub mod notification_routes;
+pub mod poll_routes;
+pub mod room_routes;
+pub mod scheduled_message_routes;
+pub mod search_routes;
+pub mod webhook_routes;
 
+pub use announcement_routes::announcement_routes;
+pub use attachment_routes::attachment_routes;
 pub use auth_routes::auth_routes;
+pub use block_routes::block_routes;
 pub use chat_routes::chat_routes;
-pub use file_routes::file_routes;
\ No newline at end of file
+pub use file_routes::file_routes;
+pub use incoming_webhook_routes::incoming_webhook_routes;
+pub use invite_routes::invite_routes;
+pub use moderation_routes::moderation_routes;
+pub use notification_routes::notification_routes;
+pub use poll_routes::poll_routes;
+pub use room_routes::room_routes;
+pub use scheduled_message_routes::scheduled_message_routes;
+pub use search_routes::search_routes;
+pub use webhook_routes::webhook_routes;
\ No newline at end of file
@@ -0,0 +1,313 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use sqlx::Pool;
+use sqlx::Postgres;
+use uuid::Uuid;
+
+use crate::config::environment::Environment;
+use crate::modules::auth::utils::jwt::JwtUtil;
+use crate::modules::chat::dto::moderation_dto::{
+    CreateModerationRuleDto, FlaggedMessageResponse, ModerationQueueQuery, ModerationRuleResponse,
+};
+use crate::modules::chat::server::{ChatState, MessageSender, OutgoingMessage};
+use crate::modules::chat::service::ModerationService;
+
+/// Configure content moderation routes
+pub fn moderation_routes(chat_state: ChatState) -> Router<Pool<Postgres>> {
+    Router::new()
+        .route("/api/rooms/:room/moderation/rules", get(list_moderation_rules).post(create_moderation_rule))
+        .route("/api/rooms/:room/moderation/rules/:id", delete(delete_moderation_rule))
+        .route("/api/rooms/:room/moderation/queue", get(list_moderation_queue))
+        .route("/api/rooms/:room/moderation/queue/:id/approve", post(approve_flagged_message))
+        .route("/api/rooms/:room/moderation/queue/:id/reject", post(reject_flagged_message))
+        .with_state(chat_state)
+}
+
+/// Map moderation service errors to HTTP status codes
+fn moderation_error(e: Box<dyn std::error::Error>) -> (StatusCode, String) {
+    let message = e.to_string();
+    if message.contains("Not a member") || message.contains("Only room") {
+        (StatusCode::FORBIDDEN, message)
+    } else if message.contains("not found") {
+        (StatusCode::NOT_FOUND, message)
+    } else if message.contains("Invalid") || message.contains("at most") {
+        (StatusCode::BAD_REQUEST, message)
+    } else {
+        (StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+/// Add a moderation rule to a room
+///
+/// Only room owners, moderators and server admins may manage rules. Every message is first checked
+/// for secrets (AWS keys, JWTs), which are masked, and for server-wide profanity;
+/// the room's rules then run in the order they were created. A rule matches a
+/// `word`, a `regex` or links to a `link_domain` (including subdomains) and will
+/// `mask` the match, `reject` the message or `flag` it for review.
+///
+/// # Example
+///
+/// ```json
+/// { "kind": "link_domain", "pattern": "spam.example.com", "action": "reject" }
+/// ```
+#[utoipa::path(
+    post,
+    path = "/api/rooms/{room}/moderation/rules",
+    request_body = CreateModerationRuleDto,
+    responses(
+        (status = 200, description = "Rule created", body = ModerationRuleResponse),
+        (status = 400, description = "Invalid rule or too many rules", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a moderator of the room or an admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Moderation"
+)]
+pub async fn create_moderation_rule(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateModerationRuleDto>,
+) -> Result<Json<ModerationRuleResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let moderation_service = ModerationService::new(state.db_pool()?);
+
+    let rule = moderation_service
+        .create_rule(user_id, state.is_admin(user_id), &room, &payload.kind, &payload.pattern, &payload.action)
+        .await
+        .map_err(moderation_error)?;
+    state.invalidate_moderation(&room);
+
+    Ok(Json(rule))
+}
+
+/// List the moderation rules of a room, in the order they are applied
+#[utoipa::path(
+    get,
+    path = "/api/rooms/{room}/moderation/rules",
+    responses(
+        (status = 200, description = "Rules of the room", body = [ModerationRuleResponse]),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a moderator of the room or an admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Moderation"
+)]
+pub async fn list_moderation_rules(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<ModerationRuleResponse>>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let moderation_service = ModerationService::new(state.db_pool()?);
+
+    moderation_service
+        .list_rules(user_id, state.is_admin(user_id), &room)
+        .await
+        .map(Json)
+        .map_err(moderation_error)
+}
+
+/// Delete a moderation rule
+#[utoipa::path(
+    delete,
+    path = "/api/rooms/{room}/moderation/rules/{id}",
+    responses(
+        (status = 200, description = "Rule deleted"),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a moderator of the room or an admin", body = ErrorResponse),
+        (status = 404, description = "Rule not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+        ("id" = String, Path, description = "Rule ID"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Moderation"
+)]
+pub async fn delete_moderation_rule(
+    State(state): State<ChatState>,
+    Path((room, id)): Path<(String, Uuid)>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let moderation_service = ModerationService::new(state.db_pool()?);
+
+    moderation_service
+        .delete_rule(user_id, state.is_admin(user_id), &room, id)
+        .await
+        .map_err(moderation_error)?;
+    state.invalidate_moderation(&room);
+
+    Ok(Json(json!({ "success": true })))
+}
+
+/// List flagged messages awaiting review
+///
+/// Messages flagged by a rule are held back instead of being broadcast. Pass
+/// `status=approved` or `status=rejected` to see reviewed messages.
+#[utoipa::path(
+    get,
+    path = "/api/rooms/{room}/moderation/queue",
+    params(
+        ("room" = String, Path, description = "Room name"),
+        ModerationQueueQuery,
+    ),
+    responses(
+        (status = 200, description = "Flagged messages, oldest first", body = [FlaggedMessageResponse]),
+        (status = 400, description = "Invalid status", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a moderator of the room or an admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Moderation"
+)]
+pub async fn list_moderation_queue(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    Query(query): Query<ModerationQueueQuery>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<FlaggedMessageResponse>>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let moderation_service = ModerationService::new(state.db_pool()?);
+
+    moderation_service
+        .list_queue(user_id, state.is_admin(user_id), &room, query.status.as_deref())
+        .await
+        .map(Json)
+        .map_err(moderation_error)
+}
+
+/// Approve a flagged message
+///
+/// The message is posted to the room as its author, exactly as it would have been
+/// had it not been flagged, and the author is told it was approved.
+#[utoipa::path(
+    post,
+    path = "/api/rooms/{room}/moderation/queue/{id}/approve",
+    responses(
+        (status = 200, description = "Message approved and posted", body = FlaggedMessageResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a moderator of the room or an admin", body = ErrorResponse),
+        (status = 404, description = "No pending flagged message with this ID", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+        ("id" = String, Path, description = "Flagged message ID"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Moderation"
+)]
+pub async fn approve_flagged_message(
+    State(state): State<ChatState>,
+    Path((room, id)): Path<(String, Uuid)>,
+    headers: HeaderMap,
+) -> Result<Json<FlaggedMessageResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let moderation_service = ModerationService::new(state.db_pool()?);
+
+    let mut flagged = moderation_service
+        .review(user_id, state.is_admin(user_id), &room, id, true)
+        .await
+        .map_err(moderation_error)?;
+
+    let chat_msg = state
+        .post_message(OutgoingMessage {
+            room_name: flagged.room_name.clone(),
+            sender: MessageSender::User(flagged.user_id),
+            username: flagged.username.clone(),
+            message: flagged.body.clone(),
+            attachment_id: flagged.attachment_id,
+            action: flagged.is_action,
+        })
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    if let Some(message_id) = chat_msg.id.as_deref().and_then(|id| Uuid::parse_str(id).ok()) {
+        moderation_service
+            .set_posted_message(flagged.id, message_id)
+            .await
+            .map_err(moderation_error)?;
+        flagged.message_id = Some(message_id);
+    }
+    state.send_system_to_user(
+        flagged.user_id,
+        format!("Your held message in {} was approved by a moderator.", flagged.room_name),
+    );
+
+    Ok(Json(flagged.to_response()))
+}
+
+/// Reject a flagged message
+///
+/// The message is never posted, and the author is told it was rejected.
+#[utoipa::path(
+    post,
+    path = "/api/rooms/{room}/moderation/queue/{id}/reject",
+    responses(
+        (status = 200, description = "Message rejected", body = FlaggedMessageResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a moderator of the room or an admin", body = ErrorResponse),
+        (status = 404, description = "No pending flagged message with this ID", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+        ("id" = String, Path, description = "Flagged message ID"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Moderation"
+)]
+pub async fn reject_flagged_message(
+    State(state): State<ChatState>,
+    Path((room, id)): Path<(String, Uuid)>,
+    headers: HeaderMap,
+) -> Result<Json<FlaggedMessageResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let moderation_service = ModerationService::new(state.db_pool()?);
+
+    let flagged = moderation_service
+        .review(user_id, state.is_admin(user_id), &room, id, false)
+        .await
+        .map_err(moderation_error)?;
+    state.send_system_to_user(
+        flagged.user_id,
+        format!("Your held message in {} was rejected by a moderator.", flagged.room_name),
+    );
+
+    Ok(Json(flagged.to_response()))
+}
@@ -0,0 +1,130 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use sqlx::Pool;
+use sqlx::Postgres;
+use uuid::Uuid;
+
+use crate::config::environment::Environment;
+use crate::modules::auth::utils::jwt::JwtUtil;
+use crate::modules::chat::dto::notification_dto::{ListNotificationsQuery, NotificationListResponse};
+use crate::modules::chat::service::NotificationService;
+
+/// Configure notification routes
+pub fn notification_routes() -> Router<Pool<Postgres>> {
+    Router::new()
+        .route("/api/notifications", get(list_notifications))
+        .route("/api/notifications/read-all", post(mark_all_notifications_read))
+        .route("/api/notifications/:id/read", post(mark_notification_read))
+}
+
+/// List notifications
+///
+/// Returns the caller's notification inbox, newest first, together with the total
+/// number of unread notifications. Mentions (`@name`) in chat messages create a
+/// notification of kind `mention`.
+///
+/// # Example
+///
+/// ```text
+/// GET /api/notifications?unread_only=true&limit=20
+/// ```
+#[utoipa::path(
+    get,
+    path = "/api/notifications",
+    params(ListNotificationsQuery),
+    responses(
+        (status = 200, description = "Notifications", body = NotificationListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Notifications"
+)]
+pub async fn list_notifications(
+    State(pool): State<Pool<Postgres>>,
+    Query(query): Query<ListNotificationsQuery>,
+    headers: HeaderMap,
+) -> Result<Json<NotificationListResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let notification_service = NotificationService::new(pool);
+
+    match notification_service.list(user_id, query).await {
+        Ok(response) => Ok(Json(response)),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+/// Mark a notification as read
+#[utoipa::path(
+    post,
+    path = "/api/notifications/{id}/read",
+    params(
+        ("id" = String, Path, description = "Notification ID")
+    ),
+    responses(
+        (status = 200, description = "Notification marked as read"),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 404, description = "Notification not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Notifications"
+)]
+pub async fn mark_notification_read(
+    State(pool): State<Pool<Postgres>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let notification_service = NotificationService::new(pool);
+
+    match notification_service.mark_read(user_id, id).await {
+        Ok(()) => Ok(Json(json!({ "success": true }))),
+        Err(e) => {
+            if e.to_string().contains("not found") {
+                Err((StatusCode::NOT_FOUND, e.to_string()))
+            } else {
+                Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+            }
+        }
+    }
+}
+
+/// Mark all notifications as read
+#[utoipa::path(
+    post,
+    path = "/api/notifications/read-all",
+    responses(
+        (status = 200, description = "All notifications marked as read"),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Notifications"
+)]
+pub async fn mark_all_notifications_read(
+    State(pool): State<Pool<Postgres>>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let notification_service = NotificationService::new(pool);
+
+    match notification_service.mark_all_read(user_id).await {
+        Ok(updated) => Ok(Json(json!({ "success": true, "updated": updated }))),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
@@ -0,0 +1,233 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use sqlx::Pool;
+use sqlx::Postgres;
+use uuid::Uuid;
+
+use crate::config::environment::Environment;
+use crate::modules::auth::utils::jwt::JwtUtil;
+use crate::modules::chat::dto::poll_dto::{CreatePollDto, PollListResponse, PollResponse, VotePollDto};
+use crate::modules::chat::server::{display_name, ChatState};
+use crate::modules::chat::service::PollService;
+
+/// Configure poll routes
+pub fn poll_routes(chat_state: ChatState) -> Router<Pool<Postgres>> {
+    Router::new()
+        .route("/api/rooms/:room/polls", get(list_polls).post(create_poll))
+        .route("/api/polls/:id", get(get_poll))
+        .route("/api/polls/:id/votes", post(vote_poll))
+        .route("/api/polls/:id/close", post(close_poll))
+        .with_state(chat_state)
+}
+
+/// Map poll errors to HTTP status codes
+fn poll_error(message: String) -> (StatusCode, String) {
+    if message.contains("Not a member") || message.contains("Only the poll") {
+        (StatusCode::FORBIDDEN, message)
+    } else if message.contains("not found") {
+        (StatusCode::NOT_FOUND, message)
+    } else if message.contains("not available") {
+        (StatusCode::SERVICE_UNAVAILABLE, message)
+    } else if message.contains("Invalid") {
+        (StatusCode::BAD_REQUEST, message)
+    } else if message.contains("Poll was not posted") || message.contains("not allowed") || message.contains("blocked") {
+        (StatusCode::UNPROCESSABLE_ENTITY, message)
+    } else {
+        (StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+/// Create a poll in a room
+///
+/// Members post polls with 2 to 10 options, optionally allowing several choices,
+/// hiding voters and closing automatically at `closes_at`. The poll is announced in
+/// the room's history and live members receive a `poll:` event with the tallies
+/// every time a vote arrives. Question and options pass the room's moderation rules.
+///
+/// # Example
+///
+/// ```json
+/// { "question": "Lunch?", "options": ["Pizza", "Sushi"], "multiple_choice": false, "anonymous": true, "closes_at": "2025-01-01T12:00:00Z" }
+/// ```
+#[utoipa::path(
+    post,
+    path = "/api/rooms/{room}/polls",
+    request_body = CreatePollDto,
+    responses(
+        (status = 201, description = "Poll created", body = PollResponse),
+        (status = 400, description = "Invalid question, options or deadline", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a member of the room", body = ErrorResponse),
+        (status = 422, description = "Rejected by the room's moderation rules", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Polls"
+)]
+pub async fn create_poll(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<CreatePollDto>,
+) -> Result<(StatusCode, Json<PollResponse>), (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+
+    let poll = state
+        .create_poll(user_id, &display_name(user_id), &room, payload)
+        .await
+        .map_err(poll_error)?;
+    Ok((StatusCode::CREATED, Json(poll)))
+}
+
+/// List the recent polls of a room, newest first
+#[utoipa::path(
+    get,
+    path = "/api/rooms/{room}/polls",
+    responses(
+        (status = 200, description = "Polls with their tallies", body = PollListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a member of the room", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Polls"
+)]
+pub async fn list_polls(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<PollListResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let poll_service = PollService::new(state.db_pool()?);
+
+    let polls = poll_service
+        .list_polls(user_id, &room)
+        .await
+        .map_err(|e| poll_error(e.to_string()))?;
+    Ok(Json(PollListResponse { polls }))
+}
+
+/// Get a poll with its current tallies
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}",
+    responses(
+        (status = 200, description = "Poll with its tallies", body = PollResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a member of the poll's room", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("id" = String, Path, description = "Poll ID"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Polls"
+)]
+pub async fn get_poll(
+    State(state): State<ChatState>,
+    Path(poll_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<PollResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let poll_service = PollService::new(state.db_pool()?);
+
+    poll_service
+        .get_poll(user_id, poll_id)
+        .await
+        .map(Json)
+        .map_err(|e| poll_error(e.to_string()))
+}
+
+/// Vote in a poll
+///
+/// Options are chosen by their `index`, starting at 0. Voting again replaces the
+/// member's earlier vote. Votes are accepted until the poll is closed.
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/votes",
+    request_body = VotePollDto,
+    responses(
+        (status = 200, description = "Vote recorded", body = PollResponse),
+        (status = 400, description = "Invalid choice or poll closed", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a member of the poll's room", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("id" = String, Path, description = "Poll ID"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Polls"
+)]
+pub async fn vote_poll(
+    State(state): State<ChatState>,
+    Path(poll_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<VotePollDto>,
+) -> Result<Json<PollResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+
+    state
+        .vote_poll(user_id, poll_id, &payload.options)
+        .await
+        .map(Json)
+        .map_err(poll_error)
+}
+
+/// Close a poll before its deadline
+///
+/// Allowed for the poll's creator and the room's owners and moderators. The final
+/// results are posted to the room's history.
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/close",
+    responses(
+        (status = 200, description = "Poll closed", body = PollResponse),
+        (status = 400, description = "Poll already closed", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not the poll creator or a room moderator", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("id" = String, Path, description = "Poll ID"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Polls"
+)]
+pub async fn close_poll(
+    State(state): State<ChatState>,
+    Path(poll_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<PollResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+
+    state.close_poll(user_id, poll_id).await.map(Json).map_err(poll_error)
+}
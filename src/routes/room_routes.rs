@@ -0,0 +1,470 @@
+use axum::{
+    body::StreamBody,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, put, delete},
+    Json, Router,
+};
+use sqlx::Pool;
+use sqlx::Postgres;
+use uuid::Uuid;
+
+use crate::config::environment::Environment;
+use crate::modules::auth::utils::jwt::JwtUtil;
+use crate::modules::chat::dto::message_dto::ExportTranscriptQuery;
+use crate::modules::chat::dto::retention_dto::{RetentionPolicyResponse, SetRetentionPolicyDto};
+use crate::modules::chat::dto::room_dto::{
+    PinMessageDto, PinnedMessagesResponse, RoomPrivacyResponse, RoomTopicResponse, SetMemberRoleDto,
+    SetRoomPrivacyDto, SetTopicDto,
+};
+use crate::modules::chat::server::{display_name, ChatState};
+use crate::modules::chat::service::{MessageService, RetentionService, RoomService};
+
+/// Configure room management routes
+pub fn room_routes(chat_state: ChatState) -> Router<Pool<Postgres>> {
+    Router::new()
+        .route("/api/rooms/:room/topic", get(get_room_topic).put(set_room_topic))
+        .route("/api/rooms/:room/pins", get(get_pinned_messages).post(pin_message))
+        .route("/api/rooms/:room/pins/:message_id", delete(unpin_message))
+        .route("/api/rooms/:room/members/:user_id/role", put(set_member_role))
+        .route("/api/rooms/:room/privacy", put(set_room_privacy))
+        .route("/api/rooms/:room/export", get(export_room_transcript))
+        .route("/api/rooms/:room/retention", get(get_retention_policy).put(set_retention_policy))
+        .with_state(chat_state)
+}
+
+/// Map room service errors to HTTP status codes
+fn room_error(e: Box<dyn std::error::Error>) -> (StatusCode, String) {
+    let message = e.to_string();
+    if message.contains("Not a member") || message.contains("Only the room") || message.contains("Only room") || message.contains("Only admins") {
+        (StatusCode::FORBIDDEN, message)
+    } else if message.contains("not found") {
+        (StatusCode::NOT_FOUND, message)
+    } else if message.contains("at most") || message.contains("Invalid") {
+        (StatusCode::BAD_REQUEST, message)
+    } else {
+        (StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+/// Get the topic of a room
+#[utoipa::path(
+    get,
+    path = "/api/rooms/{room}/topic",
+    responses(
+        (status = 200, description = "Room topic", body = RoomTopicResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a member of the room", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Chat"
+)]
+pub async fn get_room_topic(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<RoomTopicResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let room_service = RoomService::new(state.db_pool()?);
+
+    room_service.get_topic(user_id, &room).await.map(Json).map_err(room_error)
+}
+
+/// Change the topic of a room
+///
+/// Only room owners, moderators and server admins may change the topic. An empty topic clears it.
+/// Live members receive a `topic:` event over their WebSocket connection.
+#[utoipa::path(
+    put,
+    path = "/api/rooms/{room}/topic",
+    request_body = SetTopicDto,
+    responses(
+        (status = 200, description = "Topic updated", body = RoomTopicResponse),
+        (status = 400, description = "Topic is too long", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a moderator of the room or an admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Chat"
+)]
+pub async fn set_room_topic(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<SetTopicDto>,
+) -> Result<Json<RoomTopicResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let room_service = RoomService::new(state.db_pool()?);
+
+    let topic = room_service
+        .set_topic(user_id, state.is_admin(user_id), &room, &payload.topic)
+        .await
+        .map_err(room_error)?;
+    state.publish_topic(topic.clone(), &display_name(user_id)).await;
+
+    Ok(Json(topic))
+}
+
+/// List the pinned messages of a room
+#[utoipa::path(
+    get,
+    path = "/api/rooms/{room}/pins",
+    responses(
+        (status = 200, description = "Pinned messages", body = PinnedMessagesResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a member of the room", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Chat"
+)]
+pub async fn get_pinned_messages(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<PinnedMessagesResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let room_service = RoomService::new(state.db_pool()?);
+
+    room_service.get_pins(user_id, &room).await.map(Json).map_err(room_error)
+}
+
+/// Pin a message in a room
+///
+/// Only room owners, moderators and server admins may pin messages, and a room holds a small,
+/// fixed number of pins. Live members receive a `pins:` event with the updated list.
+#[utoipa::path(
+    post,
+    path = "/api/rooms/{room}/pins",
+    request_body = PinMessageDto,
+    responses(
+        (status = 200, description = "Message pinned", body = PinnedMessagesResponse),
+        (status = 400, description = "Too many pinned messages or invalid ID", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a moderator of the room or an admin", body = ErrorResponse),
+        (status = 404, description = "Message not found in the room", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Chat"
+)]
+pub async fn pin_message(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<PinMessageDto>,
+) -> Result<Json<PinnedMessagesResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let message_id = Uuid::parse_str(&payload.message_id)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid message ID".to_string()))?;
+    let room_service = RoomService::new(state.db_pool()?);
+
+    let pins = room_service
+        .pin_message(user_id, state.is_admin(user_id), &room, message_id)
+        .await
+        .map_err(room_error)?;
+    state.publish_pins(&pins).await;
+
+    Ok(Json(pins))
+}
+
+/// Unpin a message in a room
+#[utoipa::path(
+    delete,
+    path = "/api/rooms/{room}/pins/{message_id}",
+    responses(
+        (status = 200, description = "Message unpinned", body = PinnedMessagesResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a moderator of the room or an admin", body = ErrorResponse),
+        (status = 404, description = "Message is not pinned", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+        ("message_id" = String, Path, description = "ID of the pinned message"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Chat"
+)]
+pub async fn unpin_message(
+    State(state): State<ChatState>,
+    Path((room, message_id)): Path<(String, Uuid)>,
+    headers: HeaderMap,
+) -> Result<Json<PinnedMessagesResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let room_service = RoomService::new(state.db_pool()?);
+
+    let pins = room_service
+        .unpin_message(user_id, state.is_admin(user_id), &room, message_id)
+        .await
+        .map_err(room_error)?;
+    state.publish_pins(&pins).await;
+
+    Ok(Json(pins))
+}
+
+/// Change a member's role in a room
+///
+/// Only the room owner (the first user to join it) and server admins may promote
+/// members to `moderator` or demote them back to `member`.
+#[utoipa::path(
+    put,
+    path = "/api/rooms/{room}/members/{user_id}/role",
+    request_body = SetMemberRoleDto,
+    responses(
+        (status = 200, description = "Role updated"),
+        (status = 400, description = "Invalid role", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not the room owner or an admin", body = ErrorResponse),
+        (status = 404, description = "Member not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+        ("user_id" = String, Path, description = "ID of the member"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Chat"
+)]
+pub async fn set_member_role(
+    State(state): State<ChatState>,
+    Path((room, member_id)): Path<(String, Uuid)>,
+    headers: HeaderMap,
+    Json(payload): Json<SetMemberRoleDto>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let room_service = RoomService::new(state.db_pool()?);
+
+    room_service
+        .set_member_role(user_id, state.is_admin(user_id), &room, member_id, &payload.role)
+        .await
+        .map_err(room_error)?;
+
+    let response = serde_json::json!({
+        "success": true,
+        "message": "Role updated successfully"
+    });
+    Ok(Json(response))
+}
+
+/// Make a room private or public
+///
+/// Only the room owner and server admins may change this. Private rooms admit only their members:
+/// other users can no longer connect to or `/join` the room and need an invite
+/// (see `POST /api/rooms/{room}/invites`). Existing members are unaffected. The
+/// default `general` room has no owner and is always public.
+#[utoipa::path(
+    put,
+    path = "/api/rooms/{room}/privacy",
+    request_body = SetRoomPrivacyDto,
+    responses(
+        (status = 200, description = "Privacy updated", body = RoomPrivacyResponse),
+        (status = 400, description = "The default room cannot be made private", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not the room owner or an admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Chat"
+)]
+pub async fn set_room_privacy(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<SetRoomPrivacyDto>,
+) -> Result<Json<RoomPrivacyResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let room_service = RoomService::new(state.db_pool()?);
+
+    room_service
+        .set_private(user_id, state.is_admin(user_id), &room, payload.private)
+        .await
+        .map(Json)
+        .map_err(room_error)
+}
+
+
+/// Export the transcript of a room
+///
+/// Streams every persisted message of the room, oldest first, optionally limited to a
+/// time range. Each message includes its author, whether it was posted by a bot or as
+/// an action, and a reference to its attachment. Only the room owner and server admins
+/// (`CHAT_ADMIN_USERS`) may export a room.
+///
+/// Edits and deletions are not part of the transcript: messages cannot be edited or
+/// deleted, and messages purged by the retention policy are no longer stored.
+///
+/// # Example
+///
+/// ```text
+/// GET /api/rooms/general/export?format=csv&from=2025-01-01T00:00:00Z&to=2025-02-01T00:00:00Z
+/// ```
+#[utoipa::path(
+    get,
+    path = "/api/rooms/{room}/export",
+    params(
+        ("room" = String, Path, description = "Room name"),
+        ExportTranscriptQuery,
+    ),
+    responses(
+        (status = 200, description = "Transcript as JSON, CSV or plain text", content_type = "application/octet-stream"),
+        (status = 400, description = "Invalid format or time range", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not the room owner or an admin", body = ErrorResponse),
+        (status = 404, description = "Room not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Chat"
+)]
+pub async fn export_room_transcript(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    Query(query): Query<ExportTranscriptQuery>,
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let message_service = MessageService::new(state.db_pool()?);
+
+    let (format, body) = message_service
+        .export_transcript(user_id, state.is_admin(user_id), &room, query)
+        .await
+        .map_err(room_error)?;
+
+    let file_name: String = room
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let disposition = format!("attachment; filename=\"{}-transcript.{}\"", file_name, format.as_str());
+    let headers = [
+        (header::CONTENT_TYPE, format.content_type().to_string()),
+        (header::CONTENT_DISPOSITION, disposition),
+    ];
+    Ok((headers, StreamBody::new(body)).into_response())
+}
+
+/// Get the retention policy of a room
+///
+/// `effective_retention_days` is how long messages are actually kept, after applying the
+/// server default (`CHAT_RETENTION_DAYS`) and legal hold; `null` means forever.
+#[utoipa::path(
+    get,
+    path = "/api/rooms/{room}/retention",
+    responses(
+        (status = 200, description = "Retention policy", body = RetentionPolicyResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a member of the room", body = ErrorResponse),
+        (status = 404, description = "Room not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Chat"
+)]
+pub async fn get_retention_policy(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<RetentionPolicyResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let is_admin = state.is_admin(user_id);
+    let retention_service = RetentionService::new(state.db_pool()?, env);
+
+    retention_service
+        .get_policy(user_id, is_admin, &room)
+        .await
+        .map(Json)
+        .map_err(room_error)
+}
+
+/// Change the retention policy of a room
+///
+/// Messages older than the retention period are deleted by a background job, together
+/// with attachments no message refers to any more. `retention_days` of `null` uses the
+/// server default and 0 keeps messages forever. The room owner and server admins may
+/// change the period; only admins may place or lift a legal hold, which exempts the
+/// room from purging entirely.
+#[utoipa::path(
+    put,
+    path = "/api/rooms/{room}/retention",
+    request_body = SetRetentionPolicyDto,
+    responses(
+        (status = 200, description = "Retention policy updated", body = RetentionPolicyResponse),
+        (status = 400, description = "Invalid retention period", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not the room owner or an admin", body = ErrorResponse),
+        (status = 404, description = "Room not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Chat"
+)]
+pub async fn set_retention_policy(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<SetRetentionPolicyDto>,
+) -> Result<Json<RetentionPolicyResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let is_admin = state.is_admin(user_id);
+    let retention_service = RetentionService::new(state.db_pool()?, env);
+
+    retention_service
+        .set_policy(user_id, is_admin, &room, payload)
+        .await
+        .map(Json)
+        .map_err(room_error)
+}
\ No newline at end of file
@@ -0,0 +1,244 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use sqlx::Pool;
+use sqlx::Postgres;
+use uuid::Uuid;
+
+use crate::config::environment::Environment;
+use crate::modules::auth::utils::jwt::JwtUtil;
+use crate::modules::chat::dto::scheduled_message_dto::{
+    ScheduleMessageDto, ScheduledMessageListResponse, ScheduledMessageResponse, ScheduledMessagesQuery,
+    UpdateScheduledMessageDto,
+};
+use crate::modules::chat::server::ChatState;
+use crate::modules::chat::service::ScheduledMessageService;
+
+/// Configure scheduled message routes
+pub fn scheduled_message_routes(chat_state: ChatState) -> Router<Pool<Postgres>> {
+    Router::new()
+        .route("/api/rooms/:room/scheduled-messages", post(schedule_message))
+        .route("/api/scheduled-messages", get(list_scheduled_messages))
+        .route(
+            "/api/scheduled-messages/:id",
+            get(get_scheduled_message)
+                .patch(update_scheduled_message)
+                .delete(cancel_scheduled_message),
+        )
+        .with_state(chat_state)
+}
+
+/// Map scheduled message errors to HTTP status codes
+fn scheduled_message_error(e: Box<dyn std::error::Error>) -> (StatusCode, String) {
+    let message = e.to_string();
+    if message.contains("Not a member") {
+        (StatusCode::FORBIDDEN, message)
+    } else if message.contains("not found") {
+        (StatusCode::NOT_FOUND, message)
+    } else if message.contains("already been sent") {
+        (StatusCode::CONFLICT, message)
+    } else if message.contains("Invalid") {
+        (StatusCode::BAD_REQUEST, message)
+    } else {
+        (StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+/// Schedule a message to a room
+///
+/// At `send_at` the message is posted as its author, exactly as if it had been sent
+/// over the WebSocket then: the room's moderation rules and plugins apply at that
+/// time, and the author must still be a member of the room. Scheduled messages are
+/// stored in the database and survive restarts; messages that came due while the
+/// server was down are posted when it starts.
+///
+/// # Example
+///
+/// ```json
+/// { "message": "Standup starts in 5 minutes!", "send_at": "2025-01-01T09:55:00Z" }
+/// ```
+#[utoipa::path(
+    post,
+    path = "/api/rooms/{room}/scheduled-messages",
+    request_body = ScheduleMessageDto,
+    responses(
+        (status = 201, description = "Message scheduled", body = ScheduledMessageResponse),
+        (status = 400, description = "Invalid message, send time or attachment", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not a member of the room", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Scheduled Messages"
+)]
+pub async fn schedule_message(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<ScheduleMessageDto>,
+) -> Result<(StatusCode, Json<ScheduledMessageResponse>), (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let scheduled_message_service = ScheduledMessageService::new(state.db_pool()?);
+
+    let scheduled = scheduled_message_service
+        .schedule(user_id, &room, &payload)
+        .await
+        .map_err(scheduled_message_error)?;
+    state.schedule_notify.notify_one();
+    Ok((StatusCode::CREATED, Json(scheduled)))
+}
+
+/// List your pending scheduled messages, soonest first
+#[utoipa::path(
+    get,
+    path = "/api/scheduled-messages",
+    params(ScheduledMessagesQuery),
+    responses(
+        (status = 200, description = "Pending scheduled messages", body = ScheduledMessageListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Scheduled Messages"
+)]
+pub async fn list_scheduled_messages(
+    State(state): State<ChatState>,
+    Query(query): Query<ScheduledMessagesQuery>,
+    headers: HeaderMap,
+) -> Result<Json<ScheduledMessageListResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let scheduled_message_service = ScheduledMessageService::new(state.db_pool()?);
+
+    let scheduled_messages = scheduled_message_service
+        .list_pending(user_id, query.room.as_deref())
+        .await
+        .map_err(scheduled_message_error)?;
+    Ok(Json(ScheduledMessageListResponse { scheduled_messages }))
+}
+
+/// Get one of your scheduled messages
+///
+/// Sent and failed messages can still be looked up here, e.g. to find the ID of
+/// the chat message that was posted or why posting failed.
+#[utoipa::path(
+    get,
+    path = "/api/scheduled-messages/{id}",
+    responses(
+        (status = 200, description = "The scheduled message", body = ScheduledMessageResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 404, description = "Scheduled message not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("id" = String, Path, description = "Scheduled message ID"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Scheduled Messages"
+)]
+pub async fn get_scheduled_message(
+    State(state): State<ChatState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<ScheduledMessageResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let scheduled_message_service = ScheduledMessageService::new(state.db_pool()?);
+
+    scheduled_message_service
+        .get(user_id, id)
+        .await
+        .map(Json)
+        .map_err(scheduled_message_error)
+}
+
+/// Edit a pending scheduled message
+///
+/// Changes the text, the send time or both. Messages that are being sent or have
+/// been sent can no longer be edited.
+#[utoipa::path(
+    patch,
+    path = "/api/scheduled-messages/{id}",
+    request_body = UpdateScheduledMessageDto,
+    responses(
+        (status = 200, description = "Scheduled message updated", body = ScheduledMessageResponse),
+        (status = 400, description = "Invalid message or send time", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 404, description = "Scheduled message not found", body = ErrorResponse),
+        (status = 409, description = "Message already sent", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("id" = String, Path, description = "Scheduled message ID"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Scheduled Messages"
+)]
+pub async fn update_scheduled_message(
+    State(state): State<ChatState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateScheduledMessageDto>,
+) -> Result<Json<ScheduledMessageResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let scheduled_message_service = ScheduledMessageService::new(state.db_pool()?);
+
+    let scheduled = scheduled_message_service
+        .update(user_id, id, &payload)
+        .await
+        .map_err(scheduled_message_error)?;
+    state.schedule_notify.notify_one();
+    Ok(Json(scheduled))
+}
+
+/// Cancel a pending scheduled message
+#[utoipa::path(
+    delete,
+    path = "/api/scheduled-messages/{id}",
+    responses(
+        (status = 200, description = "Scheduled message cancelled"),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 404, description = "Scheduled message not found", body = ErrorResponse),
+        (status = 409, description = "Message already sent", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("id" = String, Path, description = "Scheduled message ID"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Scheduled Messages"
+)]
+pub async fn cancel_scheduled_message(
+    State(state): State<ChatState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let scheduled_message_service = ScheduledMessageService::new(state.db_pool()?);
+
+    scheduled_message_service
+        .cancel(user_id, id)
+        .await
+        .map_err(scheduled_message_error)?;
+    Ok(Json(json!({ "success": true })))
+}
@@ -0,0 +1,67 @@
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use sqlx::Pool;
+use sqlx::Postgres;
+
+use crate::config::environment::Environment;
+use crate::modules::auth::utils::jwt::JwtUtil;
+use crate::modules::chat::dto::message_dto::{MessageSearchResponse, SearchMessagesQuery};
+use crate::modules::chat::service::MessageService;
+
+/// Configure search routes
+pub fn search_routes() -> Router<Pool<Postgres>> {
+    Router::new()
+        .route("/api/search/messages", get(search_messages))
+}
+
+/// Search chat history
+///
+/// Full-text search over persisted chat messages, limited to rooms the caller has joined.
+/// Results are ordered by relevance, then by recency, and include an HTML `snippet` with
+/// the matching terms wrapped in `<mark>` tags. The message text in the snippet is
+/// HTML-escaped, so it can be inserted into a page as is; `message` is the raw text.
+///
+/// # Example
+///
+/// ```text
+/// GET /api/search/messages?q=deploy%20failed&room=ops&from=2025-01-01T00:00:00Z&limit=20&offset=0
+/// ```
+#[utoipa::path(
+    get,
+    path = "/api/search/messages",
+    params(SearchMessagesQuery),
+    responses(
+        (status = 200, description = "Matching messages", body = MessageSearchResponse),
+        (status = 400, description = "Invalid search query", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Chat"
+)]
+pub async fn search_messages(
+    State(pool): State<Pool<Postgres>>,
+    Query(query): Query<SearchMessagesQuery>,
+    headers: HeaderMap,
+) -> Result<Json<MessageSearchResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let message_service = MessageService::new(pool);
+
+    match message_service.search(user_id, query).await {
+        Ok(response) => Ok(Json(response)),
+        Err(e) => {
+            if e.to_string().contains("must not be empty") {
+                Err((StatusCode::BAD_REQUEST, e.to_string()))
+            } else {
+                Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{delete, get},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use sqlx::Pool;
+use sqlx::Postgres;
+use uuid::Uuid;
+
+use crate::config::environment::Environment;
+use crate::modules::auth::utils::jwt::JwtUtil;
+use crate::modules::chat::dto::webhook_dto::{CreateWebhookDto, WebhookDeliveryResponse, WebhookResponse};
+use crate::modules::chat::server::ChatState;
+use crate::modules::chat::service::WebhookService;
+
+/// Configure outgoing webhook routes
+pub fn webhook_routes(chat_state: ChatState) -> Router<Pool<Postgres>> {
+    Router::new()
+        .route("/api/rooms/:room/webhooks", get(list_webhooks).post(create_webhook))
+        .route("/api/rooms/:room/webhooks/:id", delete(delete_webhook))
+        .route("/api/rooms/:room/webhooks/:id/deliveries", get(list_webhook_deliveries))
+        .with_state(chat_state)
+}
+
+/// Map webhook service errors to HTTP status codes
+fn webhook_error(e: Box<dyn std::error::Error>) -> (StatusCode, String) {
+    let message = e.to_string();
+    if message.contains("Not a member") || message.contains("Only the room") {
+        (StatusCode::FORBIDDEN, message)
+    } else if message.contains("not found") {
+        (StatusCode::NOT_FOUND, message)
+    } else if message.contains("Invalid") || message.contains("at most") {
+        (StatusCode::BAD_REQUEST, message)
+    } else {
+        (StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+/// Register an outgoing webhook for a room
+///
+/// Only the room owner and server admins may manage webhooks. The server POSTs a JSON
+/// event for every message, join and leave in the room, signed with the returned `secret`:
+/// `X-Chat-Signature` is `sha256=` followed by the hex HMAC-SHA256 of
+/// `"{X-Chat-Timestamp}.{body}"`. Failed deliveries are retried with exponential backoff.
+/// Redirects are not followed, and URLs on loopback, private or link-local addresses are
+/// refused unless `CHAT_WEBHOOK_ALLOW_PRIVATE_ADDRESSES` is set.
+///
+/// # Example
+///
+/// ```json
+/// {
+///   "id": "5b7f3c1e-...",
+///   "type": "message",
+///   "room_name": "ops",
+///   "user_id": "1a2b3c4d-...",
+///   "username": "User_1a2b3c4d",
+///   "timestamp": 1234567890123,
+///   "message": { "id": "...", "seq": 42, "message": "deploy failed", "...": "..." }
+/// }
+/// ```
+#[utoipa::path(
+    post,
+    path = "/api/rooms/{room}/webhooks",
+    request_body = CreateWebhookDto,
+    responses(
+        (status = 200, description = "Webhook registered", body = WebhookResponse),
+        (status = 400, description = "Invalid URL or too many webhooks", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not the room owner or an admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Webhooks"
+)]
+pub async fn create_webhook(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateWebhookDto>,
+) -> Result<Json<WebhookResponse>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let webhook_service = WebhookService::new(state.db_pool()?);
+
+    webhook_service
+        .create_webhook(user_id, state.is_admin(user_id), &room, &payload.url)
+        .await
+        .map(Json)
+        .map_err(webhook_error)
+}
+
+/// List the outgoing webhooks of a room
+#[utoipa::path(
+    get,
+    path = "/api/rooms/{room}/webhooks",
+    responses(
+        (status = 200, description = "Webhooks of the room", body = [WebhookResponse]),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not the room owner or an admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Webhooks"
+)]
+pub async fn list_webhooks(
+    State(state): State<ChatState>,
+    Path(room): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<WebhookResponse>>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let webhook_service = WebhookService::new(state.db_pool()?);
+
+    webhook_service
+        .list_webhooks(user_id, state.is_admin(user_id), &room)
+        .await
+        .map(Json)
+        .map_err(webhook_error)
+}
+
+/// Delete an outgoing webhook and its delivery log
+#[utoipa::path(
+    delete,
+    path = "/api/rooms/{room}/webhooks/{id}",
+    responses(
+        (status = 200, description = "Webhook deleted"),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not the room owner or an admin", body = ErrorResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+        ("id" = String, Path, description = "Webhook ID"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Webhooks"
+)]
+pub async fn delete_webhook(
+    State(state): State<ChatState>,
+    Path((room, id)): Path<(String, Uuid)>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let webhook_service = WebhookService::new(state.db_pool()?);
+
+    webhook_service
+        .delete_webhook(user_id, state.is_admin(user_id), &room, id)
+        .await
+        .map_err(webhook_error)?;
+    Ok(Json(json!({ "success": true })))
+}
+
+/// List recent deliveries of a webhook
+///
+/// Returns the latest 100 deliveries, newest first, with the number of attempts and
+/// the outcome of the last one.
+#[utoipa::path(
+    get,
+    path = "/api/rooms/{room}/webhooks/{id}/deliveries",
+    responses(
+        (status = 200, description = "Delivery log", body = [WebhookDeliveryResponse]),
+        (status = 401, description = "Unauthorized - Invalid or missing JWT token", body = ErrorResponse),
+        (status = 403, description = "Not the room owner or an admin", body = ErrorResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+    ),
+    params(
+        ("room" = String, Path, description = "Room name"),
+        ("id" = String, Path, description = "Webhook ID"),
+    ),
+    security(
+        ("Authorization" = [])
+    ),
+    tag = "Webhooks"
+)]
+pub async fn list_webhook_deliveries(
+    State(state): State<ChatState>,
+    Path((room, id)): Path<(String, Uuid)>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<WebhookDeliveryResponse>>, (StatusCode, String)> {
+    let env = Environment::from_env();
+    let user_id = JwtUtil::user_id_from_headers(&headers, &env.auth)?;
+    let webhook_service = WebhookService::new(state.db_pool()?);
+
+    webhook_service
+        .list_deliveries(user_id, state.is_admin(user_id), &room, id)
+        .await
+        .map(Json)
+        .map_err(webhook_error)
+}
@@ -0,0 +1,78 @@
+//! Origin allowlist for browser requests, protecting chat sockets from cross-site hijacking
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use crate::config::env::AppConfig;
+
+/// Which browser origins may talk to the server.
+///
+/// Requests without an `Origin` header come from non-browser clients and are always
+/// allowed; browsers cannot omit the header on cross-origin requests.
+#[derive(Debug, Clone, Default)]
+pub struct OriginPolicy {
+    allowed: Vec<String>,
+    allow_any: bool,
+}
+
+impl OriginPolicy {
+    pub fn new(allowed: Vec<String>) -> Self {
+        let allow_any = allowed.iter().any(|origin| origin == "*");
+        Self { allowed, allow_any }
+    }
+
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::new(config.allowed_origins.clone())
+    }
+
+    /// Whether `origin` is allowed for a request to `host`; the server's own origin always is
+    pub fn is_allowed(&self, origin: &str, host: Option<&str>) -> bool {
+        let origin = origin.trim_end_matches('/').to_lowercase();
+        if self.allow_any || self.allowed.contains(&origin) {
+            return true;
+        }
+        let origin_host = origin.split_once("://").map(|(_, host)| host);
+        matches!((origin_host, host), (Some(origin_host), Some(host)) if origin_host.eq_ignore_ascii_case(host))
+    }
+
+    /// Check the `Origin` header of a request against the policy
+    pub fn allows(&self, headers: &HeaderMap) -> bool {
+        let Some(origin) = headers.get(header::ORIGIN) else {
+            return true;
+        };
+        let host = headers.get(header::HOST).and_then(|host| host.to_str().ok());
+        origin.to_str().is_ok_and(|origin| self.is_allowed(origin, host))
+    }
+}
+
+/// Middleware rejecting requests from origins outside the allowlist
+pub async fn enforce_origin(
+    State(policy): State<Arc<OriginPolicy>>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    if !policy.allows(request.headers()) {
+        return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+    }
+    next.run(request).await
+}
+
+/// CORS for the REST routes, allowing the same origins as [`enforce_origin`]
+pub fn cors_layer(policy: Arc<OriginPolicy>) -> CorsLayer {
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::predicate(move |origin: &HeaderValue, parts| {
+            let host = parts.headers.get(header::HOST).and_then(|host| host.to_str().ok());
+            origin.to_str().is_ok_and(|origin| policy.is_allowed(origin, host))
+        }))
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE])
+        .expose_headers([header::RETRY_AFTER, header::CONTENT_DISPOSITION])
+        .max_age(std::time::Duration::from_secs(60 * 60))
+}